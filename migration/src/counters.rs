@@ -0,0 +1,53 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+/// Table of the last number handed out per key, locked while numbers are
+/// taken so two requests never get the same one (see `utils::counters` in
+/// the app). `keys` form its unique key.
+pub fn counter_table(table: impl IntoIden, keys: Vec<ColumnDef>, number: impl IntoIden) -> TableCreateStatement {
+    let table = table.into_iden();
+
+    let mut unique = Index::create();
+    unique.unique().name(format!("uq_{}_key", table.to_string()));
+
+    let mut create = Table::create();
+    create.table(table).if_not_exists().col(pk_auto(Alias::new("id")));
+    for mut key in keys {
+        unique.col(Alias::new(key.get_column_name()));
+        create.col(&mut key);
+    }
+    create
+        .col(big_integer(number).default(0))
+        .col(timestamp(Alias::new("updated_at")).default(Expr::current_timestamp()))
+        .index(&mut unique)
+        .to_owned()
+}
+
+/// Start the counters of `table` from the highest `used` number of each key
+/// already stored in `from`; keys without one start from 0 on first use
+pub async fn seed_counters(
+    manager: &SchemaManager<'_>,
+    table: impl IntoIden + 'static,
+    keys: &[&str],
+    number: impl IntoIden + 'static,
+    from: impl IntoIden + 'static,
+    used: impl IntoIden + 'static,
+) -> Result<(), DbErr> {
+    let mut select = Query::select();
+    select.from(from);
+    for key in keys {
+        select.column(Alias::new(*key)).group_by_col(Alias::new(*key));
+    }
+    let used = used.into_iden();
+    select.expr(Func::max(Expr::col(used.clone()))).and_where(Expr::col(used).is_not_null());
+
+    let mut columns: Vec<DynIden> = keys.iter().map(|key| Alias::new(*key).into_iden()).collect();
+    columns.push(number.into_iden());
+
+    let seed = Query::insert()
+        .into_table(table)
+        .columns(columns)
+        .select_from(select)
+        .map_err(|e| DbErr::Migration(e.to_string()))?
+        .to_owned();
+    manager.exec_stmt(seed).await
+}
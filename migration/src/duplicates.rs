@@ -0,0 +1,54 @@
+use sea_orm_migration::{
+    prelude::*,
+    sea_orm::{ConnectionTrait, Statement},
+};
+
+/// How many duplicate groups the error lists
+const REPORTED: usize = 20;
+
+/// Fails the migration when rows of `table` share the same non-null values
+/// of `cols`, listing the ids of each duplicate group. Invoices are already
+/// signed under those numbers, so they are not renumbered or removed here;
+/// the operator resolves them and runs the migration again.
+pub async fn refuse_duplicates(
+    manager: &SchemaManager<'_>,
+    table: &str,
+    cols: &[&str],
+    index: &str,
+) -> Result<(), DbErr> {
+    let key = cols.join(", ");
+    let not_null = cols
+        .iter()
+        .map(|col| format!("{col} IS NOT NULL"))
+        .collect::<Vec<_>>()
+        .join(" AND ");
+
+    let db = manager.get_connection();
+    let rows = db
+        .query_all(Statement::from_string(
+            db.get_database_backend(),
+            format!(
+                "SELECT string_agg(id::text, ',' ORDER BY id) AS ids FROM {table} \
+                 WHERE {not_null} GROUP BY {key} HAVING COUNT(*) > 1 ORDER BY MIN(id)"
+            ),
+        ))
+        .await?;
+
+    if rows.is_empty() {
+        return Ok(());
+    }
+
+    let groups = rows
+        .iter()
+        .take(REPORTED)
+        .map(|row| row.try_get::<String>("", "ids").map(|ids| format!("[{ids}]")))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Err(DbErr::Migration(format!(
+        "cannot create {index}: {} group(s) of {table} rows share ({key}); ids {}{}. \
+         Resolve them and run the migration again.",
+        rows.len(),
+        groups.join(" "),
+        if rows.len() > REPORTED { " …" } else { "" },
+    )))
+}
@@ -1,5 +1,8 @@
 pub use sea_orm_migration::prelude::*;
 
+mod counters;
+mod duplicates;
+
 
 
 mod m20260119_134024_branch_customers;
@@ -17,6 +20,29 @@ mod m20260203_051617_make_id_big;
 
 
 mod m20260203_072820_new_stock_master;
+mod m20260204_081522_sales_receipt_signature;
+mod m20260205_093040_credentials_device_info;
+mod m20260206_110215_code_lists;
+mod m20260207_140533_item_classes;
+mod m20260208_091207_stock_master_transmission;
+mod m20260209_102418_stock_movements;
+mod m20260210_084355_branch_transmission;
+mod m20260211_132650_purchases;
+mod m20260212_101733_import_items;
+mod m20260213_083012_notices;
+mod m20260214_094521_branches;
+mod m20260215_110342_item_composition;
+mod m20260216_090114_sales_decimal_amounts;
+mod m20260217_141905_invoice_counters;
+mod m20260218_103247_sales_idempotency;
+mod m20260219_085533_sales_refunded_amt;
+mod m20260220_071544_transmission_claims;
+mod m20260221_093412_sar_counters;
+mod m20260222_101530_sales_component_usage;
+mod m20260223_084210_purchase_counters;
+mod m20260224_091845_branch_local_only;
+mod m20260225_103020_item_composition_decimal_qty;
+mod m20260226_140512_item_composition_use_yn;
 
 
 pub struct Migrator;
@@ -37,6 +63,29 @@ impl MigratorTrait for Migrator {
             Box::new(m20260203_051617_make_id_big::Migration),
          
             Box::new(m20260203_072820_new_stock_master::Migration),
+            Box::new(m20260204_081522_sales_receipt_signature::Migration),
+            Box::new(m20260205_093040_credentials_device_info::Migration),
+            Box::new(m20260206_110215_code_lists::Migration),
+            Box::new(m20260207_140533_item_classes::Migration),
+            Box::new(m20260208_091207_stock_master_transmission::Migration),
+            Box::new(m20260209_102418_stock_movements::Migration),
+            Box::new(m20260210_084355_branch_transmission::Migration),
+            Box::new(m20260211_132650_purchases::Migration),
+            Box::new(m20260212_101733_import_items::Migration),
+            Box::new(m20260213_083012_notices::Migration),
+            Box::new(m20260214_094521_branches::Migration),
+            Box::new(m20260215_110342_item_composition::Migration),
+            Box::new(m20260216_090114_sales_decimal_amounts::Migration),
+            Box::new(m20260217_141905_invoice_counters::Migration),
+            Box::new(m20260218_103247_sales_idempotency::Migration),
+            Box::new(m20260219_085533_sales_refunded_amt::Migration),
+            Box::new(m20260220_071544_transmission_claims::Migration),
+            Box::new(m20260221_093412_sar_counters::Migration),
+            Box::new(m20260222_101530_sales_component_usage::Migration),
+            Box::new(m20260223_084210_purchase_counters::Migration),
+            Box::new(m20260224_091845_branch_local_only::Migration),
+            Box::new(m20260225_103020_item_composition_decimal_qty::Migration),
+            Box::new(m20260226_140512_item_composition_use_yn::Migration),
         ]
     }
 }
@@ -0,0 +1,53 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Sales::Table)
+                    .add_column(big_integer_null(Sales::RcptNo))
+                    .add_column(big_integer_null(Sales::TotRcptNo))
+                    .add_column(string_null(Sales::RcptSign))
+                    .add_column(string_null(Sales::IntrlData))
+                    .add_column(string_null(Sales::SdcId))
+                    .add_column(string_null(Sales::MrcNo))
+                    .add_column(string_null(Sales::RcptPbctDt))
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Sales::Table)
+                    .drop_column(Sales::RcptNo)
+                    .drop_column(Sales::TotRcptNo)
+                    .drop_column(Sales::RcptSign)
+                    .drop_column(Sales::IntrlData)
+                    .drop_column(Sales::SdcId)
+                    .drop_column(Sales::MrcNo)
+                    .drop_column(Sales::RcptPbctDt)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum Sales {
+    Table,
+    RcptNo,
+    TotRcptNo,
+    RcptSign,
+    IntrlData,
+    SdcId,
+    MrcNo,
+    RcptPbctDt,
+}
@@ -0,0 +1,44 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Credentials::Table)
+                    .add_column(string_null(Credentials::TaxprNm))
+                    .add_column(string_null(Credentials::BhfNm))
+                    .add_column(string_null(Credentials::MrcNo))
+                    .add_column(string_null(Credentials::SdcId))
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Credentials::Table)
+                    .drop_column(Credentials::TaxprNm)
+                    .drop_column(Credentials::BhfNm)
+                    .drop_column(Credentials::MrcNo)
+                    .drop_column(Credentials::SdcId)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum Credentials {
+    Table,
+    TaxprNm,
+    BhfNm,
+    MrcNo,
+    SdcId,
+}
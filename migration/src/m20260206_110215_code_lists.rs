@@ -0,0 +1,128 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // lastReqDt bookkeeping for every VSCU "select" sync, per device
+        manager
+            .create_table(
+                Table::create()
+                    .table(SyncState::Table)
+                    .if_not_exists()
+                    .col(pk_auto(SyncState::Id))
+                    .col(integer(SyncState::CredentialsId))
+                    .col(string(SyncState::Resource))
+                    .col(string(SyncState::LastReqDt))
+                    .col(timestamp(SyncState::UpdatedAt).default(Expr::current_timestamp()))
+                    .index(
+                        Index::create()
+                            .unique()
+                            .name("uq_sync_state_device_resource")
+                            .col(SyncState::CredentialsId)
+                            .col(SyncState::Resource),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_table(
+                Table::create()
+                    .table(CodeClass::Table)
+                    .if_not_exists()
+                    .col(pk_auto(CodeClass::Id))
+                    .col(string(CodeClass::CdCls).unique_key())
+                    .col(string(CodeClass::CdClsNm))
+                    .col(string_null(CodeClass::CdClsDesc))
+                    .col(string(CodeClass::UseYn))
+                    .col(string_null(CodeClass::UserDfnNm1))
+                    .col(string_null(CodeClass::UserDfnNm2))
+                    .col(string_null(CodeClass::UserDfnNm3))
+                    .col(timestamp(CodeClass::UpdatedAt).default(Expr::current_timestamp()))
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_table(
+                Table::create()
+                    .table(CodeDetail::Table)
+                    .if_not_exists()
+                    .col(pk_auto(CodeDetail::Id))
+                    .col(string(CodeDetail::CdCls))
+                    .col(string(CodeDetail::Cd))
+                    .col(string(CodeDetail::CdNm))
+                    .col(string_null(CodeDetail::CdDesc))
+                    .col(string(CodeDetail::UseYn))
+                    .col(integer_null(CodeDetail::SrtOrd))
+                    .col(string_null(CodeDetail::UserDfnCd1))
+                    .col(string_null(CodeDetail::UserDfnCd2))
+                    .col(string_null(CodeDetail::UserDfnCd3))
+                    .col(timestamp(CodeDetail::UpdatedAt).default(Expr::current_timestamp()))
+                    .index(
+                        Index::create()
+                            .unique()
+                            .name("uq_code_detail_cls_cd")
+                            .col(CodeDetail::CdCls)
+                            .col(CodeDetail::Cd),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(CodeDetail::Table).to_owned())
+            .await?;
+        manager
+            .drop_table(Table::drop().table(CodeClass::Table).to_owned())
+            .await?;
+        manager
+            .drop_table(Table::drop().table(SyncState::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum SyncState {
+    Table,
+    Id,
+    CredentialsId,
+    Resource,
+    LastReqDt,
+    UpdatedAt,
+}
+
+#[derive(DeriveIden)]
+enum CodeClass {
+    Table,
+    Id,
+    CdCls,
+    CdClsNm,
+    CdClsDesc,
+    UseYn,
+    UserDfnNm1,
+    UserDfnNm2,
+    UserDfnNm3,
+    UpdatedAt,
+}
+
+#[derive(DeriveIden)]
+enum CodeDetail {
+    Table,
+    Id,
+    CdCls,
+    Cd,
+    CdNm,
+    CdDesc,
+    UseYn,
+    SrtOrd,
+    UserDfnCd1,
+    UserDfnCd2,
+    UserDfnCd3,
+    UpdatedAt,
+}
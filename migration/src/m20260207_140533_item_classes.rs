@@ -0,0 +1,55 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(ItemClass::Table)
+                    .if_not_exists()
+                    .col(pk_auto(ItemClass::Id))
+                    .col(string(ItemClass::ItemClsCd).unique_key())
+                    .col(string(ItemClass::ItemClsNm))
+                    .col(integer(ItemClass::ItemClsLvl))
+                    .col(string_null(ItemClass::TaxTyCd))
+                    .col(string_null(ItemClass::MjrTgYn))
+                    .col(string(ItemClass::UseYn))
+                    .col(timestamp(ItemClass::UpdatedAt).default(Expr::current_timestamp()))
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_item_class_lvl")
+                    .table(ItemClass::Table)
+                    .col(ItemClass::ItemClsLvl)
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(ItemClass::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum ItemClass {
+    Table,
+    Id,
+    ItemClsCd,
+    ItemClsNm,
+    ItemClsLvl,
+    TaxTyCd,
+    MjrTgYn,
+    UseYn,
+    UpdatedAt,
+}
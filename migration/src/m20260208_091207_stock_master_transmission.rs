@@ -0,0 +1,44 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(StockMaster::Table)
+                    .add_column(string(StockMaster::Status).default("RECEIVED"))
+                    .add_column(json_binary_null(StockMaster::Response))
+                    .add_column(big_integer(StockMaster::RetryCount).default(0))
+                    .add_column(string_null(StockMaster::NextRetryAt))
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(StockMaster::Table)
+                    .drop_column(StockMaster::Status)
+                    .drop_column(StockMaster::Response)
+                    .drop_column(StockMaster::RetryCount)
+                    .drop_column(StockMaster::NextRetryAt)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum StockMaster {
+    Table,
+    Status,
+    Response,
+    RetryCount,
+    NextRetryAt,
+}
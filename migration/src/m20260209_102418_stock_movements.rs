@@ -0,0 +1,151 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // Stock in/out header, sar_no is allocated per branch
+        manager
+            .create_table(
+                Table::create()
+                    .table(StockMovement::Table)
+                    .if_not_exists()
+                    .col(pk_auto(StockMovement::Id))
+                    .col(string(StockMovement::Tin))
+                    .col(string(StockMovement::BhfId))
+                    .col(big_integer(StockMovement::SarNo))
+                    .col(big_integer(StockMovement::OrgSarNo).default(0))
+                    .col(string(StockMovement::RegTyCd))
+                    .col(string_null(StockMovement::CustTin))
+                    .col(string_null(StockMovement::CustNm))
+                    .col(string_null(StockMovement::CustBhfId))
+                    .col(string(StockMovement::SarTyCd))
+                    .col(string(StockMovement::OcrnDt))
+                    .col(integer(StockMovement::TotItemCnt))
+                    .col(double(StockMovement::TotTaxblAmt))
+                    .col(double(StockMovement::TotTaxAmt))
+                    .col(double(StockMovement::TotAmt))
+                    .col(string_null(StockMovement::Remark))
+                    .col(string(StockMovement::RegrNm))
+                    .col(string(StockMovement::RegrId))
+                    .col(string(StockMovement::ModrNm))
+                    .col(string(StockMovement::ModrId))
+                    .col(string(StockMovement::Status).default("RECEIVED"))
+                    .col(json_binary_null(StockMovement::Response))
+                    .col(big_integer(StockMovement::RetryCount).default(0))
+                    .col(string_null(StockMovement::NextRetryAt))
+                    .col(timestamp(StockMovement::CreatedAt).default(Expr::current_timestamp()))
+                    .index(
+                        Index::create()
+                            .unique()
+                            .name("uq_stock_movement_branch_sar_no")
+                            .col(StockMovement::Tin)
+                            .col(StockMovement::BhfId)
+                            .col(StockMovement::SarNo),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_table(
+                Table::create()
+                    .table(StockMovementItem::Table)
+                    .if_not_exists()
+                    .col(pk_auto(StockMovementItem::Id))
+                    .col(integer(StockMovementItem::MovementId))
+                    .col(integer(StockMovementItem::ItemSeq))
+                    .col(string(StockMovementItem::ItemCd))
+                    .col(string(StockMovementItem::ItemClsCd))
+                    .col(string(StockMovementItem::ItemNm))
+                    .col(string_null(StockMovementItem::Bcd))
+                    .col(string(StockMovementItem::PkgUnitCd))
+                    .col(double(StockMovementItem::Pkg))
+                    .col(string(StockMovementItem::QtyUnitCd))
+                    .col(double(StockMovementItem::Qty))
+                    .col(string_null(StockMovementItem::ItemExprDt))
+                    .col(double(StockMovementItem::Prc))
+                    .col(double(StockMovementItem::SplyAmt))
+                    .col(double(StockMovementItem::TotDcAmt))
+                    .col(double(StockMovementItem::TaxblAmt))
+                    .col(string(StockMovementItem::TaxTyCd))
+                    .col(double(StockMovementItem::TaxAmt))
+                    .col(double(StockMovementItem::TotAmt))
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_stock_movement_item_movement")
+                            .from(StockMovementItem::Table, StockMovementItem::MovementId)
+                            .to(StockMovement::Table, StockMovement::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(StockMovementItem::Table).to_owned())
+            .await?;
+
+        manager
+            .drop_table(Table::drop().table(StockMovement::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum StockMovement {
+    Table,
+    Id,
+    Tin,
+    BhfId,
+    SarNo,
+    OrgSarNo,
+    RegTyCd,
+    CustTin,
+    CustNm,
+    CustBhfId,
+    SarTyCd,
+    OcrnDt,
+    TotItemCnt,
+    TotTaxblAmt,
+    TotTaxAmt,
+    TotAmt,
+    Remark,
+    RegrNm,
+    RegrId,
+    ModrNm,
+    ModrId,
+    Status,
+    Response,
+    RetryCount,
+    NextRetryAt,
+    CreatedAt,
+}
+
+#[derive(DeriveIden)]
+enum StockMovementItem {
+    Table,
+    Id,
+    MovementId,
+    ItemSeq,
+    ItemCd,
+    ItemClsCd,
+    ItemNm,
+    Bcd,
+    PkgUnitCd,
+    Pkg,
+    QtyUnitCd,
+    Qty,
+    ItemExprDt,
+    Prc,
+    SplyAmt,
+    TotDcAmt,
+    TaxblAmt,
+    TaxTyCd,
+    TaxAmt,
+    TotAmt,
+}
@@ -0,0 +1,71 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // Same transmission bookkeeping as sales; api_key tells the retry
+        // worker which device the row was stored through
+        for table in [BhfCustomer::Table.into_iden(), BhfUsers::Table.into_iden(), BhfInsurance::Table.into_iden()] {
+            manager
+                .alter_table(
+                    Table::alter()
+                        .table(table)
+                        .add_column(string_null(Transmission::ApiKey))
+                        .add_column(string(Transmission::Status).default("RECEIVED"))
+                        .add_column(json_binary_null(Transmission::Response))
+                        .add_column(big_integer(Transmission::RetryCount).default(0))
+                        .add_column(string_null(Transmission::NextRetryAt))
+                        .to_owned(),
+                )
+                .await?;
+        }
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        for table in [BhfCustomer::Table.into_iden(), BhfUsers::Table.into_iden(), BhfInsurance::Table.into_iden()] {
+            manager
+                .alter_table(
+                    Table::alter()
+                        .table(table)
+                        .drop_column(Transmission::ApiKey)
+                        .drop_column(Transmission::Status)
+                        .drop_column(Transmission::Response)
+                        .drop_column(Transmission::RetryCount)
+                        .drop_column(Transmission::NextRetryAt)
+                        .to_owned(),
+                )
+                .await?;
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(DeriveIden)]
+enum BhfCustomer {
+    Table,
+}
+
+#[derive(DeriveIden)]
+enum BhfUsers {
+    Table,
+}
+
+#[derive(DeriveIden)]
+enum BhfInsurance {
+    Table,
+}
+
+#[derive(DeriveIden)]
+enum Transmission {
+    ApiKey,
+    Status,
+    Response,
+    RetryCount,
+    NextRetryAt,
+}
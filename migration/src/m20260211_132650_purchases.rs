@@ -0,0 +1,176 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // Supplier sales pulled from trnsPurchase/selectTrnsPurchaseSales and
+        // our accept/reject decision sent back with trnsPurchase/savePurchases
+        manager
+            .create_table(
+                Table::create()
+                    .table(Purchase::Table)
+                    .if_not_exists()
+                    .col(pk_auto(Purchase::Id))
+                    .col(integer(Purchase::CredentialsId))
+                    .col(string(Purchase::SpplrTin))
+                    .col(string(Purchase::SpplrNm))
+                    .col(string(Purchase::SpplrBhfId))
+                    .col(big_integer(Purchase::SpplrInvcNo))
+                    .col(string(Purchase::RcptTyCd))
+                    .col(string_null(Purchase::PmtTyCd))
+                    .col(string(Purchase::CfmDt))
+                    .col(string(Purchase::SalesDt))
+                    .col(integer(Purchase::TotItemCnt))
+                    .col(double(Purchase::TotTaxblAmt))
+                    .col(double(Purchase::TotTaxAmt))
+                    .col(double(Purchase::TotAmt))
+                    .col(json_binary(Purchase::SupplierSale))
+                    .col(string(Purchase::PchsSttsCd).default("01"))
+                    .col(big_integer_null(Purchase::InvcNo))
+                    .col(string_null(Purchase::PchsTyCd))
+                    .col(string_null(Purchase::PchsDt))
+                    .col(string_null(Purchase::Remark))
+                    .col(string_null(Purchase::RegrNm))
+                    .col(string_null(Purchase::RegrId))
+                    .col(integer_null(Purchase::StockMovementId))
+                    .col(string(Purchase::Status).default("PENDING"))
+                    .col(json_binary_null(Purchase::Response))
+                    .col(big_integer(Purchase::RetryCount).default(0))
+                    .col(string_null(Purchase::NextRetryAt))
+                    .col(timestamp(Purchase::CreatedAt).default(Expr::current_timestamp()))
+                    .col(timestamp_null(Purchase::UpdatedAt))
+                    .index(
+                        Index::create()
+                            .unique()
+                            .name("uq_purchase_supplier_invoice")
+                            .col(Purchase::CredentialsId)
+                            .col(Purchase::SpplrTin)
+                            .col(Purchase::SpplrBhfId)
+                            .col(Purchase::SpplrInvcNo),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        // invc_no is only allocated once a decision is made
+        manager
+            .create_index(
+                Index::create()
+                    .unique()
+                    .name("uq_purchase_device_invc_no")
+                    .table(Purchase::Table)
+                    .col(Purchase::CredentialsId)
+                    .col(Purchase::InvcNo)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_table(
+                Table::create()
+                    .table(PurchaseItem::Table)
+                    .if_not_exists()
+                    .col(pk_auto(PurchaseItem::Id))
+                    .col(integer(PurchaseItem::PurchaseId))
+                    .col(integer(PurchaseItem::ItemSeq))
+                    .col(string_null(PurchaseItem::SpplrItemCd))
+                    .col(string_null(PurchaseItem::ItemCd))
+                    .col(string(PurchaseItem::ItemClsCd))
+                    .col(string(PurchaseItem::ItemNm))
+                    .col(string_null(PurchaseItem::Bcd))
+                    .col(string(PurchaseItem::PkgUnitCd))
+                    .col(double(PurchaseItem::Pkg))
+                    .col(string(PurchaseItem::QtyUnitCd))
+                    .col(double(PurchaseItem::Qty))
+                    .col(double(PurchaseItem::Prc))
+                    .col(double(PurchaseItem::SplyAmt))
+                    .col(double(PurchaseItem::DcRt))
+                    .col(double(PurchaseItem::DcAmt))
+                    .col(string(PurchaseItem::TaxTyCd))
+                    .col(double(PurchaseItem::TaxblAmt))
+                    .col(double(PurchaseItem::TaxAmt))
+                    .col(double(PurchaseItem::TotAmt))
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_purchase_item_purchase")
+                            .from(PurchaseItem::Table, PurchaseItem::PurchaseId)
+                            .to(Purchase::Table, Purchase::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(PurchaseItem::Table).to_owned())
+            .await?;
+
+        manager
+            .drop_table(Table::drop().table(Purchase::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum Purchase {
+    Table,
+    Id,
+    CredentialsId,
+    SpplrTin,
+    SpplrNm,
+    SpplrBhfId,
+    SpplrInvcNo,
+    RcptTyCd,
+    PmtTyCd,
+    CfmDt,
+    SalesDt,
+    TotItemCnt,
+    TotTaxblAmt,
+    TotTaxAmt,
+    TotAmt,
+    SupplierSale,
+    PchsSttsCd,
+    InvcNo,
+    PchsTyCd,
+    PchsDt,
+    Remark,
+    RegrNm,
+    RegrId,
+    StockMovementId,
+    Status,
+    Response,
+    RetryCount,
+    NextRetryAt,
+    CreatedAt,
+    UpdatedAt,
+}
+
+#[derive(DeriveIden)]
+enum PurchaseItem {
+    Table,
+    Id,
+    PurchaseId,
+    ItemSeq,
+    SpplrItemCd,
+    ItemCd,
+    ItemClsCd,
+    ItemNm,
+    Bcd,
+    PkgUnitCd,
+    Pkg,
+    QtyUnitCd,
+    Qty,
+    Prc,
+    SplyAmt,
+    DcRt,
+    DcAmt,
+    TaxTyCd,
+    TaxblAmt,
+    TaxAmt,
+    TotAmt,
+}
@@ -0,0 +1,106 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // Declared import lines from imports/selectImportItems and our
+        // approve/reject decision sent back with imports/updateImportItems
+        manager
+            .create_table(
+                Table::create()
+                    .table(ImportItem::Table)
+                    .if_not_exists()
+                    .col(pk_auto(ImportItem::Id))
+                    .col(integer(ImportItem::CredentialsId))
+                    .col(string(ImportItem::TaskCd))
+                    .col(string(ImportItem::DclDe))
+                    .col(integer(ImportItem::ItemSeq))
+                    .col(string(ImportItem::DclNo))
+                    .col(string(ImportItem::HsCd))
+                    .col(string(ImportItem::ItemNm))
+                    .col(string_null(ImportItem::OrgnNatCd))
+                    .col(string_null(ImportItem::ExptNatCd))
+                    .col(double(ImportItem::Pkg))
+                    .col(string_null(ImportItem::PkgUnitCd))
+                    .col(double(ImportItem::Qty))
+                    .col(string_null(ImportItem::QtyUnitCd))
+                    .col(double(ImportItem::TotWt))
+                    .col(double(ImportItem::NetWt))
+                    .col(string_null(ImportItem::SpplrNm))
+                    .col(string_null(ImportItem::AgntNm))
+                    .col(double(ImportItem::InvcFcurAmt))
+                    .col(string_null(ImportItem::InvcFcurCd))
+                    .col(double(ImportItem::InvcFcurExcrt))
+                    .col(string(ImportItem::ImptItemSttsCd).default("2"))
+                    .col(string_null(ImportItem::ItemCd))
+                    .col(string_null(ImportItem::ItemClsCd))
+                    .col(string_null(ImportItem::Remark))
+                    .col(string_null(ImportItem::ModrNm))
+                    .col(string_null(ImportItem::ModrId))
+                    .col(string(ImportItem::Status).default("PENDING"))
+                    .col(json_binary_null(ImportItem::Response))
+                    .col(big_integer(ImportItem::RetryCount).default(0))
+                    .col(string_null(ImportItem::NextRetryAt))
+                    .col(timestamp(ImportItem::CreatedAt).default(Expr::current_timestamp()))
+                    .col(timestamp_null(ImportItem::UpdatedAt))
+                    .index(
+                        Index::create()
+                            .unique()
+                            .name("uq_import_item_declaration_line")
+                            .col(ImportItem::CredentialsId)
+                            .col(ImportItem::TaskCd)
+                            .col(ImportItem::DclDe)
+                            .col(ImportItem::ItemSeq),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(ImportItem::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum ImportItem {
+    Table,
+    Id,
+    CredentialsId,
+    TaskCd,
+    DclDe,
+    ItemSeq,
+    DclNo,
+    HsCd,
+    ItemNm,
+    OrgnNatCd,
+    ExptNatCd,
+    Pkg,
+    PkgUnitCd,
+    Qty,
+    QtyUnitCd,
+    TotWt,
+    NetWt,
+    SpplrNm,
+    AgntNm,
+    InvcFcurAmt,
+    InvcFcurCd,
+    InvcFcurExcrt,
+    ImptItemSttsCd,
+    ItemCd,
+    ItemClsCd,
+    Remark,
+    ModrNm,
+    ModrId,
+    Status,
+    Response,
+    RetryCount,
+    NextRetryAt,
+    CreatedAt,
+    UpdatedAt,
+}
@@ -0,0 +1,108 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // KRA notices from notices/selectNotices, per device
+        manager
+            .create_table(
+                Table::create()
+                    .table(Notices::Table)
+                    .if_not_exists()
+                    .col(pk_auto(Notices::Id))
+                    .col(integer(Notices::CredentialsId))
+                    .col(big_integer(Notices::NtcNo))
+                    .col(string(Notices::Title))
+                    .col(text(Notices::Cont))
+                    .col(string_null(Notices::DtlUrl))
+                    .col(string_null(Notices::RegrNm))
+                    .col(string(Notices::RegDt))
+                    .col(timestamp(Notices::CreatedAt).default(Expr::current_timestamp()))
+                    .index(
+                        Index::create()
+                            .unique()
+                            .name("uq_notices_device_ntc_no")
+                            .col(Notices::CredentialsId)
+                            .col(Notices::NtcNo),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        // Which dashboard user has read which notice
+        manager
+            .create_table(
+                Table::create()
+                    .table(NoticeReads::Table)
+                    .if_not_exists()
+                    .col(pk_auto(NoticeReads::Id))
+                    .col(integer(NoticeReads::NoticeId))
+                    .col(integer(NoticeReads::UserId))
+                    .col(timestamp(NoticeReads::ReadAt).default(Expr::current_timestamp()))
+                    .index(
+                        Index::create()
+                            .unique()
+                            .name("uq_notice_reads_notice_user")
+                            .col(NoticeReads::NoticeId)
+                            .col(NoticeReads::UserId),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_notice_reads_notice")
+                            .from(NoticeReads::Table, NoticeReads::NoticeId)
+                            .to(Notices::Table, Notices::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_notice_reads_user")
+                            .from(NoticeReads::Table, NoticeReads::UserId)
+                            .to(User::Table, User::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(NoticeReads::Table).to_owned())
+            .await?;
+        manager
+            .drop_table(Table::drop().table(Notices::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum Notices {
+    Table,
+    Id,
+    CredentialsId,
+    NtcNo,
+    Title,
+    Cont,
+    DtlUrl,
+    RegrNm,
+    RegDt,
+    CreatedAt,
+}
+
+#[derive(DeriveIden)]
+enum NoticeReads {
+    Table,
+    Id,
+    NoticeId,
+    UserId,
+    ReadAt,
+}
+
+#[derive(DeriveIden)]
+enum User {
+    Table,
+    Id,
+}
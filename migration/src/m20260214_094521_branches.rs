@@ -0,0 +1,66 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // Every branch KRA has registered under a PIN, from branches/selectBranches.
+        // tin is stored encrypted the same way as credentials.pin.
+        manager
+            .create_table(
+                Table::create()
+                    .table(Branches::Table)
+                    .if_not_exists()
+                    .col(pk_auto(Branches::Id))
+                    .col(string(Branches::Tin))
+                    .col(string(Branches::BhfId))
+                    .col(string(Branches::BhfNm))
+                    .col(string(Branches::BhfSttsCd))
+                    .col(string_null(Branches::PrvncNm))
+                    .col(string_null(Branches::DstrtNm))
+                    .col(string_null(Branches::SctrNm))
+                    .col(string_null(Branches::LocDesc))
+                    .col(string_null(Branches::MgrNm))
+                    .col(string_null(Branches::MgrTelNo))
+                    .col(string_null(Branches::MgrEmail))
+                    .col(string(Branches::HqYn))
+                    .col(timestamp(Branches::UpdatedAt).default(Expr::current_timestamp()))
+                    .index(
+                        Index::create()
+                            .unique()
+                            .name("uq_branches_tin_bhf_id")
+                            .col(Branches::Tin)
+                            .col(Branches::BhfId),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(Branches::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum Branches {
+    Table,
+    Id,
+    Tin,
+    BhfId,
+    BhfNm,
+    BhfSttsCd,
+    PrvncNm,
+    DstrtNm,
+    SctrNm,
+    LocDesc,
+    MgrNm,
+    MgrTelNo,
+    MgrEmail,
+    HqYn,
+    UpdatedAt,
+}
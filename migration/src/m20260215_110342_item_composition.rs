@@ -0,0 +1,68 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // Components of a composite item_master item (items/saveItemComposition).
+        // tin/bhf_id are stored encrypted like item_master.
+        manager
+            .create_table(
+                Table::create()
+                    .table(ItemComposition::Table)
+                    .if_not_exists()
+                    .col(pk_auto(ItemComposition::Id))
+                    .col(string(ItemComposition::Tin))
+                    .col(string(ItemComposition::BhfId))
+                    .col(string(ItemComposition::ItemCd))
+                    .col(string(ItemComposition::CpstItemCd))
+                    .col(double(ItemComposition::CpstQty))
+                    .col(string(ItemComposition::RegrNm))
+                    .col(string(ItemComposition::RegrId))
+                    .col(string(ItemComposition::Status).default("RECEIVED"))
+                    .col(json_binary_null(ItemComposition::Response))
+                    .col(big_integer(ItemComposition::RetryCount).default(0))
+                    .col(string_null(ItemComposition::NextRetryAt))
+                    .col(timestamp(ItemComposition::CreatedAt).default(Expr::current_timestamp()))
+                    .col(timestamp_null(ItemComposition::UpdatedAt))
+                    .index(
+                        Index::create()
+                            .unique()
+                            .name("uq_item_composition_component")
+                            .col(ItemComposition::Tin)
+                            .col(ItemComposition::BhfId)
+                            .col(ItemComposition::ItemCd)
+                            .col(ItemComposition::CpstItemCd),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(ItemComposition::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum ItemComposition {
+    Table,
+    Id,
+    Tin,
+    BhfId,
+    ItemCd,
+    CpstItemCd,
+    CpstQty,
+    RegrNm,
+    RegrId,
+    Status,
+    Response,
+    RetryCount,
+    NextRetryAt,
+    CreatedAt,
+    UpdatedAt,
+}
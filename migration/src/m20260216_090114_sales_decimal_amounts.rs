@@ -0,0 +1,77 @@
+use sea_orm_migration::prelude::*;
+
+/// Amounts and tax rates of `sales` as NUMERIC instead of double precision.
+/// Existing values are rounded to cents by the cast.
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+const COLUMNS: [Sales; 18] = [
+    Sales::TaxblAmtA,
+    Sales::TaxblAmtB,
+    Sales::TaxblAmtC,
+    Sales::TaxblAmtD,
+    Sales::TaxblAmtE,
+    Sales::TaxAmtA,
+    Sales::TaxAmtB,
+    Sales::TaxAmtC,
+    Sales::TaxAmtD,
+    Sales::TaxAmtE,
+    Sales::TotTaxblAmt,
+    Sales::TotTaxAmt,
+    Sales::TotAmt,
+    Sales::TaxRtA,
+    Sales::TaxRtB,
+    Sales::TaxRtC,
+    Sales::TaxRtD,
+    Sales::TaxRtE,
+];
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let mut alter = Table::alter();
+        alter.table(Sales::Table);
+        for col in COLUMNS {
+            alter.modify_column(ColumnDef::new(col).decimal_len(18, 2));
+        }
+
+        manager.alter_table(alter.to_owned()).await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let mut alter = Table::alter();
+        alter.table(Sales::Table);
+        for col in COLUMNS {
+            alter.modify_column(ColumnDef::new(col).double());
+        }
+
+        manager.alter_table(alter.to_owned()).await
+    }
+}
+
+#[derive(DeriveIden, Clone, Copy)]
+enum Sales {
+    Table,
+
+    TaxblAmtA,
+    TaxblAmtB,
+    TaxblAmtC,
+    TaxblAmtD,
+    TaxblAmtE,
+
+    TaxRtA,
+    TaxRtB,
+    TaxRtC,
+    TaxRtD,
+    TaxRtE,
+
+    TaxAmtA,
+    TaxAmtB,
+    TaxAmtC,
+    TaxAmtD,
+    TaxAmtE,
+
+    TotTaxblAmt,
+    TotTaxAmt,
+    TotAmt,
+}
@@ -0,0 +1,74 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+use crate::{
+    counters::{counter_table, seed_counters},
+    duplicates,
+};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // Last invoice number handed out per device (api key). The row is
+        // locked while a sale is inserted so numbers are never reused.
+        manager
+            .create_table(counter_table(
+                InvoiceCounters::Table,
+                vec![string(InvoiceCounters::ApiKey)],
+                InvoiceCounters::LastInvcNo,
+            ))
+            .await?;
+
+        // Continue from the numbers already used
+        seed_counters(manager, InvoiceCounters::Table, &["api_key"], InvoiceCounters::LastInvcNo, Sales::Table, Sales::GeneratedInvcNo)
+            .await?;
+
+        // Two sales of one device signed under the same number can't be fixed
+        // here; report them instead of letting the index fail on its own
+        duplicates::refuse_duplicates(manager, "sales", &["api_key", "invc_no"], "uq_sales_api_key_invc_no").await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .unique()
+                    .name("uq_sales_api_key_invc_no")
+                    .table(Sales::Table)
+                    .col(Sales::ApiKey)
+                    .col(Sales::InvcNo)
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_index(
+                Index::drop()
+                    .name("uq_sales_api_key_invc_no")
+                    .table(Sales::Table)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .drop_table(Table::drop().table(InvoiceCounters::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum InvoiceCounters {
+    Table,
+    ApiKey,
+    LastInvcNo,
+}
+
+#[derive(DeriveIden)]
+enum Sales {
+    Table,
+    ApiKey,
+    GeneratedInvcNo,
+    InvcNo,
+}
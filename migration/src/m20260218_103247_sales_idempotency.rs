@@ -0,0 +1,87 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+use crate::duplicates;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // Idempotency-Key of a /sales request with the answer it got, so a
+        // resubmission replays the answer instead of creating new invoices
+        manager
+            .create_table(
+                Table::create()
+                    .table(IdempotencyKeys::Table)
+                    .if_not_exists()
+                    .col(pk_auto(IdempotencyKeys::Id))
+                    .col(string(IdempotencyKeys::ApiKey))
+                    .col(string(IdempotencyKeys::IdemKey))
+                    .col(string(IdempotencyKeys::RequestHash))
+                    .col(integer_null(IdempotencyKeys::StatusCode))
+                    .col(json_binary_null(IdempotencyKeys::Response))
+                    .col(timestamp(IdempotencyKeys::CreatedAt).default(Expr::current_timestamp()))
+                    .index(
+                        Index::create()
+                            .unique()
+                            .name("uq_idempotency_keys_api_key_idem_key")
+                            .col(IdempotencyKeys::ApiKey)
+                            .col(IdempotencyKeys::IdemKey),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        // One invoice per POS invoice number; NULL trdInvcNo is not deduplicated.
+        // Resubmissions stored before this already have invoices of their own.
+        duplicates::refuse_duplicates(manager, "sales", &["api_key", "trd_invc_no"], "uq_sales_api_key_trd_invc_no")
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .unique()
+                    .name("uq_sales_api_key_trd_invc_no")
+                    .table(Sales::Table)
+                    .col(Sales::ApiKey)
+                    .col(Sales::TrdInvcNo)
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_index(
+                Index::drop()
+                    .name("uq_sales_api_key_trd_invc_no")
+                    .table(Sales::Table)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .drop_table(Table::drop().table(IdempotencyKeys::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum IdempotencyKeys {
+    Table,
+    Id,
+    ApiKey,
+    IdemKey,
+    RequestHash,
+    StatusCode,
+    Response,
+    CreatedAt,
+}
+
+#[derive(DeriveIden)]
+enum Sales {
+    Table,
+    ApiKey,
+    TrdInvcNo,
+}
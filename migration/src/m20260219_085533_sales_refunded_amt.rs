@@ -0,0 +1,36 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // Total of the credit notes issued against a sale that KRA has not rejected
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Sales::Table)
+                    .add_column(decimal_len(Sales::RefundedAmt, 18, 2).default(0))
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Sales::Table)
+                    .drop_column(Sales::RefundedAmt)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum Sales {
+    Table,
+    RefundedAmt,
+}
@@ -0,0 +1,57 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+/// Every table a sender marks PROCESSING while its VSCU call is in flight
+const TABLES: [&str; 9] = [
+    "sales",
+    "stock_master",
+    "stock_movement",
+    "bhf_customer",
+    "bhf_users",
+    "bhf_insurance",
+    "purchase",
+    "import_item",
+    "item_composition",
+];
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // When the row was last set to PROCESSING, so the retry worker only
+        // takes over a send that has been silent for longer than the lease
+        for table in TABLES {
+            manager
+                .alter_table(
+                    Table::alter()
+                        .table(Alias::new(table))
+                        .add_column(timestamp_null(Transmission::ClaimedAt))
+                        .to_owned(),
+                )
+                .await?;
+        }
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        for table in TABLES {
+            manager
+                .alter_table(
+                    Table::alter()
+                        .table(Alias::new(table))
+                        .drop_column(Transmission::ClaimedAt)
+                        .to_owned(),
+                )
+                .await?;
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(DeriveIden)]
+enum Transmission {
+    ClaimedAt,
+}
@@ -0,0 +1,45 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+use crate::counters::{counter_table, seed_counters};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // Last sarNo handed out per branch. The row is locked while a stock
+        // movement is inserted so two movements never get the same number.
+        manager
+            .create_table(counter_table(
+                SarCounters::Table,
+                vec![string(SarCounters::Tin), string(SarCounters::BhfId)],
+                SarCounters::LastSarNo,
+            ))
+            .await?;
+
+        // Continue from the numbers already used
+        seed_counters(manager, SarCounters::Table, &["tin", "bhf_id"], SarCounters::LastSarNo, StockMovement::Table, StockMovement::SarNo)
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(SarCounters::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum SarCounters {
+    Table,
+    Tin,
+    BhfId,
+    LastSarNo,
+}
+
+#[derive(DeriveIden)]
+enum StockMovement {
+    Table,
+    SarNo,
+}
@@ -0,0 +1,37 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // Components the sale's automatic stock out took, per composite item,
+        // so a credit note gives back what was taken and not today's recipe
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Sales::Table)
+                    .add_column(json_binary_null(Sales::ComponentUsage))
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Sales::Table)
+                    .drop_column(Sales::ComponentUsage)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum Sales {
+    Table,
+    ComponentUsage,
+}
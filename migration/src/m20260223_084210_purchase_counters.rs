@@ -0,0 +1,44 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+use crate::counters::{counter_table, seed_counters};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // Last purchase invoice number handed out per device, locked while a
+        // purchase is approved or cancelled
+        manager
+            .create_table(counter_table(
+                PurchaseCounters::Table,
+                vec![integer(PurchaseCounters::CredentialsId)],
+                PurchaseCounters::LastInvcNo,
+            ))
+            .await?;
+
+        // Continue from the numbers already used
+        seed_counters(manager, PurchaseCounters::Table, &["credentials_id"], PurchaseCounters::LastInvcNo, Purchase::Table, Purchase::InvcNo)
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(PurchaseCounters::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum PurchaseCounters {
+    Table,
+    CredentialsId,
+    LastInvcNo,
+}
+
+#[derive(DeriveIden)]
+enum Purchase {
+    Table,
+    InvcNo,
+}
@@ -0,0 +1,43 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // Records posted without a bearer token were stored RECEIVED although
+        // nothing can ever send them; mark them LOCAL_ONLY
+        for table in [BhfTable::BhfCustomer, BhfTable::BhfUsers, BhfTable::BhfInsurance] {
+            let update = Query::update()
+                .table(table)
+                .value(BhfTable::Status, "LOCAL_ONLY")
+                .and_where(Expr::col(BhfTable::ApiKey).is_null())
+                .and_where(Expr::col(BhfTable::Status).eq("RECEIVED"))
+                .to_owned();
+            manager.exec_stmt(update).await?;
+        }
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        for table in [BhfTable::BhfCustomer, BhfTable::BhfUsers, BhfTable::BhfInsurance] {
+            let update = Query::update()
+                .table(table)
+                .value(BhfTable::Status, "RECEIVED")
+                .and_where(Expr::col(BhfTable::Status).eq("LOCAL_ONLY"))
+                .to_owned();
+            manager.exec_stmt(update).await?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(DeriveIden)]
+enum BhfTable {
+    BhfCustomer,
+    BhfUsers,
+    BhfInsurance,
+    Status,
+    ApiKey,
+}
@@ -0,0 +1,36 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // cpstQty is NUMBER(13,2) at KRA; a double could not be compared exactly
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(ItemComposition::Table)
+                    .modify_column(ColumnDef::new(ItemComposition::CpstQty).decimal_len(13, 2).not_null())
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(ItemComposition::Table)
+                    .modify_column(ColumnDef::new(ItemComposition::CpstQty).double().not_null())
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum ItemComposition {
+    Table,
+    CpstQty,
+}
@@ -0,0 +1,37 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // N once the component was removed from its composite item; kept for
+        // history, no longer taken out of stock with a sale
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(ItemComposition::Table)
+                    .add_column(string_len(ItemComposition::UseYn, 1).default("Y"))
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(ItemComposition::Table)
+                    .drop_column(ItemComposition::UseYn)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum ItemComposition {
+    Table,
+    UseYn,
+}
@@ -0,0 +1,748 @@
+//! Local stand-in for a VSCU JAR.
+//!
+//! Implements the VSCU endpoints this service calls so transmission paths can
+//! be exercised offline. Behaviour is configured at start-up through env vars
+//! and can be changed at runtime through `/mock/config`:
+//!
+//! - `MOCK_VSCU_PORT`        port to listen on (default 8088)
+//! - `MOCK_VSCU_RESULT_CD`   resultCd returned by endpoints without a queued script (default "000")
+//! - `MOCK_VSCU_LATENCY_MS`  delay before every answer (default 0)
+//! - `MOCK_VSCU_HTTP_STATUS` HTTP status to answer with (default 200)
+//! - `MOCK_VSCU_HANG`        "true" to never answer, forcing client timeouts
+//! - `MOCK_VSCU_SCRIPT`      per endpoint resultCds consumed one per request to
+//!   that endpoint, `path=codes` entries separated by `;`, e.g.
+//!   "trnsSales/saveSales=999,999,000;stock/saveStockItems=999" to fail two
+//!   sales then succeed and fail one stock movement
+
+use std::{
+    collections::{BTreeMap, HashMap, VecDeque},
+    env,
+    net::SocketAddr,
+    sync::Arc,
+    time::Duration,
+};
+
+use axum::{
+    Json, Router,
+    extract::{MatchedPath, State},
+    http::StatusCode,
+    response::{IntoResponse, Response},
+    routing::{get, post},
+};
+use chrono::Local;
+use rand::{Rng, distributions::Alphanumeric};
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+use tokio::{net::TcpListener, sync::Mutex};
+use tracing::{info, warn};
+
+// ========== Configuration ==========
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct MockConfig {
+    result_cd: String,
+    latency_ms: u64,
+    http_status: u16,
+    hang: bool,
+    /// Queued resultCds by endpoint path (without the leading `/`)
+    script: BTreeMap<String, VecDeque<String>>,
+}
+
+impl MockConfig {
+    fn from_env() -> Self {
+        Self {
+            result_cd: env::var("MOCK_VSCU_RESULT_CD").unwrap_or_else(|_| "000".to_string()),
+            latency_ms: env_parse("MOCK_VSCU_LATENCY_MS", 0),
+            http_status: env_parse("MOCK_VSCU_HTTP_STATUS", 200),
+            hang: env_parse("MOCK_VSCU_HANG", false),
+            script: env::var("MOCK_VSCU_SCRIPT")
+                .map(|s| parse_script(&s))
+                .unwrap_or_default(),
+        }
+    }
+}
+
+/// Partial update accepted by `POST /mock/config`
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct MockConfigUpdate {
+    result_cd: Option<String>,
+    latency_ms: Option<u64>,
+    http_status: Option<u16>,
+    hang: Option<bool>,
+    script: Option<String>,
+}
+
+fn env_parse<T: std::str::FromStr>(key: &str, default: T) -> T {
+    env::var(key)
+        .ok()
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(default)
+}
+
+/// `path=cd,cd;path=cd` into queued resultCds by endpoint path
+fn parse_script(script: &str) -> BTreeMap<String, VecDeque<String>> {
+    let mut queues = BTreeMap::new();
+
+    for entry in script.split(';').map(str::trim).filter(|e| !e.is_empty()) {
+        let Some((path, codes)) = entry.split_once('=') else {
+            warn!("Ignoring script entry without an endpoint: {:?}", entry);
+            continue;
+        };
+
+        let codes: VecDeque<String> = codes
+            .split(',')
+            .map(|s| s.trim().to_string())
+            .filter(|s| !s.is_empty())
+            .collect();
+        queues.insert(path.trim().trim_start_matches('/').to_string(), codes);
+    }
+
+    queues
+}
+
+// ========== State ==========
+
+#[derive(Default)]
+struct Counters {
+    /// Receipt numbers per (tin, bhfId, rcptTyCd)
+    rcpt_no: HashMap<(String, String, String), i64>,
+    /// Total receipt numbers per (tin, bhfId)
+    tot_rcpt_no: HashMap<(String, String), i64>,
+    /// Items accepted through saveItems per (tin, bhfId), keyed by itemCd
+    items: HashMap<(String, String), HashMap<String, Value>>,
+}
+
+struct MockState {
+    config: Mutex<MockConfig>,
+    counters: Mutex<Counters>,
+}
+
+type SharedState = Arc<MockState>;
+
+#[tokio::main]
+async fn main() -> anyhow::Result<()> {
+    tracing_subscriber::fmt()
+        .with_env_filter(
+            tracing_subscriber::EnvFilter::try_from_default_env()
+                .unwrap_or_else(|_| "info".into()),
+        )
+        .init();
+
+    let config = MockConfig::from_env();
+    info!("Mock VSCU config: {:?}", config);
+
+    let state = Arc::new(MockState {
+        config: Mutex::new(config),
+        counters: Mutex::new(Counters::default()),
+    });
+
+    let app = Router::new()
+        .route("/mock/config", get(get_config).post(update_config))
+        .route("/initializer/selectInitInfo", post(select_init_info))
+        .route("/code/selectCodes", post(select_codes))
+        .route("/itemClass/selectItemsClass", post(select_item_classes))
+        .route("/trnsSales/saveSales", post(save_sales))
+        .route("/items/saveItems", post(save_items))
+        .route("/items/selectItems", post(select_items))
+        .route("/items/saveItemComposition", post(acknowledge))
+        .route("/stockMaster/saveStockMaster", post(acknowledge))
+        .route("/stock/saveStockItems", post(acknowledge))
+        .route("/branches/saveBrancheCustomers", post(acknowledge))
+        .route("/branches/saveBrancheUsers", post(acknowledge))
+        .route("/branches/saveBrancheInsurances", post(acknowledge))
+        .route("/trnsPurchase/selectTrnsPurchaseSales", post(select_purchase_sales))
+        .route("/trnsPurchase/savePurchases", post(acknowledge))
+        .route("/imports/selectImportItems", post(select_import_items))
+        .route("/imports/updateImportItems", post(acknowledge))
+        .route("/notices/selectNotices", post(select_notices))
+        .route("/customers/selectCustomer", post(select_customer))
+        .route("/branches/selectBranches", post(select_branches))
+        .with_state(state);
+
+    let addr = SocketAddr::from(([0, 0, 0, 0], env_parse("MOCK_VSCU_PORT", 8088)));
+    let listener = TcpListener::bind(addr).await?;
+    info!("Mock VSCU listening on {}", addr);
+
+    axum::serve(listener, app).await?;
+    Ok(())
+}
+
+// ========== Config Handlers ==========
+
+async fn get_config(State(state): State<SharedState>) -> impl IntoResponse {
+    Json(state.config.lock().await.clone())
+}
+
+async fn update_config(
+    State(state): State<SharedState>,
+    Json(update): Json<MockConfigUpdate>,
+) -> impl IntoResponse {
+    let mut config = state.config.lock().await;
+
+    if let Some(v) = update.result_cd {
+        config.result_cd = v;
+    }
+    if let Some(v) = update.latency_ms {
+        config.latency_ms = v;
+    }
+    if let Some(v) = update.http_status {
+        config.http_status = v;
+    }
+    if let Some(v) = update.hang {
+        config.hang = v;
+    }
+    if let Some(v) = update.script {
+        config.script = parse_script(&v);
+    }
+
+    info!("Mock VSCU config updated: {:?}", *config);
+    Json(config.clone())
+}
+
+// ========== VSCU Handlers ==========
+
+/// What the next VSCU answer should look like
+enum Behaviour {
+    Answer(String),
+    HttpError(StatusCode),
+}
+
+/// Applies latency/hang and picks the result code for this request to `path`
+async fn next_behaviour(state: &SharedState, path: &MatchedPath) -> Behaviour {
+    let (latency, hang, http_status, result_cd) = {
+        let mut config = state.config.lock().await;
+        let result_cd = config
+            .script
+            .get_mut(path.as_str().trim_start_matches('/'))
+            .and_then(VecDeque::pop_front)
+            .unwrap_or_else(|| config.result_cd.clone());
+        (config.latency_ms, config.hang, config.http_status, result_cd)
+    };
+
+    if hang {
+        // Long enough for any sane client timeout to fire first
+        tokio::time::sleep(Duration::from_secs(3600)).await;
+    }
+    if latency > 0 {
+        tokio::time::sleep(Duration::from_millis(latency)).await;
+    }
+
+    match StatusCode::from_u16(http_status) {
+        Ok(status) if !status.is_success() => Behaviour::HttpError(status),
+        _ => Behaviour::Answer(result_cd),
+    }
+}
+
+fn envelope(result_cd: &str, data: Value) -> Response {
+    Json(json!({
+        "resultCd": result_cd,
+        "resultMsg": result_msg(result_cd),
+        "resultDt": Local::now().format("%Y%m%d%H%M%S").to_string(),
+        "data": data,
+    }))
+    .into_response()
+}
+
+/// Save endpoints that only answer with a result code
+async fn acknowledge(
+    State(state): State<SharedState>,
+    path: MatchedPath,
+    Json(body): Json<Value>,
+) -> Response {
+    info!("Mock VSCU received: {}", body);
+
+    match next_behaviour(&state, &path).await {
+        Behaviour::HttpError(status) => status.into_response(),
+        Behaviour::Answer(cd) => envelope(&cd, Value::Null),
+    }
+}
+
+async fn select_init_info(
+    State(state): State<SharedState>,
+    path: MatchedPath,
+    Json(body): Json<Value>,
+) -> Response {
+    info!("Mock VSCU received init request: {}", body);
+
+    let result_cd = match next_behaviour(&state, &path).await {
+        Behaviour::HttpError(status) => return status.into_response(),
+        Behaviour::Answer(cd) => cd,
+    };
+
+    if result_cd != "000" {
+        return envelope(&result_cd, Value::Null);
+    }
+
+    let tin = body["tin"].as_str().unwrap_or_default();
+    let bhf_id = body["bhfId"].as_str().unwrap_or_default();
+
+    envelope(
+        &result_cd,
+        json!({
+            "info": {
+                "tin": tin,
+                "taxprNm": "MOCK TAXPAYER LTD",
+                "bsnsActv": "Retail",
+                "bhfId": bhf_id,
+                "bhfNm": format!("Mock Branch {}", bhf_id),
+                "bhfOpenDt": "20240101",
+                "prvncNm": "NAIROBI",
+                "dstrtNm": "WESTLANDS",
+                "sctrNm": "PARKLANDS",
+                "locDesc": "Mock Plaza",
+                "hqYn": if bhf_id == "00" { "Y" } else { "N" },
+                "mgrNm": "Mock Manager",
+                "mgrTelNo": "0700000000",
+                "mgrEmail": "manager@example.com",
+                "dvcId": body["dvcSrlNo"],
+                "sdcId": format!("KRACU01000{:05}", bhf_id.parse::<u32>().unwrap_or(0)),
+                "mrcNo": "WIS01000001",
+                "cmcKey": random_code(32),
+            }
+        }),
+    )
+}
+
+async fn select_codes(
+    State(state): State<SharedState>,
+    path: MatchedPath,
+    Json(body): Json<Value>,
+) -> Response {
+    info!("Mock VSCU received code request: {}", body);
+
+    match next_behaviour(&state, &path).await {
+        Behaviour::HttpError(status) => status.into_response(),
+        Behaviour::Answer(cd) if cd != "000" => envelope(&cd, Value::Null),
+        Behaviour::Answer(cd) => envelope(&cd, json!({ "clsList": mock_code_classes() })),
+    }
+}
+
+/// Small but representative slice of the KRA code lists
+fn mock_code_classes() -> Value {
+    fn class(cd_cls: &str, nm: &str, codes: &[(&str, &str, Option<&str>)]) -> Value {
+        let dtl_list: Vec<Value> = codes
+            .iter()
+            .enumerate()
+            .map(|(i, (cd, cd_nm, dfn1))| {
+                json!({
+                    "cd": cd,
+                    "cdNm": cd_nm,
+                    "cdDesc": cd_nm,
+                    "useYn": "Y",
+                    "srtOrd": i + 1,
+                    "userDfnCd1": dfn1,
+                    "userDfnCd2": null,
+                    "userDfnCd3": null,
+                })
+            })
+            .collect();
+
+        json!({
+            "cdCls": cd_cls,
+            "cdClsNm": nm,
+            "cdClsDesc": null,
+            "useYn": "Y",
+            "userDfnNm1": null,
+            "userDfnNm2": null,
+            "userDfnNm3": null,
+            "dtlList": dtl_list,
+        })
+    }
+
+    json!([
+        class("04", "Taxation Type", &[
+            ("A", "A-Exempt", Some("0")),
+            ("B", "B-16.00%", Some("16")),
+            ("C", "C-0%", Some("0")),
+            ("D", "D-Non-VAT", Some("0")),
+            ("E", "E-8%", Some("8")),
+        ]),
+        class("05", "Country", &[("KE", "KENYA", None), ("UG", "UGANDA", None), ("TZ", "TANZANIA", None)]),
+        class("07", "Payment Type", &[
+            ("01", "CASH", None),
+            ("02", "CREDIT", None),
+            ("03", "CASH/CREDIT", None),
+            ("04", "BANK CHECK", None),
+            ("05", "DEBIT&CREDIT CARD", None),
+            ("06", "MOBILE MONEY", None),
+            ("07", "OTHER", None),
+        ]),
+        class("10", "Quantity Unit", &[("U", "Pieces/item [Number]", None), ("KG", "Kilo-Gramme", None), ("L", "Litre", None)]),
+        class("17", "Packing Unit", &[("NT", "NET", None), ("BG", "Bag", None), ("BX", "Box", None)]),
+        class("24", "Item Type", &[("1", "Raw Material", None), ("2", "Finished Product", None), ("3", "Service", None)]),
+        class("32", "Refund Reason", &[
+            ("01", "Missing Quantity", None),
+            ("02", "Missing Item", None),
+            ("03", "Damaged", None),
+            ("04", "Wasted", None),
+            ("05", "Raw Material Shortage", None),
+            ("06", "Refund", None),
+        ]),
+    ])
+}
+
+async fn select_item_classes(
+    State(state): State<SharedState>,
+    path: MatchedPath,
+    Json(body): Json<Value>,
+) -> Response {
+    info!("Mock VSCU received item class request: {}", body);
+
+    let classes = [
+        ("10000000", "Live Plant and Animal Material and Accessories and Supplies", 1, None, "N"),
+        ("10100000", "Live animals", 2, None, "N"),
+        ("10101500", "Livestock", 3, None, "N"),
+        ("10101501", "Cats", 4, Some("B"), "N"),
+        ("50000000", "Food Beverage and Tobacco Products", 1, None, "Y"),
+        ("50200000", "Beverages", 2, None, "Y"),
+        ("50202300", "Non alcoholic beverages", 3, None, "Y"),
+        ("50202301", "Water", 4, Some("B"), "Y"),
+        ("51000000", "Drugs and Pharmaceutical Products", 1, None, "N"),
+        ("51101500", "Antibacterials", 3, None, "N"),
+        ("51101501", "Amoxicillin", 4, Some("A"), "N"),
+        ("90101500", "Restaurants", 3, None, "N"),
+        ("90101501", "Fast food restaurants", 4, Some("B"), "N"),
+    ];
+
+    let item_cls_list: Vec<Value> = classes
+        .iter()
+        .map(|(cd, nm, lvl, tax, mjr)| {
+            json!({
+                "itemClsCd": cd,
+                "itemClsNm": nm,
+                "itemClsLvl": lvl,
+                "taxTyCd": tax,
+                "mjrTgYn": mjr,
+                "useYn": "Y",
+            })
+        })
+        .collect();
+
+    match next_behaviour(&state, &path).await {
+        Behaviour::HttpError(status) => status.into_response(),
+        Behaviour::Answer(cd) if cd != "000" => envelope(&cd, Value::Null),
+        Behaviour::Answer(cd) => envelope(&cd, json!({ "itemClsList": item_cls_list })),
+    }
+}
+
+async fn select_purchase_sales(
+    State(state): State<SharedState>,
+    path: MatchedPath,
+    Json(body): Json<Value>,
+) -> Response {
+    info!("Mock VSCU received purchase request: {}", body);
+
+    match next_behaviour(&state, &path).await {
+        Behaviour::HttpError(status) => status.into_response(),
+        Behaviour::Answer(cd) if cd != "000" => envelope(&cd, Value::Null),
+        Behaviour::Answer(cd) => envelope(&cd, json!({ "saleList": mock_supplier_sales() })),
+    }
+}
+
+/// One supplier invoice with a taxable (B, 16%) and an exempt (A) line
+fn mock_supplier_sales() -> Value {
+    let soda = json!({
+        "itemSeq": 1, "itemCd": "KE1NTXU0000001", "itemClsCd": "5020230500",
+        "itemNm": "Mock Soda 500ml", "bcd": null, "pkgUnitCd": "NT", "pkg": 2.0,
+        "qtyUnitCd": "U", "qty": 2.0, "prc": 58.0, "splyAmt": 116.0, "dcRt": 0.0,
+        "dcAmt": 0.0, "taxTyCd": "B", "taxblAmt": 116.0, "taxAmt": 16.0, "totAmt": 116.0
+    });
+    let flour = json!({
+        "itemSeq": 2, "itemCd": "KE1NTXU0000002", "itemClsCd": "5010150000",
+        "itemNm": "Mock Maize Flour 1kg", "bcd": null, "pkgUnitCd": "NT", "pkg": 1.0,
+        "qtyUnitCd": "U", "qty": 1.0, "prc": 50.0, "splyAmt": 50.0, "dcRt": 0.0,
+        "dcAmt": 0.0, "taxTyCd": "A", "taxblAmt": 50.0, "taxAmt": 0.0, "totAmt": 50.0
+    });
+
+    let mut sale = json!({
+        "spplrTin": "P000000001Z",
+        "spplrNm": "Mock Supplier Ltd",
+        "spplrBhfId": "00",
+        "spplrInvcNo": 1,
+        "rcptTyCd": "S",
+        "pmtTyCd": "01",
+        "cfmDt": Local::now().format("%Y-%m-%d %H:%M:%S").to_string(),
+        "salesDt": Local::now().format("%Y%m%d").to_string(),
+        "stockRlsDt": null,
+        "totItemCnt": 2,
+        "totTaxblAmt": 166.0,
+        "totTaxAmt": 16.0,
+        "totAmt": 166.0,
+        "remark": null,
+        "itemList": [soda, flour],
+    });
+
+    let taxes = [
+        ("A", 50.0, 0.0, 0.0),
+        ("B", 116.0, 16.0, 16.0),
+        ("C", 0.0, 0.0, 0.0),
+        ("D", 0.0, 0.0, 0.0),
+        ("E", 0.0, 8.0, 0.0),
+    ];
+    for (ty, taxbl_amt, tax_rt, tax_amt) in taxes {
+        sale[format!("taxblAmt{ty}")] = json!(taxbl_amt);
+        sale[format!("taxRt{ty}")] = json!(tax_rt);
+        sale[format!("taxAmt{ty}")] = json!(tax_amt);
+    }
+
+    json!([sale])
+}
+
+async fn select_import_items(
+    State(state): State<SharedState>,
+    path: MatchedPath,
+    Json(body): Json<Value>,
+) -> Response {
+    info!("Mock VSCU received import request: {}", body);
+
+    match next_behaviour(&state, &path).await {
+        Behaviour::HttpError(status) => status.into_response(),
+        Behaviour::Answer(cd) if cd != "000" => envelope(&cd, Value::Null),
+        Behaviour::Answer(cd) => envelope(&cd, json!({ "itemList": mock_import_items() })),
+    }
+}
+
+/// Two lines of one customs declaration, both still waiting for the branch
+fn mock_import_items() -> Value {
+    let dcl_de = Local::now().format("%Y%m%d").to_string();
+
+    let rice = json!({
+        "taskCd": "2239078", "dclDe": dcl_de, "itemSeq": 1, "dclNo": "C3460-2019-TZMWZ",
+        "hsCd": "1006300000", "itemNm": "Mock Rice 25kg", "imptItemSttsCd": "2",
+        "orgnNatCd": "TZ", "exptNatCd": "TZ", "pkg": 10.0, "pkgUnitCd": "BG",
+        "qty": 10.0, "qtyUnitCd": "U", "totWt": 250.0, "netWt": 250.0,
+        "spplrNm": "Mock Exporter Ltd", "agntNm": "Mock Clearing Agent",
+        "invcFcurAmt": 500.0, "invcFcurCd": "USD", "invcFcurExcrt": 129.5
+    });
+    let oil = json!({
+        "taskCd": "2239078", "dclDe": dcl_de, "itemSeq": 2, "dclNo": "C3460-2019-TZMWZ",
+        "hsCd": "1507900000", "itemNm": "Mock Cooking Oil 20l", "imptItemSttsCd": "2",
+        "orgnNatCd": "TZ", "exptNatCd": "TZ", "pkg": 5.0, "pkgUnitCd": "CT",
+        "qty": 5.0, "qtyUnitCd": "U", "totWt": 100.0, "netWt": 92.0,
+        "spplrNm": "Mock Exporter Ltd", "agntNm": "Mock Clearing Agent",
+        "invcFcurAmt": 300.0, "invcFcurCd": "USD", "invcFcurExcrt": 129.5
+    });
+
+    json!([rice, oil])
+}
+
+async fn select_notices(
+    State(state): State<SharedState>,
+    path: MatchedPath,
+    Json(body): Json<Value>,
+) -> Response {
+    info!("Mock VSCU received notice request: {}", body);
+
+    let notice = json!({
+        "ntcNo": 1,
+        "title": "Mock notice",
+        "cont": "eTIMS will be under maintenance this weekend.",
+        "dtlUrl": "http://localhost/notice/1",
+        "regrNm": "KRA",
+        "regDt": Local::now().format("%Y%m%d%H%M%S").to_string(),
+    });
+
+    match next_behaviour(&state, &path).await {
+        Behaviour::HttpError(status) => status.into_response(),
+        Behaviour::Answer(cd) if cd != "000" => envelope(&cd, Value::Null),
+        Behaviour::Answer(cd) => envelope(&cd, json!({ "noticeList": [notice] })),
+    }
+}
+
+/// Any PIN starting with "P" is registered, everything else is unknown
+async fn select_customer(
+    State(state): State<SharedState>,
+    path: MatchedPath,
+    Json(body): Json<Value>,
+) -> Response {
+    info!("Mock VSCU received customer request: {}", body);
+
+    let pin = body["custmTin"].as_str().unwrap_or_default().to_string();
+    let customer = json!({
+        "tin": pin,
+        "taxprNm": "Mock Customer Ltd",
+        "taxprSttsCd": "A",
+        "prvncNm": "NAIROBI",
+        "dstrtNm": "WESTLANDS",
+        "sctrNm": "PARKLANDS",
+        "locDesc": "Mock Street",
+    });
+
+    match next_behaviour(&state, &path).await {
+        Behaviour::HttpError(status) => status.into_response(),
+        Behaviour::Answer(cd) if cd != "000" => envelope(&cd, Value::Null),
+        Behaviour::Answer(_) if !pin.starts_with('P') => envelope("001", Value::Null),
+        Behaviour::Answer(cd) => envelope(&cd, json!({ "custList": [customer] })),
+    }
+}
+
+/// Head office "00" plus one outlet under the requesting PIN
+async fn select_branches(
+    State(state): State<SharedState>,
+    path: MatchedPath,
+    Json(body): Json<Value>,
+) -> Response {
+    info!("Mock VSCU received branch request: {}", body);
+
+    let tin = body["tin"].as_str().unwrap_or_default();
+    let head_office = json!({
+        "tin": tin, "bhfId": "00", "bhfNm": "Mock Head Office", "bhfSttsCd": "01",
+        "prvncNm": "NAIROBI", "dstrtNm": "WESTLANDS", "sctrNm": "PARKLANDS",
+        "locDesc": "Mock Street", "mgrNm": "Mock Manager", "mgrTelNo": "0700000000",
+        "mgrEmail": "hq@example.com", "hqYn": "Y"
+    });
+    let outlet = json!({
+        "tin": tin, "bhfId": "01", "bhfNm": "Mock Outlet", "bhfSttsCd": "01",
+        "prvncNm": "MOMBASA", "dstrtNm": "MVITA", "sctrNm": "OLD TOWN",
+        "locDesc": "Mock Road", "mgrNm": "Mock Supervisor", "mgrTelNo": "0711000000",
+        "mgrEmail": "outlet@example.com", "hqYn": "N"
+    });
+
+    match next_behaviour(&state, &path).await {
+        Behaviour::HttpError(status) => status.into_response(),
+        Behaviour::Answer(cd) if cd != "000" => envelope(&cd, Value::Null),
+        Behaviour::Answer(cd) => envelope(&cd, json!({ "bhfList": [head_office, outlet] })),
+    }
+}
+
+fn device_key(body: &Value) -> (String, String) {
+    (
+        body["tin"].as_str().unwrap_or_default().to_string(),
+        body["bhfId"].as_str().unwrap_or_default().to_string(),
+    )
+}
+
+async fn save_items(
+    State(state): State<SharedState>,
+    path: MatchedPath,
+    Json(body): Json<Value>,
+) -> Response {
+    info!("Mock VSCU received item: {}", body);
+
+    let result_cd = match next_behaviour(&state, &path).await {
+        Behaviour::HttpError(status) => return status.into_response(),
+        Behaviour::Answer(cd) => cd,
+    };
+
+    if result_cd == "000" {
+        let item_cd = body["itemCd"].as_str().unwrap_or_default().to_string();
+        state
+            .counters
+            .lock()
+            .await
+            .items
+            .entry(device_key(&body))
+            .or_default()
+            .insert(item_cd, body.clone());
+    }
+
+    envelope(&result_cd, Value::Null)
+}
+
+async fn select_items(
+    State(state): State<SharedState>,
+    path: MatchedPath,
+    Json(body): Json<Value>,
+) -> Response {
+    info!("Mock VSCU received item request: {}", body);
+
+    let result_cd = match next_behaviour(&state, &path).await {
+        Behaviour::HttpError(status) => return status.into_response(),
+        Behaviour::Answer(cd) => cd,
+    };
+
+    if result_cd != "000" {
+        return envelope(&result_cd, Value::Null);
+    }
+
+    let items: Vec<Value> = state
+        .counters
+        .lock()
+        .await
+        .items
+        .get(&device_key(&body))
+        .map(|items| items.values().cloned().collect())
+        .unwrap_or_default();
+
+    if items.is_empty() {
+        return envelope("001", Value::Null);
+    }
+
+    envelope(&result_cd, json!({ "itemList": items }))
+}
+
+async fn save_sales(
+    State(state): State<SharedState>,
+    path: MatchedPath,
+    Json(body): Json<Value>,
+) -> Response {
+    info!("Mock VSCU received sale: {}", body);
+
+    let result_cd = match next_behaviour(&state, &path).await {
+        Behaviour::HttpError(status) => return status.into_response(),
+        Behaviour::Answer(cd) => cd,
+    };
+
+    if result_cd != "000" {
+        return envelope(&result_cd, Value::Null);
+    }
+
+    let (tin, bhf_id) = device_key(&body);
+    let rcpt_ty_cd = body["rcptTyCd"].as_str().unwrap_or("S").to_string();
+
+    let (rcpt_no, tot_rcpt_no) = {
+        let mut counters = state.counters.lock().await;
+        let rcpt_no = counters
+            .rcpt_no
+            .entry((tin.clone(), bhf_id.clone(), rcpt_ty_cd))
+            .or_insert(0);
+        *rcpt_no += 1;
+        let rcpt_no = *rcpt_no;
+
+        let tot = counters.tot_rcpt_no.entry((tin, bhf_id.clone())).or_insert(0);
+        *tot += 1;
+        (rcpt_no, *tot)
+    };
+
+    envelope(
+        &result_cd,
+        json!({
+            "rcptNo": rcpt_no,
+            "intrlData": random_code(26),
+            "rcptSign": random_code(16),
+            "totRcptNo": tot_rcpt_no,
+            "vsdcRcptPbctDate": Local::now().format("%Y%m%d%H%M%S").to_string(),
+            "sdcId": format!("KRACU01000{:05}", bhf_id.parse::<u32>().unwrap_or(0)),
+            "mrcNo": "WIS01000001",
+        }),
+    )
+}
+
+fn random_code(len: usize) -> String {
+    rand::thread_rng()
+        .sample_iter(&Alphanumeric)
+        .take(len)
+        .map(|c| char::from(c).to_ascii_uppercase())
+        .collect()
+}
+
+fn result_msg(result_cd: &str) -> &'static str {
+    match result_cd {
+        "000" => "It is succeeded",
+        "001" => "There is no search result",
+        "891" => "An error occurred while Request URL is created",
+        "894" => "Server communication error occurred",
+        "899" => "An error regarding Client occurred",
+        "901" => "It is not valid device",
+        "902" => "This device is installed",
+        "910" => "Request parameter error",
+        "921" => "Sales data that has been declared as sales cannot be received",
+        "922" => "Sales invoice data can be received after receiving the sales data",
+        "991" => "Registration error",
+        "994" => "Overlapped data",
+        "999" => "Unknown error",
+        _ => "Mock VSCU error",
+    }
+}
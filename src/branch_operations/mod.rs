@@ -1 +1,4 @@
-pub mod route_branches;
\ No newline at end of file
+pub mod route_branches;
+pub mod transmit_branches;
+pub mod route_branch_registry;
+pub mod sync_branches;
@@ -0,0 +1,160 @@
+use std::sync::Arc;
+
+use axum::{
+    extract::State,
+    http::StatusCode,
+    response::IntoResponse,
+    routing::{get, post},
+    Json, Router,
+};
+use axum_extra::TypedHeader;
+use headers::{Authorization, authorization::Bearer};
+use sea_orm::{ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter, QueryOrder};
+use serde_json::{json, Value};
+use tracing::error;
+
+use crate::{
+    branch_operations::sync_branches::sync_branches,
+    models::{branches, initialization},
+    types::salespayloadtype::AuthUser,
+    utils::{bearer::bearer_resolver, crypto::{decrypt, decrypt_deterministic}},
+    vscu::device::Device,
+};
+
+pub fn branches_router(db: Arc<DatabaseConnection>) -> Router {
+    Router::new()
+        .route("/", get(handle_branches_get))
+        .route("/sync", post(handle_branches_sync))
+        .with_state(db)
+}
+
+fn fail(code: StatusCode, message: String) -> (StatusCode, Json<Value>) {
+    (code, Json(json!({ "status": "error", "message": message })))
+}
+
+async fn resolve_user(token: &str, db: &DatabaseConnection) -> Result<AuthUser, (StatusCode, Json<Value>)> {
+    match bearer_resolver(token, db).await {
+        Ok(val) => serde_json::from_value(val)
+            .map_err(|e| fail(StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to parse user: {}", e))),
+        Err(e) => Err(fail(StatusCode::UNAUTHORIZED, e)),
+    }
+}
+
+/// Every branch registered under the caller's PIN, each with the devices
+/// registered here for it
+pub async fn handle_branches_get(
+    TypedHeader(auth): TypedHeader<Authorization<Bearer>>,
+    State(db): State<Arc<DatabaseConnection>>,
+) -> impl IntoResponse {
+    let user = match resolve_user(auth.token(), db.as_ref()).await {
+        Ok(u) => u,
+        Err(res) => return res,
+    };
+
+    let rows = match branches::Entity::find()
+        .filter(branches::Column::Tin.eq(&user.pin))
+        .order_by_asc(branches::Column::BhfId)
+        .all(db.as_ref())
+        .await
+    {
+        Ok(rows) => rows,
+        Err(e) => {
+            error!("Failed to fetch branches: {:?}", e);
+            return fail(StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to fetch branches: {}", e));
+        }
+    };
+
+    // branch_id is encrypted non-deterministically, so match devices in memory
+    let devices: Vec<(String, initialization::Model)> = match initialization::Entity::find()
+        .filter(initialization::Column::Pin.eq(&user.pin))
+        .all(db.as_ref())
+        .await
+    {
+        Ok(devices) => devices
+            .into_iter()
+            .filter_map(|d| match decrypt(&d.branch_id) {
+                Ok(bhf_id) => Some((bhf_id, d)),
+                Err(e) => {
+                    error!("Skipping device {}: {}", d.id, e);
+                    None
+                }
+            })
+            .collect(),
+        Err(e) => {
+            error!("Failed to fetch devices: {:?}", e);
+            return fail(StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to fetch devices: {}", e));
+        }
+    };
+
+    let data: Vec<Value> = rows
+        .into_iter()
+        .map(|branch| {
+            let branch_devices: Vec<Value> = devices
+                .iter()
+                .filter(|(bhf_id, _)| *bhf_id == branch.bhf_id)
+                .map(|(_, d)| {
+                    json!({
+                        "credentialsId": d.id,
+                        // Stored encrypted; never hand out the ciphertext
+                        "deviceSerial": decrypt_deterministic(&d.device_serial).ok(),
+                        "environmentName": d.environment_name,
+                        "sdcId": d.sdc_id,
+                        "mrcNo": d.mrc_no,
+                    })
+                })
+                .collect();
+
+            json!({
+                "bhfId": branch.bhf_id,
+                "bhfNm": branch.bhf_nm,
+                "bhfSttsCd": branch.bhf_stts_cd,
+                "prvncNm": branch.prvnc_nm,
+                "dstrtNm": branch.dstrt_nm,
+                "sctrNm": branch.sctr_nm,
+                "locDesc": branch.loc_desc,
+                "mgrNm": branch.mgr_nm,
+                "mgrTelNo": branch.mgr_tel_no,
+                "mgrEmail": branch.mgr_email,
+                "hqYn": branch.hq_yn,
+                "updatedAt": branch.updated_at,
+                "devices": branch_devices,
+            })
+        })
+        .collect();
+
+    (
+        StatusCode::OK,
+        Json(json!({
+            "status": "success",
+            "data": data
+        })),
+    )
+}
+
+/// Refresh the branch list from VSCU now instead of waiting for the background sync
+pub async fn handle_branches_sync(
+    TypedHeader(auth): TypedHeader<Authorization<Bearer>>,
+    State(db): State<Arc<DatabaseConnection>>,
+) -> impl IntoResponse {
+    let user = match resolve_user(auth.token(), db.as_ref()).await {
+        Ok(u) => u,
+        Err(res) => return res,
+    };
+
+    let device = match Device::from_user(&user) {
+        Ok(d) => d,
+        Err(e) => return fail(StatusCode::INTERNAL_SERVER_ERROR, e),
+    };
+
+    match sync_branches(db.as_ref(), &device).await {
+        Ok(synced) => (
+            StatusCode::OK,
+            Json(json!({
+                "status": "success",
+                "message": "Branches synced",
+                "synced": synced,
+            })),
+        ),
+        Err(e) => fail(StatusCode::BAD_GATEWAY, format!("Branch sync failed: {}", e)),
+    }
+}
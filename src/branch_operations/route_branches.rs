@@ -1,13 +1,15 @@
 use std::sync::Arc;
 
 use axum::{
-    extract::State,
+    extract::{Query, State},
     http::StatusCode,
     response::IntoResponse,
     routing::{ post},
     Json, Router,
 };
 
+use axum_extra::TypedHeader;
+use headers::{Authorization, authorization::Bearer};
 use sea_orm::{
     ActiveModelTrait,
     ActiveValue::Set,
@@ -15,7 +17,7 @@ use sea_orm::{
     EntityTrait,
 };
 
-use serde_json::json;
+use serde_json::{json, Value};
 use tracing::error;
 
 use crate::{
@@ -33,11 +35,20 @@ use crate::{
             Entity as InsuranceEntity,
         },
     },
-    types::braches_data_payload::{
-        BhfCustSaveReq,
-        BhfUserSaveReq,
-        BhfInsuranceSaveReq,
+    branch_operations::transmit_branches::transmit,
+    customers::verify::check_pins,
+    sales::status,
+    types::{
+        braches_data_payload::{
+            BhfCustSaveReq,
+            BhfUserSaveReq,
+            BhfInsuranceSaveReq,
+        },
+        customers::{PinCheckQuery, PinPolicy},
+        salespayloadtype::AuthUser,
     },
+    utils::bearer::bearer_resolver,
+    vscu::device::Device,
 };
 
 /// ──────────────────────────────────────────────
@@ -62,14 +73,95 @@ pub fn branch_insurances(db: Arc<DatabaseConnection>) -> Router {
         .with_state(db)
 }
 
+/// The device to send a branch record as. The bearer token is optional so
+/// existing clients keep working: without it the record is only stored, as
+/// before, and never sent to KRA. With it, the payload must be for that
+/// device's branch.
+async fn resolve_device(
+    auth: Option<TypedHeader<Authorization<Bearer>>>,
+    db: &DatabaseConnection,
+    tin: &str,
+    bhf_id: &str,
+) -> Result<Option<(AuthUser, Device)>, (StatusCode, Json<Value>)> {
+    let Some(TypedHeader(auth)) = auth else {
+        return Ok(None);
+    };
+
+    let fail = |code: StatusCode, message: String| {
+        (code, Json(json!({ "status": "error", "message": message })))
+    };
+
+    let user: AuthUser = match bearer_resolver(auth.token(), db).await {
+        Ok(val) => serde_json::from_value(val)
+            .map_err(|e| fail(StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to parse user: {}", e)))?,
+        Err(e) => return Err(fail(StatusCode::UNAUTHORIZED, e)),
+    };
+
+    let device = Device::from_user(&user).map_err(|e| fail(StatusCode::INTERNAL_SERVER_ERROR, e))?;
+
+    if tin != device.tin || bhf_id != device.bhf_id {
+        return Err(fail(
+            StatusCode::BAD_REQUEST,
+            "tin/bhfId do not match the authenticated device".to_string(),
+        ));
+    }
+
+    Ok(Some((user, device)))
+}
+
+/// Status of a record stored for `sender`, also returned as `transmission`:
+/// RECEIVED when it is queued for KRA, LOCAL_ONLY when no device was
+/// authenticated to send it with
+fn transmission(sender: &Option<(AuthUser, Device)>) -> &'static str {
+    match sender {
+        Some(_) => status::RECEIVED,
+        None => status::LOCAL_ONLY,
+    }
+}
+
 /// ──────────────────────────────────────────────
 /// CUSTOMER HANDLERS
 /// ──────────────────────────────────────────────
 
 pub async fn handle_customer_post(
+    auth: Option<TypedHeader<Authorization<Bearer>>>,
     State(db): State<Arc<DatabaseConnection>>,
+    Query(query): Query<PinCheckQuery>,
     Json(payload): Json<BhfCustSaveReq>,
 ) -> impl IntoResponse {
+    let sender = match resolve_device(auth, db.as_ref(), &payload.tin, &payload.bhfId).await {
+        Ok(found) => found,
+        Err(res) => return res,
+    };
+
+    // PINs are looked up as the sending device, so a check needs the token
+    let pin_problems = match &sender {
+        Some((_, device)) => check_pins(device, query.pin_check, &[payload.custTin.as_str()]).await,
+        None if query.pin_check == PinPolicy::Off => Ok(Vec::new()),
+        None => {
+            return (
+                StatusCode::UNAUTHORIZED,
+                Json(json!({
+                    "status": "error",
+                    "message": "pinCheck requires a bearer token",
+                })),
+            )
+        }
+    };
+    let pin_problems = match pin_problems {
+        Ok(problems) => problems,
+        Err(unknown) => {
+            return (
+                StatusCode::UNPROCESSABLE_ENTITY,
+                Json(json!({
+                    "status": "error",
+                    "message": "Unknown customer PIN",
+                    "pinCheck": unknown,
+                })),
+            )
+        }
+    };
+
     let model = CustomerActiveModel {
         tin: Set(payload.tin),
         bhf_id: Set(payload.bhfId),
@@ -86,18 +178,35 @@ pub async fn handle_customer_post(
         regr_id: Set(payload.regrId),
         modr_nm: Set(payload.modrNm),
         modr_id: Set(payload.modrId),
+        api_key: Set(sender.as_ref().map(|(user, _)| user.api_key.clone())),
+        status: Set(transmission(&sender).to_string()),
         ..Default::default()
     };
 
     match model.insert(db.as_ref()).await {
-        Ok(saved) => (
-            StatusCode::CREATED,
-            Json(json!({
-                "status": "success",
-                "message": "Customer created",
-                "id": saved.id
-            })),
-        ),
+        Ok(saved) => {
+            let transmission = transmission(&sender);
+
+            // Send to KRA in the background, failures are picked up by the retry worker
+            if let Some((_, device)) = sender {
+                let db_clone = db.clone();
+                let id = saved.id;
+                tokio::spawn(async move {
+                    transmit::<CustomerEntity>(db_clone.as_ref(), &device, id).await;
+                });
+            }
+
+            (
+                StatusCode::CREATED,
+                Json(json!({
+                    "status": "success",
+                    "message": "Customer created",
+                    "id": saved.id,
+                    "transmission": transmission,
+                    "pinCheck": pin_problems,
+                })),
+            )
+        }
         Err(e) => {
             error!("Failed to save customer: {:?}", e);
             (
@@ -142,9 +251,15 @@ pub async fn handle_customer_get(
 /// ──────────────────────────────────────────────
 
 pub async fn handle_user_post(
+    auth: Option<TypedHeader<Authorization<Bearer>>>,
     State(db): State<Arc<DatabaseConnection>>,
     Json(payload): Json<BhfUserSaveReq>,
 ) -> impl IntoResponse {
+    let sender = match resolve_device(auth, db.as_ref(), &payload.tin, &payload.bhfId).await {
+        Ok(found) => found,
+        Err(res) => return res,
+    };
+
     let model = UserActiveModel {
         tin: Set(payload.tin),
         bhf_id: Set(payload.bhfId),
@@ -160,18 +275,34 @@ pub async fn handle_user_post(
         regr_id: Set(payload.regrId),
         modr_nm: Set(payload.modrNm),
         modr_id: Set(payload.modrId),
+        api_key: Set(sender.as_ref().map(|(user, _)| user.api_key.clone())),
+        status: Set(transmission(&sender).to_string()),
         ..Default::default()
     };
 
     match model.insert(db.as_ref()).await {
-        Ok(saved) => (
-            StatusCode::CREATED,
-            Json(json!({
-                "status": "success",
-                "message": "User created",
-                "id": saved.id   // change to saved.user_id if that's your PK
-            })),
-        ),
+        Ok(saved) => {
+            let transmission = transmission(&sender);
+
+            // Send to KRA in the background, failures are picked up by the retry worker
+            if let Some((_, device)) = sender {
+                let db_clone = db.clone();
+                let id = saved.id;
+                tokio::spawn(async move {
+                    transmit::<UserEntity>(db_clone.as_ref(), &device, id).await;
+                });
+            }
+
+            (
+                StatusCode::CREATED,
+                Json(json!({
+                    "status": "success",
+                    "message": "User created",
+                    "id": saved.id,
+                    "transmission": transmission,
+                })),
+            )
+        }
         Err(e) => {
             error!("Failed to insert user: {:?}", e);
             (
@@ -216,9 +347,15 @@ pub async fn handle_user_get(
 /// ──────────────────────────────────────────────
 
 pub async fn handle_insurance_post(
+    auth: Option<TypedHeader<Authorization<Bearer>>>,
     State(db): State<Arc<DatabaseConnection>>,
     Json(payload): Json<BhfInsuranceSaveReq>,
 ) -> impl IntoResponse {
+    let sender = match resolve_device(auth, db.as_ref(), &payload.tin, &payload.bhfId).await {
+        Ok(found) => found,
+        Err(res) => return res,
+    };
+
     let model = InsuranceActiveModel {
         tin: Set(payload.tin),
         bhf_id: Set(payload.bhfId),
@@ -230,18 +367,34 @@ pub async fn handle_insurance_post(
         regr_id: Set(payload.regrId),
         modr_nm: Set(payload.modrNm),
         modr_id: Set(payload.modrId),
+        api_key: Set(sender.as_ref().map(|(user, _)| user.api_key.clone())),
+        status: Set(transmission(&sender).to_string()),
         ..Default::default()
     };
 
     match model.insert(db.as_ref()).await {
-        Ok(saved) => (
-            StatusCode::CREATED,
-            Json(json!({
-                "status": "success",
-                "message": "Insurance created",
-                "id": saved.id
-            })),
-        ),
+        Ok(saved) => {
+            let transmission = transmission(&sender);
+
+            // Send to KRA in the background, failures are picked up by the retry worker
+            if let Some((_, device)) = sender {
+                let db_clone = db.clone();
+                let id = saved.id;
+                tokio::spawn(async move {
+                    transmit::<InsuranceEntity>(db_clone.as_ref(), &device, id).await;
+                });
+            }
+
+            (
+                StatusCode::CREATED,
+                Json(json!({
+                    "status": "success",
+                    "message": "Insurance created",
+                    "id": saved.id,
+                    "transmission": transmission,
+                })),
+            )
+        }
         Err(e) => {
             error!("Failed to save insurance: {:?}", e);
             (
@@ -0,0 +1,105 @@
+use sea_orm::{
+    ActiveValue::Set, DatabaseConnection, EntityTrait, TransactionTrait, sea_query::OnConflict,
+};
+use chrono::Utc;
+use tracing::{info, warn};
+
+use crate::{
+    models::branches::{ActiveModel, Column, Entity},
+    types::braches_data_payload::BhfInfo,
+    utils::{
+        crypto::encrypt_deterministic,
+        sync_state::{last_req_dt, mark_synced, now_req_dt},
+    },
+    vscu::{device::Device, result::ResultClass},
+};
+
+pub const RESOURCE: &str = "branches";
+
+/// Pull the branches registered under the device's PIN and upsert them
+pub async fn sync_branches(db: &DatabaseConnection, device: &Device) -> Result<usize, String> {
+    let since = last_req_dt(db, device.credentials_id, RESOURCE)
+        .await
+        .map_err(|e| e.to_string())?;
+    let started_at = now_req_dt();
+
+    let res = device
+        .client
+        .select_branches(&device.select_request(&since))
+        .await
+        .map_err(|e| e.to_string())?;
+
+    if res.is_empty_search() {
+        info!("No branch changes for device {} since {}", device.credentials_id, since);
+        mark_synced(db, device.credentials_id, RESOURCE, &started_at)
+            .await
+            .map_err(|e| e.to_string())?;
+        return Ok(0);
+    }
+
+    if res.class() != ResultClass::Success {
+        warn!("selectBranches failed for device {}: {} {}", device.credentials_id, res.result_cd, res.result_msg);
+        return Err(format!("{} {}", res.result_cd, res.result_msg));
+    }
+
+    let branches = res.data.map(|d| d.bhf_list).unwrap_or_default();
+    let count = branches.len();
+
+    store_branches(db, &device.tin, branches).await.map_err(|e| e.to_string())?;
+    mark_synced(db, device.credentials_id, RESOURCE, &started_at)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    info!("Synced {} branches for device {}", count, device.credentials_id);
+    Ok(count)
+}
+
+/// Rows are keyed by the device's PIN rather than the `tin` KRA echoes back,
+/// so they line up with `credentials.pin`
+async fn store_branches(db: &DatabaseConnection, tin: &str, branches: Vec<BhfInfo>) -> Result<(), sea_orm::DbErr> {
+    let now = Utc::now().naive_utc();
+    let tin = encrypt_deterministic(tin);
+    let txn = db.begin().await?;
+
+    for branch in branches {
+        let model = ActiveModel {
+            tin: Set(tin.clone()),
+            bhf_id: Set(branch.bhf_id),
+            bhf_nm: Set(branch.bhf_nm),
+            bhf_stts_cd: Set(branch.bhf_stts_cd),
+            prvnc_nm: Set(branch.prvnc_nm),
+            dstrt_nm: Set(branch.dstrt_nm),
+            sctr_nm: Set(branch.sctr_nm),
+            loc_desc: Set(branch.loc_desc),
+            mgr_nm: Set(branch.mgr_nm),
+            mgr_tel_no: Set(branch.mgr_tel_no),
+            mgr_email: Set(branch.mgr_email),
+            hq_yn: Set(branch.hq_yn),
+            updated_at: Set(now),
+            ..Default::default()
+        };
+
+        Entity::insert(model)
+            .on_conflict(
+                OnConflict::columns([Column::Tin, Column::BhfId])
+                    .update_columns([
+                        Column::BhfNm,
+                        Column::BhfSttsCd,
+                        Column::PrvncNm,
+                        Column::DstrtNm,
+                        Column::SctrNm,
+                        Column::LocDesc,
+                        Column::MgrNm,
+                        Column::MgrTelNo,
+                        Column::MgrEmail,
+                        Column::HqYn,
+                        Column::UpdatedAt,
+                    ])
+                    .to_owned(),
+            )
+            .exec(&txn)
+            .await?;
+    }
+
+    txn.commit().await
+}
@@ -0,0 +1,223 @@
+use sea_orm::{
+    DatabaseConnection, EntityTrait, QueryFilter,
+    sea_query::{Alias, Expr},
+};
+use serde_json::{Value, json};
+use tracing::{info, error, warn};
+
+use crate::{
+    models::{branch_customers, branch_insurances, branch_users},
+    sales::status,
+    types::braches_data_payload::BhfSaveRes,
+    utils::retry::{MAX_RETRIES, claim, claimable, is_due, next_retry_at},
+    vscu::{client::VscuError, device::Device, result::ResultClass},
+};
+
+/// Status/retry fields to write back after one attempt
+struct Outcome {
+    status: &'static str,
+    response: Option<Value>,
+    retry_count: i64,
+    next_retry_at: Option<String>,
+}
+
+fn outcome(what: &str, id: i64, retry_count: i64, result: Result<BhfSaveRes, VscuError>) -> Outcome {
+    let retry = |response| {
+        let next = next_retry_at(retry_count + 1);
+        info!("{} {} FAILED ({}/{}), next retry at {}", what, id, retry_count + 1, MAX_RETRIES, next);
+        Outcome {
+            status: status::FAILED,
+            response,
+            retry_count: retry_count + 1,
+            next_retry_at: Some(next),
+        }
+    };
+
+    let res = match result {
+        Ok(res) => res,
+        Err(e) => {
+            error!("Failed to transmit {} {}: {}", what, id, e);
+            return retry(None);
+        }
+    };
+
+    let response = serde_json::to_value(&res).ok();
+    let status = match res.class() {
+        ResultClass::Success => status::TRANSMITTED,
+        ResultClass::Rejected => {
+            warn!("{} {} rejected by KRA: {} {}", what, id, res.result_cd, res.result_msg);
+            status::REJECTED
+        }
+        ResultClass::Retryable => return retry(response),
+    };
+
+    info!("{} {} is {}", what, id, status);
+    Outcome { status, response, retry_count, next_retry_at: None }
+}
+
+/// A branch table whose rows are sent to one `branches/saveBranche*` endpoint
+pub trait BranchTable: EntityTrait {
+    /// Name used in logs
+    const WHAT: &'static str;
+    /// VSCU path the rows are posted to
+    const ENDPOINT: &'static str;
+
+    /// Request body for `record`, sent as `device`
+    fn payload(record: &Self::Model, device: &Device) -> Value;
+
+    /// (id, retry_count, api_key, next_retry_at) of `record`
+    fn queue_state(record: &Self::Model) -> (i64, i64, Option<&str>, Option<&str>);
+}
+
+impl BranchTable for branch_customers::Entity {
+    const WHAT: &'static str = "Branch customer";
+    const ENDPOINT: &'static str = "branches/saveBrancheCustomers";
+
+    fn payload(record: &Self::Model, device: &Device) -> Value {
+        json!({
+            "tin": device.tin,
+            "bhfId": device.bhf_id,
+            "custNo": record.cust_no,
+            "custTin": record.cust_tin,
+            "custNm": record.cust_nm,
+            "adrs": record.adrs,
+            "telNo": record.tel_no,
+            "email": record.email,
+            "faxNo": record.fax_no,
+            "useYn": record.use_yn,
+            "remark": record.remark,
+            "regrNm": record.regr_nm,
+            "regrId": record.regr_id,
+            "modrNm": record.modr_nm,
+            "modrId": record.modr_id,
+        })
+    }
+
+    fn queue_state(r: &Self::Model) -> (i64, i64, Option<&str>, Option<&str>) {
+        (r.id, r.retry_count, r.api_key.as_deref(), r.next_retry_at.as_deref())
+    }
+}
+
+impl BranchTable for branch_users::Entity {
+    const WHAT: &'static str = "Branch user";
+    const ENDPOINT: &'static str = "branches/saveBrancheUsers";
+
+    fn payload(record: &Self::Model, device: &Device) -> Value {
+        json!({
+            "tin": device.tin,
+            "bhfId": device.bhf_id,
+            "userId": record.user_id,
+            "userNm": record.user_nm,
+            "pwd": record.pwd,
+            "adrs": record.adrs,
+            "cntc": record.cntc,
+            "authCd": record.auth_cd,
+            "remark": record.remark,
+            "useYn": record.use_yn,
+            "regrNm": record.regr_nm,
+            "regrId": record.regr_id,
+            "modrNm": record.modr_nm,
+            "modrId": record.modr_id,
+        })
+    }
+
+    fn queue_state(r: &Self::Model) -> (i64, i64, Option<&str>, Option<&str>) {
+        (r.id, r.retry_count, r.api_key.as_deref(), r.next_retry_at.as_deref())
+    }
+}
+
+impl BranchTable for branch_insurances::Entity {
+    const WHAT: &'static str = "Branch insurance";
+    const ENDPOINT: &'static str = "branches/saveBrancheInsurances";
+
+    fn payload(record: &Self::Model, device: &Device) -> Value {
+        json!({
+            "tin": device.tin,
+            "bhfId": device.bhf_id,
+            "isrccCd": record.isrcc_cd,
+            "isrccNm": record.isrcc_nm,
+            "isrcRt": record.isrc_rt,
+            "useYn": record.use_yn,
+            "regrNm": record.regr_nm,
+            "regrId": record.regr_id,
+            "modrNm": record.modr_nm,
+            "modrId": record.modr_id,
+        })
+    }
+
+    fn queue_state(r: &Self::Model) -> (i64, i64, Option<&str>, Option<&str>) {
+        (r.id, r.retry_count, r.api_key.as_deref(), r.next_retry_at.as_deref())
+    }
+}
+
+/// Send row `id` of table `T` to its endpoint and store the outcome
+pub async fn transmit<T: BranchTable>(db: &DatabaseConnection, device: &Device, id: i64) {
+    let record = match claim::<T, _>(db, id, &[status::RECEIVED, status::FAILED]).await {
+        Ok(Some(r)) => r,
+        Ok(None) => {
+            info!("{} {} is already being sent, skipped", T::WHAT, id);
+            return;
+        }
+        Err(e) => {
+            error!("Failed to lock {} {}: {}", T::WHAT.to_lowercase(), id, e);
+            return;
+        }
+    };
+
+    let (_, retry_count, _, _) = T::queue_state(&record);
+    let result = device.client.save_branch_record(T::ENDPOINT, &T::payload(&record, device)).await;
+    let outcome = outcome(T::WHAT, id, retry_count, result);
+
+    let mut update = T::update_many()
+        .col_expr(Alias::new("status"), Expr::value(outcome.status))
+        .col_expr(Alias::new("retry_count"), Expr::value(outcome.retry_count))
+        .col_expr(Alias::new("next_retry_at"), Expr::value(outcome.next_retry_at))
+        .filter(Expr::col(Alias::new("id")).eq(id));
+    if let Some(response) = outcome.response {
+        update = update.col_expr(Alias::new("response"), Expr::value(response));
+    }
+    if let Err(e) = update.exec(db).await {
+        error!("Failed to update {} {}: {}", T::WHAT.to_lowercase(), id, e);
+    }
+}
+
+/// Retry FAILED/stuck branch customers, users and insurances whose backoff
+/// has elapsed
+pub async fn retry_failed_branch_data(db: &DatabaseConnection) -> Result<(), sea_orm::DbErr> {
+    retry_table::<branch_customers::Entity>(db).await?;
+    retry_table::<branch_users::Entity>(db).await?;
+    retry_table::<branch_insurances::Entity>(db).await
+}
+
+async fn retry_table<T: BranchTable>(db: &DatabaseConnection) -> Result<(), sea_orm::DbErr> {
+    let records = T::find()
+        .filter(claimable(&[status::FAILED]))
+        .filter(Expr::col(Alias::new("retry_count")).lt(MAX_RETRIES))
+        .filter(Expr::col(Alias::new("api_key")).is_not_null())
+        .all(db)
+        .await?;
+
+    for record in records {
+        let (id, _, api_key, next_retry_at) = T::queue_state(&record);
+        if let Some(device) = retry_device(db, api_key, next_retry_at).await {
+            transmit::<T>(db, &device, id).await;
+        }
+    }
+
+    Ok(())
+}
+
+/// Device to retry a record with, if its backoff has elapsed
+async fn retry_device(db: &DatabaseConnection, api_key: Option<&str>, next_retry_at: Option<&str>) -> Option<Device> {
+    if !is_due(next_retry_at) {
+        return None;
+    }
+
+    match Device::find_by_api_key(db, api_key?).await {
+        Ok(device) => Some(device),
+        Err(e) => {
+            error!("Cannot retry branch record: {}", e);
+            None
+        }
+    }
+}
@@ -0,0 +1,2 @@
+pub mod route_codes;
+pub mod sync_codes;
@@ -0,0 +1,85 @@
+use std::sync::Arc;
+
+use axum::{
+    Json, Router,
+    extract::{Query, State},
+    http::StatusCode,
+    response::IntoResponse,
+    routing::get,
+};
+use axum_extra::TypedHeader;
+use headers::{Authorization, authorization::Bearer};
+use sea_orm::{ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter, QueryOrder};
+use serde_json::json;
+
+use crate::{
+    models::{
+        code_class::{Column as ClassColumn, Entity as ClassEntity},
+        code_detail::{Column as DetailColumn, Entity as DetailEntity},
+    },
+    stock_management::route_stock_master::error_response,
+    types::codes::CodeQuery,
+    utils::bearer::bearer_resolver,
+};
+
+// ── Router ─────────────────────────────────────────────────────────────────────
+pub fn codes_router(db: Arc<DatabaseConnection>) -> Router {
+    Router::new()
+        .route("/", get(list_codes))
+        .with_state(db)
+}
+
+// ── Handlers ───────────────────────────────────────────────────────────────────
+
+/// Cached KRA code lists, optionally limited to one class (`?cd_cls=04`)
+async fn list_codes(
+    TypedHeader(auth): TypedHeader<Authorization<Bearer>>,
+    State(db): State<Arc<DatabaseConnection>>,
+    Query(query): Query<CodeQuery>,
+) -> impl IntoResponse {
+    if let Err(e) = bearer_resolver(auth.token(), db.as_ref()).await {
+        return error_response(&e, StatusCode::UNAUTHORIZED);
+    }
+
+    let mut classes = ClassEntity::find().order_by_asc(ClassColumn::CdCls);
+    let mut details = DetailEntity::find()
+        .order_by_asc(DetailColumn::CdCls)
+        .order_by_asc(DetailColumn::SrtOrd);
+
+    if let Some(cd_cls) = &query.cd_cls {
+        classes = classes.filter(ClassColumn::CdCls.eq(cd_cls));
+        details = details.filter(DetailColumn::CdCls.eq(cd_cls));
+    }
+
+    let classes = match classes.all(db.as_ref()).await {
+        Ok(c) => c,
+        Err(e) => return error_response(&format!("Failed to fetch code classes: {e}"), StatusCode::INTERNAL_SERVER_ERROR),
+    };
+    let details = match details.all(db.as_ref()).await {
+        Ok(d) => d,
+        Err(e) => return error_response(&format!("Failed to fetch codes: {e}"), StatusCode::INTERNAL_SERVER_ERROR),
+    };
+
+    let data: Vec<_> = classes
+        .into_iter()
+        .map(|class| {
+            let codes: Vec<_> = details.iter().filter(|d| d.cd_cls == class.cd_cls).collect();
+            json!({
+                "cdCls": class.cd_cls,
+                "cdClsNm": class.cd_cls_nm,
+                "cdClsDesc": class.cd_cls_desc,
+                "useYn": class.use_yn,
+                "dtlList": codes,
+            })
+        })
+        .collect();
+
+    (
+        StatusCode::OK,
+        Json(json!({
+            "resultCd": "000",
+            "resultMsg": "Success",
+            "data": data,
+        })),
+    )
+}
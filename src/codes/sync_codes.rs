@@ -0,0 +1,127 @@
+use sea_orm::{
+    ActiveValue::Set, DatabaseConnection, EntityTrait, TransactionTrait, sea_query::OnConflict,
+};
+use chrono::Utc;
+use tracing::{info, warn};
+
+use crate::{
+    models::{
+        code_class::{ActiveModel as ClassActiveModel, Column as ClassColumn, Entity as ClassEntity},
+        code_detail::{ActiveModel as DetailActiveModel, Column as DetailColumn, Entity as DetailEntity},
+    },
+    types::codes::CodeClassItem,
+    utils::sync_state::{last_req_dt, mark_synced, now_req_dt},
+    vscu::{device::Device, result::ResultClass},
+};
+
+pub const RESOURCE: &str = "codes";
+
+/// Pull code lists changed since the device's last sync and upsert them
+pub async fn sync_codes(db: &DatabaseConnection, device: &Device) -> Result<usize, String> {
+    let since = last_req_dt(db, device.credentials_id, RESOURCE)
+        .await
+        .map_err(|e| e.to_string())?;
+    let started_at = now_req_dt();
+
+    let res = device
+        .client
+        .select_codes(&device.select_request(&since))
+        .await
+        .map_err(|e| e.to_string())?;
+
+    if res.is_empty_search() {
+        info!("No code changes for device {} since {}", device.credentials_id, since);
+        mark_synced(db, device.credentials_id, RESOURCE, &started_at)
+            .await
+            .map_err(|e| e.to_string())?;
+        return Ok(0);
+    }
+
+    if res.class() != ResultClass::Success {
+        warn!("selectCodes failed for device {}: {} {}", device.credentials_id, res.result_cd, res.result_msg);
+        return Err(format!("{} {}", res.result_cd, res.result_msg));
+    }
+
+    let classes = res.data.map(|d| d.cls_list).unwrap_or_default();
+    let count = classes.len();
+
+    store_classes(db, classes).await.map_err(|e| e.to_string())?;
+    mark_synced(db, device.credentials_id, RESOURCE, &started_at)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    info!("Synced {} code classes for device {}", count, device.credentials_id);
+    Ok(count)
+}
+
+async fn store_classes(db: &DatabaseConnection, classes: Vec<CodeClassItem>) -> Result<(), sea_orm::DbErr> {
+    let now = Utc::now().naive_utc();
+    let txn = db.begin().await?;
+
+    for class in classes {
+        let model = ClassActiveModel {
+            cd_cls: Set(class.cd_cls.clone()),
+            cd_cls_nm: Set(class.cd_cls_nm),
+            cd_cls_desc: Set(class.cd_cls_desc),
+            use_yn: Set(class.use_yn),
+            user_dfn_nm1: Set(class.user_dfn_nm1),
+            user_dfn_nm2: Set(class.user_dfn_nm2),
+            user_dfn_nm3: Set(class.user_dfn_nm3),
+            updated_at: Set(now),
+            ..Default::default()
+        };
+
+        ClassEntity::insert(model)
+            .on_conflict(
+                OnConflict::column(ClassColumn::CdCls)
+                    .update_columns([
+                        ClassColumn::CdClsNm,
+                        ClassColumn::CdClsDesc,
+                        ClassColumn::UseYn,
+                        ClassColumn::UserDfnNm1,
+                        ClassColumn::UserDfnNm2,
+                        ClassColumn::UserDfnNm3,
+                        ClassColumn::UpdatedAt,
+                    ])
+                    .to_owned(),
+            )
+            .exec(&txn)
+            .await?;
+
+        for detail in class.dtl_list {
+            let model = DetailActiveModel {
+                cd_cls: Set(class.cd_cls.clone()),
+                cd: Set(detail.cd),
+                cd_nm: Set(detail.cd_nm),
+                cd_desc: Set(detail.cd_desc),
+                use_yn: Set(detail.use_yn),
+                srt_ord: Set(detail.srt_ord),
+                user_dfn_cd1: Set(detail.user_dfn_cd1),
+                user_dfn_cd2: Set(detail.user_dfn_cd2),
+                user_dfn_cd3: Set(detail.user_dfn_cd3),
+                updated_at: Set(now),
+                ..Default::default()
+            };
+
+            DetailEntity::insert(model)
+                .on_conflict(
+                    OnConflict::columns([DetailColumn::CdCls, DetailColumn::Cd])
+                        .update_columns([
+                            DetailColumn::CdNm,
+                            DetailColumn::CdDesc,
+                            DetailColumn::UseYn,
+                            DetailColumn::SrtOrd,
+                            DetailColumn::UserDfnCd1,
+                            DetailColumn::UserDfnCd2,
+                            DetailColumn::UserDfnCd3,
+                            DetailColumn::UpdatedAt,
+                        ])
+                        .to_owned(),
+                )
+                .exec(&txn)
+                .await?;
+        }
+    }
+
+    txn.commit().await
+}
@@ -0,0 +1,69 @@
+use std::sync::Arc;
+
+use axum::{
+    Json, Router,
+    extract::{Path, State},
+    http::StatusCode,
+    response::IntoResponse,
+    routing::get,
+};
+use axum_extra::TypedHeader;
+use headers::{Authorization, authorization::Bearer};
+use sea_orm::DatabaseConnection;
+use serde_json::json;
+
+use crate::{
+    customers::verify::{PinStatus, verify_pin},
+    stock_management::route_stock_master::error_response,
+    types::salespayloadtype::AuthUser,
+    utils::bearer::bearer_resolver,
+    vscu::device::Device,
+};
+
+pub fn customers_router(db: Arc<DatabaseConnection>) -> Router {
+    Router::new()
+        .route("/verify/{pin}", get(verify_customer))
+        .with_state(db)
+}
+
+/// Whether KRA knows the customer PIN, and who it belongs to
+async fn verify_customer(
+    TypedHeader(auth): TypedHeader<Authorization<Bearer>>,
+    State(db): State<Arc<DatabaseConnection>>,
+    Path(pin): Path<String>,
+) -> impl IntoResponse {
+    let user: AuthUser = match bearer_resolver(auth.token(), db.as_ref()).await {
+        Ok(val) => match serde_json::from_value(val) {
+            Ok(u) => u,
+            Err(e) => return error_response(&format!("Failed to parse user: {e}"), StatusCode::INTERNAL_SERVER_ERROR),
+        },
+        Err(e) => return error_response(&e, StatusCode::UNAUTHORIZED),
+    };
+
+    let device = match Device::from_user(&user) {
+        Ok(d) => d,
+        Err(e) => return error_response(&e, StatusCode::INTERNAL_SERVER_ERROR),
+    };
+
+    match verify_pin(&device, &pin).await {
+        Ok(PinStatus::Known(customer)) => (
+            StatusCode::OK,
+            Json(json!({
+                "resultCd": "000",
+                "resultMsg": "Success",
+                "valid": true,
+                "data": customer,
+            })),
+        ),
+        Ok(PinStatus::Unknown) => (
+            StatusCode::OK,
+            Json(json!({
+                "resultCd": "001",
+                "resultMsg": "PIN is not registered with KRA",
+                "valid": false,
+                "data": null,
+            })),
+        ),
+        Err(e) => error_response(&format!("PIN verification failed: {e}"), StatusCode::BAD_GATEWAY),
+    }
+}
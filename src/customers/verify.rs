@@ -0,0 +1,147 @@
+use std::{
+    collections::HashMap,
+    env,
+    sync::{Mutex, OnceLock},
+    time::{Duration, Instant},
+};
+
+use serde_json::{Value, json};
+use tracing::{info, warn};
+
+use crate::{
+    types::customers::{CustomerInfo, PinPolicy},
+    vscu::{device::Device, result::ResultClass},
+};
+
+/// How long a KRA answer for a PIN is reused, overridable with
+/// `CUSTOMER_PIN_CACHE_TTL_SECS`
+const DEFAULT_TTL_SECS: u64 = 24 * 60 * 60;
+
+/// Most answers kept at once; the oldest is dropped to make room
+const MAX_CACHED_PINS: usize = 10_000;
+
+/// KRA's answer for a PIN
+#[derive(Debug, Clone)]
+pub enum PinStatus {
+    Known(CustomerInfo),
+    Unknown,
+}
+
+/// Answers by (VSCU base url, PIN), so sandbox and production never mix
+type PinCache = Mutex<HashMap<(String, String), (Instant, PinStatus)>>;
+
+static CACHE: OnceLock<PinCache> = OnceLock::new();
+
+fn cache() -> &'static PinCache {
+    CACHE.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+fn ttl() -> Duration {
+    let secs = env::var("CUSTOMER_PIN_CACHE_TTL_SECS")
+        .ok()
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(DEFAULT_TTL_SECS);
+    Duration::from_secs(secs)
+}
+
+/// Keep `found` for `key`. Expired answers are pruned first, and when the
+/// cache is still full the oldest answer makes room.
+fn remember(key: (String, String), found: PinStatus) {
+    let mut cache = cache().lock().unwrap();
+    let ttl = ttl();
+    cache.retain(|_, (at, _)| at.elapsed() < ttl);
+
+    if cache.len() >= MAX_CACHED_PINS
+        && let Some(oldest) = cache.iter().min_by_key(|(_, (at, _))| *at).map(|(k, _)| k.clone())
+    {
+        cache.remove(&oldest);
+    }
+
+    cache.insert(key, (Instant::now(), found));
+}
+
+/// Look a customer PIN up with `customers/selectCustomer`. Only definite
+/// answers are cached; transport errors, retryable codes and rejected
+/// requests (which say nothing about the PIN) are returned as Err.
+pub async fn verify_pin(device: &Device, pin: &str) -> Result<PinStatus, String> {
+    let pin = pin.trim().to_uppercase();
+    let key = (device.client.base_url().to_string(), pin.clone());
+
+    if let Some((at, found)) = cache().lock().unwrap().get(&key)
+        && at.elapsed() < ttl()
+    {
+        return Ok(found.clone());
+    }
+
+    let res = device
+        .client
+        .select_customer(&json!({
+            "tin": device.tin,
+            "bhfId": device.bhf_id,
+            "custmTin": pin,
+        }))
+        .await
+        .map_err(|e| e.to_string())?;
+
+    let found = if res.is_empty_search() {
+        PinStatus::Unknown
+    } else {
+        match res.class() {
+            ResultClass::Success => match res.data.and_then(|d| d.cust_list.into_iter().next()) {
+                Some(customer) => PinStatus::Known(customer),
+                None => PinStatus::Unknown,
+            },
+            ResultClass::Rejected | ResultClass::Retryable => {
+                return Err(format!("{} {}", res.result_cd, res.result_msg));
+            }
+        }
+    };
+
+    info!("Customer PIN {} is {}", pin, if matches!(found, PinStatus::Known(_)) { "known" } else { "unknown" });
+    remember(key, found.clone());
+    Ok(found)
+}
+
+/// Apply `policy` to the customer PINs of a request. Empty PINs (walk-in
+/// customers) are skipped. Returns the problems to report in the response,
+/// or Err with them when the policy is `Reject` and a PIN is unknown.
+/// A PIN that could not be checked (VSCU down) never blocks the request.
+pub async fn check_pins(
+    device: &Device,
+    policy: PinPolicy,
+    pins: &[&str],
+) -> Result<Vec<Value>, Vec<Value>> {
+    if policy == PinPolicy::Off {
+        return Ok(Vec::new());
+    }
+
+    let mut problems = Vec::new();
+    let mut unknown = false;
+    let mut seen = Vec::new();
+
+    for pin in pins {
+        let pin = pin.trim();
+        if pin.is_empty() || seen.contains(&pin) {
+            continue;
+        }
+        seen.push(pin);
+
+        match verify_pin(device, pin).await {
+            Ok(PinStatus::Known(_)) => {}
+            Ok(PinStatus::Unknown) => {
+                unknown = true;
+                problems.push(json!({ "custTin": pin, "pinCheck": "UNKNOWN" }));
+            }
+            Err(e) => {
+                warn!("Could not verify customer PIN {}: {}", pin, e);
+                problems.push(json!({ "custTin": pin, "pinCheck": "UNVERIFIED", "message": e }));
+            }
+        }
+    }
+
+    if unknown && policy == PinPolicy::Reject {
+        Err(problems)
+    } else {
+        Ok(problems)
+    }
+}
@@ -0,0 +1,4 @@
+pub mod routing;
+pub mod status;
+pub mod sync_imports;
+pub mod transmit_imports;
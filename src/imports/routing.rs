@@ -0,0 +1,255 @@
+use std::sync::Arc;
+
+use axum::{
+    Json, Router,
+    extract::{Path, Query, State},
+    http::StatusCode,
+    response::IntoResponse,
+    routing::{get, post},
+};
+use axum_extra::TypedHeader;
+use chrono::Utc;
+use headers::{Authorization, authorization::Bearer};
+use sea_orm::{ActiveValue::Set, ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter, QueryOrder};
+use serde_json::{Value, json};
+use tracing::info;
+
+use crate::{
+    imports::{
+        status::{APPROVED, CANCELLED, PENDING},
+        sync_imports::sync_imports,
+        transmit_imports::transmit_import,
+    },
+    models::{import_item, product_save_items},
+    sales::status,
+    stock_management::route_stock_master::error_response,
+    types::{
+        imports::{ImportApproveReq, ImportQuery, ImportRejectReq},
+        salespayloadtype::AuthUser,
+    },
+    utils::bearer::bearer_resolver,
+    vscu::device::Device,
+};
+
+pub fn imports_router(db: Arc<DatabaseConnection>) -> Router {
+    Router::new()
+        .route("/", get(list_imports))
+        .route("/sync", post(sync_now))
+        .route("/{id}/approve", post(approve_import))
+        .route("/{id}/reject", post(reject_import))
+        .with_state(db)
+}
+
+async fn authorize(
+    token: &str,
+    db: &DatabaseConnection,
+) -> Result<(AuthUser, Device), (StatusCode, Json<Value>)> {
+    let user: AuthUser = match bearer_resolver(token, db).await {
+        Ok(val) => serde_json::from_value(val).map_err(|e| {
+            error_response(&format!("Failed to parse user: {e}"), StatusCode::INTERNAL_SERVER_ERROR)
+        })?,
+        Err(e) => return Err(error_response(&e, StatusCode::UNAUTHORIZED)),
+    };
+
+    let device = Device::from_user(&user).map_err(|e| error_response(&e, StatusCode::INTERNAL_SERVER_ERROR))?;
+
+    Ok((user, device))
+}
+
+/// Import lines of the device, newest declaration first
+async fn list_imports(
+    TypedHeader(auth): TypedHeader<Authorization<Bearer>>,
+    State(db): State<Arc<DatabaseConnection>>,
+    Query(query): Query<ImportQuery>,
+) -> impl IntoResponse {
+    let (user, _) = match authorize(auth.token(), db.as_ref()).await {
+        Ok(found) => found,
+        Err(res) => return res,
+    };
+
+    let mut select = import_item::Entity::find().filter(import_item::Column::CredentialsId.eq(user.id));
+    if let Some(status) = query.status {
+        select = select.filter(import_item::Column::Status.eq(status));
+    }
+
+    match select
+        .order_by_desc(import_item::Column::DclDe)
+        .order_by_asc(import_item::Column::TaskCd)
+        .order_by_asc(import_item::Column::ItemSeq)
+        .all(db.as_ref())
+        .await
+    {
+        Ok(items) => (
+            StatusCode::OK,
+            Json(json!({
+                "resultCd": "000",
+                "resultMsg": "Success",
+                "data": items,
+            })),
+        ),
+        Err(e) => error_response(&format!("Failed to fetch import items: {e}"), StatusCode::INTERNAL_SERVER_ERROR),
+    }
+}
+
+/// Pull new import declarations now instead of waiting for the background sync
+async fn sync_now(
+    TypedHeader(auth): TypedHeader<Authorization<Bearer>>,
+    State(db): State<Arc<DatabaseConnection>>,
+) -> impl IntoResponse {
+    let (_, device) = match authorize(auth.token(), db.as_ref()).await {
+        Ok(found) => found,
+        Err(res) => return res,
+    };
+
+    match sync_imports(db.as_ref(), &device).await {
+        Ok(stored) => (
+            StatusCode::OK,
+            Json(json!({
+                "resultCd": "000",
+                "resultMsg": "Import items synced",
+                "stored": stored,
+            })),
+        ),
+        Err(e) => error_response(&format!("Import sync failed: {e}"), StatusCode::BAD_GATEWAY),
+    }
+}
+
+/// Approve an import line as one of our `item_master` items
+async fn approve_import(
+    TypedHeader(auth): TypedHeader<Authorization<Bearer>>,
+    State(db): State<Arc<DatabaseConnection>>,
+    Path(id): Path<i32>,
+    Json(payload): Json<ImportApproveReq>,
+) -> impl IntoResponse {
+    let (user, device) = match authorize(auth.token(), db.as_ref()).await {
+        Ok(found) => found,
+        Err(res) => return res,
+    };
+
+    let record = match load_pending(db.as_ref(), user.id, id).await {
+        Ok(r) => r,
+        Err(res) => return res,
+    };
+
+    let item = match product_save_items::Entity::find()
+        .filter(product_save_items::Column::Tin.eq(&user.pin))
+        .filter(product_save_items::Column::BhfId.eq(&user.branch_id))
+        .filter(product_save_items::Column::ItemCd.eq(&payload.item_cd))
+        .one(db.as_ref())
+        .await
+    {
+        Ok(Some(item)) => item,
+        Ok(None) => {
+            return error_response(
+                &format!("Item {} is not registered for this branch", payload.item_cd),
+                StatusCode::BAD_REQUEST,
+            )
+        }
+        Err(e) => return error_response(&format!("Failed to fetch item: {e}"), StatusCode::INTERNAL_SERVER_ERROR),
+    };
+
+    let mut model: import_item::ActiveModel = record.into();
+    model.impt_item_stts_cd = Set(APPROVED.to_string());
+    model.item_cd = Set(Some(item.item_cd));
+    model.item_cls_cd = Set(Some(item.item_cls_cd));
+    model.remark = Set(payload.remark);
+    model.modr_nm = Set(Some(payload.modr_nm));
+    model.modr_id = Set(Some(payload.modr_id));
+    model.status = Set(status::RECEIVED.to_string());
+    model.updated_at = Set(Some(Utc::now().naive_utc()));
+
+    decide(db, device, model, "Import item approved and queued for KRA").await
+}
+
+/// Reject an import line
+async fn reject_import(
+    TypedHeader(auth): TypedHeader<Authorization<Bearer>>,
+    State(db): State<Arc<DatabaseConnection>>,
+    Path(id): Path<i32>,
+    Json(payload): Json<ImportRejectReq>,
+) -> impl IntoResponse {
+    let (user, device) = match authorize(auth.token(), db.as_ref()).await {
+        Ok(found) => found,
+        Err(res) => return res,
+    };
+
+    let record = match load_pending(db.as_ref(), user.id, id).await {
+        Ok(r) => r,
+        Err(res) => return res,
+    };
+
+    let mut model: import_item::ActiveModel = record.into();
+    model.impt_item_stts_cd = Set(CANCELLED.to_string());
+    model.remark = Set(payload.remark);
+    model.modr_nm = Set(Some(payload.modr_nm));
+    model.modr_id = Set(Some(payload.modr_id));
+    model.status = Set(status::RECEIVED.to_string());
+    model.updated_at = Set(Some(Utc::now().naive_utc()));
+
+    decide(db, device, model, "Import item rejected and queued for KRA").await
+}
+
+/// Store the decision and send it to KRA in the background. The update only
+/// applies while the line is still PENDING, so of two concurrent decisions
+/// the second gets a conflict.
+async fn decide(
+    db: Arc<DatabaseConnection>,
+    device: Device,
+    model: import_item::ActiveModel,
+    message: &str,
+) -> (StatusCode, Json<Value>) {
+    let id = model.id.clone().unwrap();
+    let updated = import_item::Entity::update_many()
+        .set(model)
+        .filter(import_item::Column::Id.eq(id))
+        .filter(import_item::Column::Status.eq(PENDING))
+        .exec_with_returning(db.as_ref())
+        .await;
+
+    let saved = match updated {
+        Ok(rows) => match rows.into_iter().next() {
+            Some(saved) => saved,
+            None => return error_response("Import item was already decided", StatusCode::CONFLICT),
+        },
+        Err(e) => return error_response(&format!("Failed to update import item: {e}"), StatusCode::INTERNAL_SERVER_ERROR),
+    };
+
+    info!("Import item {} decided: {}", saved.id, saved.impt_item_stts_cd);
+
+    let id = saved.id;
+    tokio::spawn(async move {
+        transmit_import(db.as_ref(), &device, id).await;
+    });
+
+    (
+        StatusCode::OK,
+        Json(json!({
+            "resultCd": "000",
+            "resultMsg": message,
+            "data": saved,
+        })),
+    )
+}
+
+/// The device's import line `id`, if it is still waiting for a decision
+async fn load_pending(
+    db: &DatabaseConnection,
+    credentials_id: i32,
+    id: i32,
+) -> Result<import_item::Model, (StatusCode, Json<Value>)> {
+    let record = import_item::Entity::find_by_id(id)
+        .filter(import_item::Column::CredentialsId.eq(credentials_id))
+        .one(db)
+        .await
+        .map_err(|e| error_response(&format!("Failed to fetch import item: {e}"), StatusCode::INTERNAL_SERVER_ERROR))?
+        .ok_or_else(|| error_response("Import item not found", StatusCode::NOT_FOUND))?;
+
+    if record.status != PENDING {
+        return Err(error_response(
+            &format!("Import item was already decided (status {})", record.status),
+            StatusCode::CONFLICT,
+        ));
+    }
+
+    Ok(record)
+}
@@ -0,0 +1,14 @@
+// import_item.status follows purchases: PENDING until the branch decides,
+// then the sales transmission states (RECEIVED ──► PROCESSING ──► ...).
+pub use crate::purchases::status::PENDING;
+
+// ========== KRA import item status codes (imptItemSttsCd) ==========
+// "2" (waiting) is what KRA reports for undecided lines
+
+pub const APPROVED: &str = "3";
+pub const CANCELLED: &str = "4";
+
+/// Whether KRA already holds a decision for the line (made on the portal)
+pub fn is_decided(impt_item_stts_cd: &str) -> bool {
+    impt_item_stts_cd == APPROVED || impt_item_stts_cd == CANCELLED
+}
@@ -0,0 +1,124 @@
+use chrono::Utc;
+use sea_orm::{
+    ActiveModelTrait, ActiveValue::Set, ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter,
+    TransactionTrait,
+};
+use tracing::{info, warn};
+
+use crate::{
+    imports::status::{PENDING, is_decided},
+    models::import_item::{ActiveModel, Column, Entity},
+    sales::status::TRANSMITTED,
+    types::imports::ImportItemInfo,
+    utils::sync_state::{last_req_dt, mark_synced, now_req_dt},
+    vscu::{device::Device, result::ResultClass},
+};
+
+pub const RESOURCE: &str = "imports";
+
+/// Pull import lines declared for the device since its last sync. Returns how
+/// many new lines were stored.
+pub async fn sync_imports(db: &DatabaseConnection, device: &Device) -> Result<usize, String> {
+    let since = last_req_dt(db, device.credentials_id, RESOURCE)
+        .await
+        .map_err(|e| e.to_string())?;
+    let started_at = now_req_dt();
+
+    let res = device
+        .client
+        .select_import_items(&device.select_request(&since))
+        .await
+        .map_err(|e| e.to_string())?;
+
+    if res.is_empty_search() {
+        info!("No import items for device {} since {}", device.credentials_id, since);
+        mark_synced(db, device.credentials_id, RESOURCE, &started_at)
+            .await
+            .map_err(|e| e.to_string())?;
+        return Ok(0);
+    }
+
+    if res.class() != ResultClass::Success {
+        warn!("selectImportItems failed for device {}: {} {}", device.credentials_id, res.result_cd, res.result_msg);
+        return Err(format!("{} {}", res.result_cd, res.result_msg));
+    }
+
+    let items = res.data.map(|d| d.item_list).unwrap_or_default();
+    let stored = store_items(db, device.credentials_id, items)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    mark_synced(db, device.credentials_id, RESOURCE, &started_at)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    info!("Stored {} new import items for device {}", stored, device.credentials_id);
+    Ok(stored)
+}
+
+/// Insert unseen lines. A known line that is still PENDING here but was
+/// decided on the KRA portal takes KRA's decision.
+async fn store_items(
+    db: &DatabaseConnection,
+    credentials_id: i32,
+    items: Vec<ImportItemInfo>,
+) -> Result<usize, sea_orm::DbErr> {
+    let txn = db.begin().await?;
+    let mut stored = 0;
+
+    for item in items {
+        let known = Entity::find()
+            .filter(Column::CredentialsId.eq(credentials_id))
+            .filter(Column::TaskCd.eq(&item.task_cd))
+            .filter(Column::DclDe.eq(&item.dcl_de))
+            .filter(Column::ItemSeq.eq(item.item_seq))
+            .one(&txn)
+            .await?;
+
+        let status = if is_decided(&item.impt_item_stts_cd) { TRANSMITTED } else { PENDING };
+
+        if let Some(existing) = known {
+            if existing.status == PENDING && status == TRANSMITTED {
+                let mut model: ActiveModel = existing.into();
+                model.impt_item_stts_cd = Set(item.impt_item_stts_cd);
+                model.status = Set(status.to_string());
+                model.updated_at = Set(Some(Utc::now().naive_utc()));
+                model.update(&txn).await?;
+            }
+            continue;
+        }
+
+        ActiveModel {
+            credentials_id: Set(credentials_id),
+            task_cd: Set(item.task_cd),
+            dcl_de: Set(item.dcl_de),
+            item_seq: Set(item.item_seq),
+            dcl_no: Set(item.dcl_no),
+            hs_cd: Set(item.hs_cd),
+            item_nm: Set(item.item_nm),
+            orgn_nat_cd: Set(item.orgn_nat_cd),
+            expt_nat_cd: Set(item.expt_nat_cd),
+            pkg: Set(item.pkg),
+            pkg_unit_cd: Set(item.pkg_unit_cd),
+            qty: Set(item.qty),
+            qty_unit_cd: Set(item.qty_unit_cd),
+            tot_wt: Set(item.tot_wt),
+            net_wt: Set(item.net_wt),
+            spplr_nm: Set(item.spplr_nm),
+            agnt_nm: Set(item.agnt_nm),
+            invc_fcur_amt: Set(item.invc_fcur_amt),
+            invc_fcur_cd: Set(item.invc_fcur_cd),
+            invc_fcur_excrt: Set(item.invc_fcur_excrt),
+            impt_item_stts_cd: Set(item.impt_item_stts_cd),
+            status: Set(status.to_string()),
+            ..Default::default()
+        }
+        .insert(&txn)
+        .await?;
+
+        stored += 1;
+    }
+
+    txn.commit().await?;
+    Ok(stored)
+}
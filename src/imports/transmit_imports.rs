@@ -0,0 +1,155 @@
+use chrono::Utc;
+use sea_orm::{ActiveModelTrait, ActiveValue::Set, ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter};
+use serde_json::{Value, json};
+use tracing::{info, error, warn};
+
+use crate::{
+    models::import_item::{ActiveModel, Column, Entity, Model},
+    sales::status,
+    utils::retry::{MAX_RETRIES, claim, is_due, next_retry_at, retryable},
+    vscu::{device::Device, result::{ResultClass, VscuResponse}},
+};
+
+/// Send an approved/rejected import line to `imports/updateImportItems`
+pub async fn transmit_import(db: &DatabaseConnection, device: &Device, id: i32) {
+    match Entity::find_by_id(id).one(db).await {
+        Ok(Some(record)) => send_and_record(db, device, record).await,
+        Ok(None) => error!("Import item {} not found", id),
+        Err(e) => error!("Failed to fetch import item {}: {}", id, e),
+    }
+}
+
+/// Retry FAILED/stuck import decisions whose backoff has elapsed, and ones
+/// whose first send never happened
+pub async fn retry_failed_imports(db: &DatabaseConnection) -> Result<(), sea_orm::DbErr> {
+    let records = Entity::find()
+        .filter(retryable(&["updated_at", "created_at"]))
+        .filter(Column::RetryCount.lt(MAX_RETRIES))
+        .all(db)
+        .await?;
+
+    for record in records {
+        if !is_due(record.next_retry_at.as_deref()) {
+            continue;
+        }
+
+        let device = match Device::find(db, record.credentials_id).await {
+            Ok(d) => d,
+            Err(e) => {
+                error!("Cannot retry import item {}: {}", record.id, e);
+                continue;
+            }
+        };
+
+        info!("🔄 Retrying import item {} (attempt {}/{})", record.id, record.retry_count + 1, MAX_RETRIES);
+        send_and_record(db, &device, record).await;
+    }
+
+    Ok(())
+}
+
+async fn send_and_record(db: &DatabaseConnection, device: &Device, record: Model) {
+    let id = record.id;
+    let retry_count = record.retry_count;
+
+    let record = match claim::<Entity, _>(db, id, &[status::RECEIVED, status::FAILED]).await {
+        Ok(Some(r)) => r,
+        Ok(None) => {
+            info!("Import item {} is already being sent, skipped", id);
+            return;
+        }
+        Err(e) => {
+            error!("Failed to lock import item {}: {}", id, e);
+            return;
+        }
+    };
+
+    let kra_payload = build_payload(device, &record);
+
+    let outcome = match device.client.update_import_items(&kra_payload).await {
+        Ok(res) => record_response(db, id, retry_count, &res).await,
+        Err(e) => {
+            error!("Failed to transmit import item {}: {}", id, e);
+            schedule_retry(db, id, retry_count, None).await
+        }
+    };
+
+    if let Err(e) = outcome {
+        error!("Failed to update import item {}: {}", id, e);
+    }
+}
+
+fn build_payload(device: &Device, record: &Model) -> Value {
+    json!({
+        "tin": device.tin,
+        "bhfId": device.bhf_id,
+        "taskCd": record.task_cd,
+        "dclDe": record.dcl_de,
+        "itemSeq": record.item_seq,
+        "hsCd": record.hs_cd,
+        "itemClsCd": record.item_cls_cd,
+        "itemCd": record.item_cd,
+        "imptItemSttsCd": record.impt_item_stts_cd,
+        "remark": record.remark,
+        "modrNm": record.modr_nm,
+        "modrId": record.modr_id,
+    })
+}
+
+async fn record_response(
+    db: &DatabaseConnection,
+    id: i32,
+    retry_count: i64,
+    res: &VscuResponse<Value>,
+) -> Result<(), sea_orm::DbErr> {
+    let response = serde_json::to_value(res).ok();
+
+    let new_status = match res.class() {
+        ResultClass::Success => status::TRANSMITTED,
+        ResultClass::Rejected => {
+            warn!("Import item {} rejected by KRA: {} {}", id, res.result_cd, res.result_msg);
+            status::REJECTED
+        }
+        ResultClass::Retryable => return schedule_retry(db, id, retry_count, response).await,
+    };
+
+    let mut model: ActiveModel = find(db, id).await?.into();
+    model.status = Set(new_status.to_string());
+    model.response = Set(response);
+    model.next_retry_at = Set(None);
+    model.updated_at = Set(Some(Utc::now().naive_utc()));
+    model.update(db).await?;
+
+    info!("Import item {} is {}", id, new_status);
+    Ok(())
+}
+
+async fn schedule_retry(
+    db: &DatabaseConnection,
+    id: i32,
+    retry_count: i64,
+    response: Option<Value>,
+) -> Result<(), sea_orm::DbErr> {
+    let new_retry_count = retry_count + 1;
+    let next = next_retry_at(new_retry_count);
+
+    let mut model: ActiveModel = find(db, id).await?.into();
+    model.status = Set(status::FAILED.to_string());
+    model.retry_count = Set(new_retry_count);
+    model.next_retry_at = Set(Some(next.clone()));
+    model.updated_at = Set(Some(Utc::now().naive_utc()));
+    if response.is_some() {
+        model.response = Set(response);
+    }
+    model.update(db).await?;
+
+    info!("Import item {} FAILED ({}/{}), next retry at {}", id, new_retry_count, MAX_RETRIES, next);
+    Ok(())
+}
+
+async fn find(db: &DatabaseConnection, id: i32) -> Result<Model, sea_orm::DbErr> {
+    Entity::find_by_id(id)
+        .one(db)
+        .await?
+        .ok_or(sea_orm::DbErr::RecordNotFound(format!("ID {}", id)))
+}
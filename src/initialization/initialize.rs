@@ -13,12 +13,13 @@ use sea_orm::{
 };
 use rand::{distributions::Alphanumeric, Rng};
 use serde_json::json;
-use tracing::info;
+use tracing::{error, info};
 
 use crate::{
     models::initialization::{
         ActiveModel, Column, Entity as Credentials
-    }, types::initializeTypes::InitializeData, utils::crypto::{encrypt, encrypt_deterministic}
+    }, types::initializeTypes::{InitInfoReq, InitializeData}, utils::crypto::{encrypt, encrypt_deterministic},
+    vscu::client::VscuClient,
 };
 
 pub fn initialization_route(db: Arc<DatabaseConnection>) -> Router {
@@ -62,7 +63,38 @@ pub async fn initialize_system(
         }));
     }
 
-    // 🔹 3. Generate API key and encrypt other fields
+    // 🔹 3. Handshake with the VSCU so we only register devices it knows
+    let vscu = VscuClient::new(&payload.environmentUrl);
+    let init_req = InitInfoReq {
+        tin: payload.pin.clone(),
+        bhf_id: payload.branchId.clone(),
+        dvc_srl_no: payload.deviceSerial.clone(),
+    };
+
+    let init_res = match vscu.select_init_info(&init_req).await {
+        Ok(res) => res,
+        Err(e) => {
+            error!("selectInitInfo failed: {}", e);
+            return Json(json!({
+                "status": "error",
+                "message": format!("Could not verify device with VSCU: {}", e)
+            }));
+        }
+    };
+
+    // 902 = device already initialized on this VSCU, which is fine for us
+    if init_res.result_cd != "000" && init_res.result_cd != "902" {
+        return Json(json!({
+            "status": "error",
+            "message": "Device was not recognised by the VSCU",
+            "resultCd": init_res.result_cd,
+            "resultMsg": init_res.result_msg
+        }));
+    }
+
+    let info = init_res.data.map(|d| d.info);
+
+    // 🔹 4. Generate API key and encrypt other fields
     let api_key: String = rand::thread_rng()
         .sample_iter(&Alphanumeric)
         .take(64)
@@ -73,7 +105,7 @@ pub async fn initialize_system(
     let encrypted_branch = encrypt(&payload.branchId);
     let encrypted_company = encrypt(&payload.companyId);
 
-    // 🔹 4. Insert
+    // 🔹 5. Insert
     let device = ActiveModel {
         company_id: sea_orm::ActiveValue::Set(encrypted_company),
         environment_name: sea_orm::ActiveValue::Set(payload.environmentName),
@@ -82,6 +114,10 @@ pub async fn initialize_system(
         branch_id: sea_orm::ActiveValue::Set(encrypted_branch),
         device_serial: sea_orm::ActiveValue::Set(encrypted_serial),
         api_key: sea_orm::ActiveValue::Set(api_key),
+        taxpr_nm: sea_orm::ActiveValue::Set(info.as_ref().and_then(|i| i.taxpr_nm.clone())),
+        bhf_nm: sea_orm::ActiveValue::Set(info.as_ref().and_then(|i| i.bhf_nm.clone())),
+        mrc_no: sea_orm::ActiveValue::Set(info.as_ref().and_then(|i| i.mrc_no.clone())),
+        sdc_id: sea_orm::ActiveValue::Set(info.as_ref().and_then(|i| i.sdc_id.clone())),
         ..Default::default()
     };
 
@@ -90,7 +126,11 @@ pub async fn initialize_system(
             "status": "success",
             "data": {
                 
-                "api_key": res.api_key
+                "api_key": res.api_key,
+                "taxpr_nm": res.taxpr_nm,
+                "bhf_nm": res.bhf_nm,
+                "mrc_no": res.mrc_no,
+                "sdc_id": res.sdc_id
             },
          
         })),
@@ -4,11 +4,16 @@ mod branch_operations;
 mod signup;
 mod stock_management;
 mod initialization;
+mod codes;
+mod purchases;
+mod imports;
+mod notices;
+mod customers;
 use reqwest::Method;
 mod utils;
 // use sales::routing::route_sales;
-use branch_operations::route_branches::{branch_insurances,branch_users,branch_customers};
-use crate::{initialization::initialize::initialization_route, product_management::items_save_items::items_save_items_router, sales::routing::sales_route, signup::signup_login::{log_in, log_in_users, sign_up}, stock_management::route_stock_master::master_router, utils::{crypto::{decrypt_deterministic, encrypt_deterministic}, polling_retry_worker::{self, start_retry_worker}}};
+use branch_operations::{route_branch_registry::branches_router, route_branches::{branch_insurances,branch_users,branch_customers}};
+use crate::{codes::route_codes::codes_router, customers::routing::customers_router, imports::routing::imports_router, notices::routing::notices_router, purchases::routing::purchases_router, initialization::initialize::initialization_route, product_management::{item_composition::item_composition_router, items_save_items::items_save_items_router, items_select_items::items_select_items_router, route_item_classes::item_classes_router}, sales::routing::sales_route, signup::signup_login::{log_in, log_in_users, sign_up}, stock_management::{route_stock_master::master_router, route_stock_movements::movements_router}, utils::{crypto::{decrypt_deterministic, encrypt_deterministic}, master_sync_worker, polling_retry_worker::{self, start_retry_worker}}};
 mod product_management;
 mod types;
 use axum::{Router, serve};
@@ -20,6 +25,7 @@ use std::net::SocketAddr;
 use tower_http::cors::{Any, CorsLayer};
 use tokio::net::TcpListener;
 mod models;
+mod vscu;
 use tracing_subscriber::{ EnvFilter};
 use tracing_appender::rolling;
 #[tokio::main(flavor = "multi_thread")]
@@ -32,6 +38,7 @@ async fn main() -> Result<()> {
     // let db = Database::connect(&database_url).await?;
 let db = Arc::new(Database::connect(&database_url).await?);
    polling_retry_worker::start_retry_worker(db.clone());
+   master_sync_worker::start_master_sync_worker(db.clone());
     
     let cors = CorsLayer::new()
         .allow_origin(Any)
@@ -44,13 +51,22 @@ let db = Arc::new(Database::connect(&database_url).await?);
         .nest("/branch/users", branch_users(db.clone()))
         .nest("/branch/insurances", branch_insurances(db.clone()))
         .nest("/stock/master", master_router(db.clone()))
+        .nest("/stock/movements", movements_router(db.clone()))
+        .nest("/purchases", purchases_router(db.clone()))
+        .nest("/imports", imports_router(db.clone()))
+        .nest("/notices", notices_router(db.clone()))
+        .nest("/customers", customers_router(db.clone()))
+        .nest("/branches", branches_router(db.clone()))
         .nest("/product/items_save", items_save_items_router(db.clone()))
-        .nest("/product/items_select", items_save_items_router(db.clone()))
+        .nest("/product/items_select", items_select_items_router(db.clone()))
+        .nest("/product/classes", item_classes_router(db.clone()))
+        .nest("/product/composition", item_composition_router(db.clone()))
         .nest("/signup", sign_up(db.clone()))
         .nest("/login", log_in(db.clone()))
         .nest("/map_users", log_in_users(db.clone()))
         .nest("/initialize", initialization_route(db.clone()))
         .nest("/sales",sales_route(db.clone()))
+        .nest("/codes", codes_router(db.clone()))
         .layer(cors)
         .layer(
             tower_http::trace::TraceLayer::new_for_http()
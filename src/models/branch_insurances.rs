@@ -18,6 +18,14 @@ pub struct Model {
     pub regr_id: String,
     pub modr_nm: String,
     pub modr_id: String,
+
+    // Transmission bookkeeping (same states as sales)
+    pub api_key: Option<String>,
+    pub status: String,
+    pub response: Option<Json>,
+    pub retry_count: i64,
+    pub next_retry_at: Option<String>,
+    pub claimed_at: Option<DateTime>,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
@@ -0,0 +1,29 @@
+use sea_orm::entity::prelude::*;
+use serde::Serialize;
+
+/// Branch registered with KRA under a taxpayer PIN
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, DeriveEntityModel)]
+#[sea_orm(table_name = "branches")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: i32,
+
+    pub tin: String,                // encrypted like credentials.pin
+    pub bhf_id: String,
+    pub bhf_nm: String,
+    pub bhf_stts_cd: String,        // 01 active, 02 closed
+    pub prvnc_nm: Option<String>,   // county
+    pub dstrt_nm: Option<String>,
+    pub sctr_nm: Option<String>,
+    pub loc_desc: Option<String>,
+    pub mgr_nm: Option<String>,
+    pub mgr_tel_no: Option<String>,
+    pub mgr_email: Option<String>,
+    pub hq_yn: String,              // head office (Y/N)
+    pub updated_at: DateTime,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
@@ -0,0 +1,24 @@
+use sea_orm::entity::prelude::*;
+use serde::Serialize;
+
+/// KRA code class (tax types, unit codes, payment types, ...)
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, DeriveEntityModel)]
+#[sea_orm(table_name = "code_class")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: i32,
+
+    pub cd_cls: String,
+    pub cd_cls_nm: String,
+    pub cd_cls_desc: Option<String>,
+    pub use_yn: String,
+    pub user_dfn_nm1: Option<String>,
+    pub user_dfn_nm2: Option<String>,
+    pub user_dfn_nm3: Option<String>,
+    pub updated_at: DateTime,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
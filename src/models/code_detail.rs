@@ -0,0 +1,26 @@
+use sea_orm::entity::prelude::*;
+use serde::Serialize;
+
+/// Single code inside a KRA code class
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, DeriveEntityModel)]
+#[sea_orm(table_name = "code_detail")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: i32,
+
+    pub cd_cls: String,
+    pub cd: String,
+    pub cd_nm: String,
+    pub cd_desc: Option<String>,
+    pub use_yn: String,
+    pub srt_ord: Option<i32>,
+    pub user_dfn_cd1: Option<String>, // for tax types this carries the rate
+    pub user_dfn_cd2: Option<String>,
+    pub user_dfn_cd3: Option<String>,
+    pub updated_at: DateTime,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
@@ -0,0 +1,23 @@
+use sea_orm::entity::prelude::*;
+use serde::Serialize;
+
+/// Idempotency-Key of a `/sales` request and the answer it got.
+/// `status_code` / `response` stay empty while the request is in flight.
+#[derive(Clone, Debug, PartialEq, Serialize, DeriveEntityModel)]
+#[sea_orm(table_name = "idempotency_keys")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: i32,
+
+    pub api_key: String,
+    pub idem_key: String,
+    pub request_hash: String,
+    pub status_code: Option<i32>,
+    pub response: Option<Json>,
+    pub created_at: DateTime,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
@@ -0,0 +1,55 @@
+use sea_orm::entity::prelude::*;
+use serde::Serialize;
+
+/// Declared import line (customs) and our approve/reject decision on it
+#[derive(Clone, Debug, PartialEq, Serialize, DeriveEntityModel)]
+#[sea_orm(table_name = "import_item")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: i32,
+
+    pub credentials_id: i32,
+
+    // ===== DECLARATION =====
+    pub task_cd: String,
+    pub dcl_de: String,             // declaration date, yyyyMMdd
+    pub item_seq: i32,
+    pub dcl_no: String,
+    pub hs_cd: String,
+    pub item_nm: String,
+    pub orgn_nat_cd: Option<String>,
+    pub expt_nat_cd: Option<String>,
+    pub pkg: f64,
+    pub pkg_unit_cd: Option<String>,
+    pub qty: f64,
+    pub qty_unit_cd: Option<String>,
+    pub tot_wt: f64,
+    pub net_wt: f64,
+    pub spplr_nm: Option<String>,
+    pub agnt_nm: Option<String>,
+    pub invc_fcur_amt: f64,
+    pub invc_fcur_cd: Option<String>,
+    pub invc_fcur_excrt: f64,
+
+    // ===== DECISION =====
+    pub impt_item_stts_cd: String,  // 2 waiting, 3 approved, 4 cancelled
+    pub item_cd: Option<String>,    // item_master code the line was mapped to
+    pub item_cls_cd: Option<String>,
+    pub remark: Option<String>,
+    pub modr_nm: Option<String>,
+    pub modr_id: Option<String>,
+
+    // ===== TRANSMISSION =====
+    pub status: String,
+    pub response: Option<Json>,
+    pub retry_count: i64,
+    pub next_retry_at: Option<String>,
+    pub claimed_at: Option<DateTime>,
+    pub created_at: DateTime,
+    pub updated_at: Option<DateTime>,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
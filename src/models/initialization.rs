@@ -15,6 +15,12 @@ pub struct Model {
     pub branch_id: String,
     pub device_serial: String,
    pub api_key: String,
+
+    // Device info returned by VSCU selectInitInfo
+    pub taxpr_nm: Option<String>,
+    pub bhf_nm: Option<String>,
+    pub mrc_no: Option<String>,
+    pub sdc_id: Option<String>,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
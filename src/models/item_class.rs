@@ -0,0 +1,23 @@
+use sea_orm::entity::prelude::*;
+use serde::Serialize;
+
+/// KRA item classification tree node
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, DeriveEntityModel)]
+#[sea_orm(table_name = "item_class")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: i32,
+
+    pub item_cls_cd: String,
+    pub item_cls_nm: String,
+    pub item_cls_lvl: i32,
+    pub tax_ty_cd: Option<String>,
+    pub mjr_tg_yn: Option<String>, // major target (Y/N)
+    pub use_yn: String,
+    pub updated_at: DateTime,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
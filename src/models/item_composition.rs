@@ -0,0 +1,34 @@
+use rust_decimal::Decimal;
+use sea_orm::entity::prelude::*;
+use serde::Serialize;
+
+/// One component of a composite item (`items/saveItemComposition`)
+#[derive(Clone, Debug, PartialEq, Serialize, DeriveEntityModel)]
+#[sea_orm(table_name = "item_composition")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: i32,
+
+    pub tin: String,
+    pub bhf_id: String,
+    pub item_cd: String,            // composite (parent) item
+    pub cpst_item_cd: String,       // component item
+    pub cpst_qty: Decimal,          // component quantity per parent unit, NUMBER(13,2)
+    pub regr_nm: String,
+    pub regr_id: String,
+    pub use_yn: String,             // N once removed from the composite item
+
+    // Transmission bookkeeping (same states as sales)
+    pub status: String,
+    pub response: Option<Json>,
+    pub retry_count: i64,
+    pub next_retry_at: Option<String>,
+    pub claimed_at: Option<DateTime>,
+    pub created_at: DateTime,
+    pub updated_at: Option<DateTime>,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
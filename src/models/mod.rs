@@ -5,4 +5,18 @@ pub mod stock_master;
 pub mod product_save_items;
 pub mod sign_up;
 pub mod initialization;
-pub mod sales_uploads;
\ No newline at end of file
+pub mod sales_uploads;
+pub mod sync_state;
+pub mod code_class;
+pub mod code_detail;
+pub mod item_class;
+pub mod stock_movement;
+pub mod stock_movement_item;
+pub mod purchase;
+pub mod purchase_item;
+pub mod import_item;
+pub mod notices;
+pub mod notice_reads;
+pub mod branches;
+pub mod item_composition;
+pub mod idempotency_keys;
@@ -0,0 +1,32 @@
+use sea_orm::entity::prelude::*;
+use serde::Serialize;
+
+/// A dashboard user (`user.id`) having read a notice
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, DeriveEntityModel)]
+#[sea_orm(table_name = "notice_reads")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: i32,
+
+    pub notice_id: i32,
+    pub user_id: i32,
+    pub read_at: DateTime,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::notices::Entity",
+        from = "Column::NoticeId",
+        to = "super::notices::Column::Id"
+    )]
+    Notice,
+}
+
+impl Related<super::notices::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Notice.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
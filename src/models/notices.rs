@@ -0,0 +1,34 @@
+use sea_orm::entity::prelude::*;
+use serde::Serialize;
+
+/// KRA notice pulled from notices/selectNotices
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, DeriveEntityModel)]
+#[sea_orm(table_name = "notices")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: i32,
+
+    pub credentials_id: i32,
+    pub ntc_no: i64,
+    pub title: String,
+    pub cont: String,
+    pub dtl_url: Option<String>,
+    pub regr_nm: Option<String>,
+    pub reg_dt: String,             // yyyyMMddHHmmss
+
+    pub created_at: DateTime,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(has_many = "super::notice_reads::Entity")]
+    Reads,
+}
+
+impl Related<super::notice_reads::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Reads.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
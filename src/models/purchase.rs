@@ -0,0 +1,60 @@
+use sea_orm::entity::prelude::*;
+use serde::Serialize;
+
+/// Supplier sale received through KRA and our purchase decision on it
+#[derive(Clone, Debug, PartialEq, Serialize, DeriveEntityModel)]
+#[sea_orm(table_name = "purchase")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: i32,
+
+    pub credentials_id: i32,
+
+    // ===== SUPPLIER SALE =====
+    pub spplr_tin: String,
+    pub spplr_nm: String,
+    pub spplr_bhf_id: String,
+    pub spplr_invc_no: i64,
+    pub rcpt_ty_cd: String,
+    pub pmt_ty_cd: Option<String>,
+    pub cfm_dt: String,
+    pub sales_dt: String,
+    pub tot_item_cnt: i32,
+    pub tot_taxbl_amt: f64,
+    pub tot_tax_amt: f64,
+    pub tot_amt: f64,
+    pub supplier_sale: Json,        // full selectTrnsPurchaseSales entry (tax buckets etc.)
+
+    // ===== DECISION =====
+    pub pchs_stts_cd: String,       // 01 waiting, 02 approved, 04 cancelled
+    pub invc_no: Option<i64>,       // our purchase invoice number, allocated on decision
+    pub pchs_ty_cd: Option<String>,
+    pub pchs_dt: Option<String>,    // yyyyMMdd
+    pub remark: Option<String>,
+    pub regr_nm: Option<String>,
+    pub regr_id: Option<String>,
+    pub stock_movement_id: Option<i32>,
+
+    // ===== TRANSMISSION =====
+    pub status: String,
+    pub response: Option<Json>,
+    pub retry_count: i64,
+    pub next_retry_at: Option<String>,
+    pub claimed_at: Option<DateTime>,
+    pub created_at: DateTime,
+    pub updated_at: Option<DateTime>,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(has_many = "super::purchase_item::Entity")]
+    Items,
+}
+
+impl Related<super::purchase_item::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Items.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
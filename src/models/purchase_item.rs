@@ -0,0 +1,48 @@
+use sea_orm::entity::prelude::*;
+use serde::Serialize;
+
+/// Line of a supplier sale / purchase
+#[derive(Clone, Debug, PartialEq, Serialize, DeriveEntityModel)]
+#[sea_orm(table_name = "purchase_item")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: i32,
+
+    pub purchase_id: i32,
+    pub item_seq: i32,
+    pub spplr_item_cd: Option<String>, // supplier's own item code
+    pub item_cd: Option<String>,       // our item code, set when the purchase is accepted
+    pub item_cls_cd: String,
+    pub item_nm: String,
+    pub bcd: Option<String>,
+    pub pkg_unit_cd: String,
+    pub pkg: f64,
+    pub qty_unit_cd: String,
+    pub qty: f64,
+    pub prc: f64,
+    pub sply_amt: f64,
+    pub dc_rt: f64,
+    pub dc_amt: f64,
+    pub tax_ty_cd: String,
+    pub taxbl_amt: f64,
+    pub tax_amt: f64,
+    pub tot_amt: f64,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::purchase::Entity",
+        from = "Column::PurchaseId",
+        to = "super::purchase::Column::Id"
+    )]
+    Purchase,
+}
+
+impl Related<super::purchase::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Purchase.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
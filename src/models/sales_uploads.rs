@@ -21,6 +21,7 @@ pub generated_invc_no:i64,
     pub trd_invc_no: Option<i32>,
 pub retry_count: Option<i64>,
 pub next_retry_at: Option<String>,
+    pub claimed_at: Option<DateTime>,
     pub invc_no: i64,
     pub org_invc_no: i64,
 
@@ -44,27 +45,32 @@ pub next_retry_at: Option<String>,
     // ===== TOTALS =====
     pub tot_item_cnt: i32,
 
-    pub taxbl_amt_a: f64,
-    pub taxbl_amt_b: f64,
-    pub taxbl_amt_c: f64,
-    pub taxbl_amt_d: f64,
-    pub taxbl_amt_e: f64,
-
-    pub tax_rt_a: f64,
-    pub tax_rt_b: f64,
-    pub tax_rt_c: f64,
-    pub tax_rt_d: f64,
-    pub tax_rt_e: f64,
-
-    pub tax_amt_a: f64,
-    pub tax_amt_b: f64,
-    pub tax_amt_c: f64,
-    pub tax_amt_d: f64,
-    pub tax_amt_e: f64,
-
-    pub tot_taxbl_amt: f64,
-    pub tot_tax_amt: f64,
-    pub tot_amt: f64,
+    pub taxbl_amt_a: Decimal,
+    pub taxbl_amt_b: Decimal,
+    pub taxbl_amt_c: Decimal,
+    pub taxbl_amt_d: Decimal,
+    pub taxbl_amt_e: Decimal,
+
+    pub tax_rt_a: Decimal,
+    pub tax_rt_b: Decimal,
+    pub tax_rt_c: Decimal,
+    pub tax_rt_d: Decimal,
+    pub tax_rt_e: Decimal,
+
+    pub tax_amt_a: Decimal,
+    pub tax_amt_b: Decimal,
+    pub tax_amt_c: Decimal,
+    pub tax_amt_d: Decimal,
+    pub tax_amt_e: Decimal,
+
+    pub tot_taxbl_amt: Decimal,
+    pub tot_tax_amt: Decimal,
+    pub tot_amt: Decimal,
+    /// Sum of the credit notes against this sale, see `sales::credit_notes`
+    pub refunded_amt: Decimal,
+    /// What the automatic stock out took for the composite items sold, see
+    /// `stock_management::consume_components`
+    pub component_usage: Option<Json>,
 
     // ===== FLAGS / AUDIT =====
     pub prchr_acptc_yn: String,
@@ -79,6 +85,15 @@ pub next_retry_at: Option<String>,
     pub receipt: Json,
     pub item_list: Json,
     pub response:Option<Json>,
+
+    // ===== SIGNED RECEIPT (from KRA on success) =====
+    pub rcpt_no: Option<i64>,
+    pub tot_rcpt_no: Option<i64>,
+    pub rcpt_sign: Option<String>,
+    pub intrl_data: Option<String>,
+    pub sdc_id: Option<String>,
+    pub mrc_no: Option<String>,
+    pub rcpt_pbct_dt: Option<String>,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
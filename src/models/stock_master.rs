@@ -16,6 +16,14 @@ pub struct Model {
     pub regr_id: String,    // Registrant ID (max 20)
     pub modr_nm: String,    // Modifier name (max 60)
     pub modr_id: String,    // Modifier ID (max 20)
+
+    // Transmission bookkeeping (same states as sales)
+    pub status: String,
+    pub response: Option<Json>,
+    pub retry_count: i64,
+    pub next_retry_at: Option<String>,
+    pub claimed_at: Option<DateTime>,
+    pub updated_at: Option<DateTime>,  // Last change of rsd_qty
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
@@ -0,0 +1,52 @@
+use sea_orm::entity::prelude::*;
+use serde::Serialize;
+
+/// Stock in/out header (`stock/saveStockItems`)
+#[derive(Clone, Debug, PartialEq, Serialize, DeriveEntityModel)]
+#[sea_orm(table_name = "stock_movement")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: i32,
+
+    pub tin: String,
+    pub bhf_id: String,
+    pub sar_no: i64,                // Stock In/Out Number, allocated per branch
+    pub org_sar_no: i64,            // Original Stock In/Out Number
+    pub reg_ty_cd: String,          // Registration Type Code (M manual / A automatic)
+    pub cust_tin: Option<String>,
+    pub cust_nm: Option<String>,
+    pub cust_bhf_id: Option<String>,
+    pub sar_ty_cd: String,          // Stock In/Out Type Code (0x in, 1x out)
+    pub ocrn_dt: String,            // yyyyMMdd
+    pub tot_item_cnt: i32,
+    pub tot_taxbl_amt: f64,
+    pub tot_tax_amt: f64,
+    pub tot_amt: f64,
+    pub remark: Option<String>,
+    pub regr_nm: String,
+    pub regr_id: String,
+    pub modr_nm: String,
+    pub modr_id: String,
+
+    // Transmission bookkeeping (same states as sales)
+    pub status: String,
+    pub response: Option<Json>,
+    pub retry_count: i64,
+    pub next_retry_at: Option<String>,
+    pub claimed_at: Option<DateTime>,
+    pub created_at: DateTime,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(has_many = "super::stock_movement_item::Entity")]
+    Items,
+}
+
+impl Related<super::stock_movement_item::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Items.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
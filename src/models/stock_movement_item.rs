@@ -0,0 +1,47 @@
+use sea_orm::entity::prelude::*;
+use serde::Serialize;
+
+/// Line of a stock in/out movement
+#[derive(Clone, Debug, PartialEq, Serialize, DeriveEntityModel)]
+#[sea_orm(table_name = "stock_movement_item")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: i32,
+
+    pub movement_id: i32,
+    pub item_seq: i32,
+    pub item_cd: String,
+    pub item_cls_cd: String,
+    pub item_nm: String,
+    pub bcd: Option<String>,
+    pub pkg_unit_cd: String,
+    pub pkg: f64,
+    pub qty_unit_cd: String,
+    pub qty: f64,
+    pub item_expr_dt: Option<String>, // yyyyMMdd
+    pub prc: f64,
+    pub sply_amt: f64,
+    pub tot_dc_amt: f64,
+    pub taxbl_amt: f64,
+    pub tax_ty_cd: String,
+    pub tax_amt: f64,
+    pub tot_amt: f64,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::stock_movement::Entity",
+        from = "Column::MovementId",
+        to = "super::stock_movement::Column::Id"
+    )]
+    Movement,
+}
+
+impl Related<super::stock_movement::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Movement.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
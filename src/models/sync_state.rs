@@ -0,0 +1,20 @@
+use sea_orm::entity::prelude::*;
+use serde::Serialize;
+
+/// Last `lastReqDt` used per device for each VSCU select endpoint
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, DeriveEntityModel)]
+#[sea_orm(table_name = "sync_state")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: i32,
+
+    pub credentials_id: i32,
+    pub resource: String,
+    pub last_req_dt: String, // yyyyMMddHHmmss
+    pub updated_at: DateTime,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
@@ -0,0 +1,2 @@
+pub mod routing;
+pub mod sync_notices;
@@ -0,0 +1,177 @@
+use std::{collections::HashMap, sync::Arc};
+
+use axum::{
+    Json, Router,
+    extract::{Path, Query, State},
+    http::{HeaderMap, StatusCode},
+    response::IntoResponse,
+    routing::{get, post},
+};
+use axum_extra::TypedHeader;
+use headers::{Authorization, authorization::Bearer};
+use sea_orm::{
+    ActiveValue::Set, ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter, QueryOrder,
+    sea_query::OnConflict,
+};
+use serde_json::{Value, json};
+
+use crate::{
+    models::{notice_reads, notices, sign_up},
+    stock_management::route_stock_master::error_response,
+    types::{notices::NoticeQuery, salespayloadtype::AuthUser},
+    utils::{bearer::bearer_resolver, crypto::decrypt_deterministic},
+};
+
+/// Header carrying the encrypted tracking id the dashboard got from `/login`
+const TRACKING_HEADER: &str = "x-tracking-id";
+
+pub fn notices_router(db: Arc<DatabaseConnection>) -> Router {
+    Router::new()
+        .route("/", get(list_notices))
+        .route("/{id}/read", post(mark_read))
+        .with_state(db)
+}
+
+/// Device from the bearer token and dashboard user from the tracking header
+async fn authorize(
+    token: &str,
+    headers: &HeaderMap,
+    db: &DatabaseConnection,
+) -> Result<(AuthUser, sign_up::Model), (StatusCode, Json<Value>)> {
+    let device: AuthUser = match bearer_resolver(token, db).await {
+        Ok(val) => serde_json::from_value(val).map_err(|e| {
+            error_response(&format!("Failed to parse user: {e}"), StatusCode::INTERNAL_SERVER_ERROR)
+        })?,
+        Err(e) => return Err(error_response(&e, StatusCode::UNAUTHORIZED)),
+    };
+
+    let tracking_id = headers
+        .get(TRACKING_HEADER)
+        .and_then(|v| v.to_str().ok())
+        .ok_or_else(|| error_response("Missing X-Tracking-Id header", StatusCode::UNAUTHORIZED))?;
+    let tracking_id = decrypt_deterministic(tracking_id)
+        .map_err(|_| error_response("Invalid tracking ID", StatusCode::UNAUTHORIZED))?;
+
+    let user = sign_up::Entity::find()
+        .filter(sign_up::Column::TrackingId.eq(tracking_id))
+        .one(db)
+        .await
+        .map_err(|e| error_response(&format!("Failed to fetch user: {e}"), StatusCode::INTERNAL_SERVER_ERROR))?
+        .ok_or_else(|| error_response("User not found", StatusCode::UNAUTHORIZED))?;
+
+    Ok((device, user))
+}
+
+/// Notices of the device, newest first, with the dashboard user's read state
+async fn list_notices(
+    TypedHeader(auth): TypedHeader<Authorization<Bearer>>,
+    headers: HeaderMap,
+    State(db): State<Arc<DatabaseConnection>>,
+    Query(query): Query<NoticeQuery>,
+) -> impl IntoResponse {
+    let (device, user) = match authorize(auth.token(), &headers, db.as_ref()).await {
+        Ok(found) => found,
+        Err(res) => return res,
+    };
+
+    let notices = match notices::Entity::find()
+        .filter(notices::Column::CredentialsId.eq(device.id))
+        .order_by_desc(notices::Column::RegDt)
+        .order_by_desc(notices::Column::NtcNo)
+        .all(db.as_ref())
+        .await
+    {
+        Ok(n) => n,
+        Err(e) => return error_response(&format!("Failed to fetch notices: {e}"), StatusCode::INTERNAL_SERVER_ERROR),
+    };
+
+    let reads: HashMap<i32, notice_reads::Model> = match notice_reads::Entity::find()
+        .filter(notice_reads::Column::UserId.eq(user.id))
+        .filter(notice_reads::Column::NoticeId.is_in(notices.iter().map(|n| n.id)))
+        .all(db.as_ref())
+        .await
+    {
+        Ok(r) => r.into_iter().map(|r| (r.notice_id, r)).collect(),
+        Err(e) => return error_response(&format!("Failed to fetch read state: {e}"), StatusCode::INTERNAL_SERVER_ERROR),
+    };
+
+    let unread_count = notices.iter().filter(|n| !reads.contains_key(&n.id)).count();
+
+    let data: Vec<Value> = notices
+        .into_iter()
+        .filter(|n| !query.unread || !reads.contains_key(&n.id))
+        .map(|n| {
+            let read_at = reads.get(&n.id).map(|r| r.read_at);
+            json!({
+                "id": n.id,
+                "ntcNo": n.ntc_no,
+                "title": n.title,
+                "cont": n.cont,
+                "dtlUrl": n.dtl_url,
+                "regrNm": n.regr_nm,
+                "regDt": n.reg_dt,
+                "read": read_at.is_some(),
+                "readAt": read_at,
+            })
+        })
+        .collect();
+
+    (
+        StatusCode::OK,
+        Json(json!({
+            "resultCd": "000",
+            "resultMsg": "Success",
+            "unreadCount": unread_count,
+            "data": data,
+        })),
+    )
+}
+
+/// Mark a notice as read by the dashboard user; marking it twice is a no-op
+async fn mark_read(
+    TypedHeader(auth): TypedHeader<Authorization<Bearer>>,
+    headers: HeaderMap,
+    State(db): State<Arc<DatabaseConnection>>,
+    Path(id): Path<i32>,
+) -> impl IntoResponse {
+    let (device, user) = match authorize(auth.token(), &headers, db.as_ref()).await {
+        Ok(found) => found,
+        Err(res) => return res,
+    };
+
+    match notices::Entity::find_by_id(id)
+        .filter(notices::Column::CredentialsId.eq(device.id))
+        .one(db.as_ref())
+        .await
+    {
+        Ok(Some(_)) => {}
+        Ok(None) => return error_response("Notice not found", StatusCode::NOT_FOUND),
+        Err(e) => return error_response(&format!("Failed to fetch notice: {e}"), StatusCode::INTERNAL_SERVER_ERROR),
+    }
+
+    let read = notice_reads::ActiveModel {
+        notice_id: Set(id),
+        user_id: Set(user.id),
+        ..Default::default()
+    };
+
+    match notice_reads::Entity::insert(read)
+        .on_conflict(
+            OnConflict::columns([notice_reads::Column::NoticeId, notice_reads::Column::UserId])
+                .do_nothing()
+                .to_owned(),
+        )
+        .do_nothing()
+        .exec(db.as_ref())
+        .await
+    {
+        Ok(_) => (
+            StatusCode::OK,
+            Json(json!({
+                "resultCd": "000",
+                "resultMsg": "Notice marked as read",
+            })),
+        ),
+        Err(e) => error_response(&format!("Failed to mark notice as read: {e}"), StatusCode::INTERNAL_SERVER_ERROR),
+    }
+}
@@ -0,0 +1,89 @@
+use sea_orm::{ActiveModelTrait, ActiveValue::Set, ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter};
+use tracing::{info, warn};
+
+use crate::{
+    models::notices::{ActiveModel, Column, Entity},
+    types::notices::NoticeInfo,
+    utils::sync_state::{last_req_dt, mark_synced, now_req_dt},
+    vscu::{device::Device, result::ResultClass},
+};
+
+pub const RESOURCE: &str = "notices";
+
+/// Pull notices KRA published for the device since its last sync. Returns how
+/// many new notices were stored.
+pub async fn sync_notices(db: &DatabaseConnection, device: &Device) -> Result<usize, String> {
+    let since = last_req_dt(db, device.credentials_id, RESOURCE)
+        .await
+        .map_err(|e| e.to_string())?;
+    let started_at = now_req_dt();
+
+    let res = device
+        .client
+        .select_notices(&device.select_request(&since))
+        .await
+        .map_err(|e| e.to_string())?;
+
+    if res.is_empty_search() {
+        info!("No notices for device {} since {}", device.credentials_id, since);
+        mark_synced(db, device.credentials_id, RESOURCE, &started_at)
+            .await
+            .map_err(|e| e.to_string())?;
+        return Ok(0);
+    }
+
+    if res.class() != ResultClass::Success {
+        warn!("selectNotices failed for device {}: {} {}", device.credentials_id, res.result_cd, res.result_msg);
+        return Err(format!("{} {}", res.result_cd, res.result_msg));
+    }
+
+    let notices = res.data.map(|d| d.notice_list).unwrap_or_default();
+    let stored = store_notices(db, device.credentials_id, notices)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    mark_synced(db, device.credentials_id, RESOURCE, &started_at)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    info!("Stored {} new notices for device {}", stored, device.credentials_id);
+    Ok(stored)
+}
+
+/// Insert notices not seen yet; KRA does not edit a published notice
+async fn store_notices(
+    db: &DatabaseConnection,
+    credentials_id: i32,
+    notices: Vec<NoticeInfo>,
+) -> Result<usize, sea_orm::DbErr> {
+    let mut stored = 0;
+
+    for notice in notices {
+        let known = Entity::find()
+            .filter(Column::CredentialsId.eq(credentials_id))
+            .filter(Column::NtcNo.eq(notice.ntc_no))
+            .one(db)
+            .await?;
+
+        if known.is_some() {
+            continue;
+        }
+
+        ActiveModel {
+            credentials_id: Set(credentials_id),
+            ntc_no: Set(notice.ntc_no),
+            title: Set(notice.title),
+            cont: Set(notice.cont),
+            dtl_url: Set(notice.dtl_url),
+            regr_nm: Set(notice.regr_nm),
+            reg_dt: Set(notice.reg_dt),
+            ..Default::default()
+        }
+        .insert(db)
+        .await?;
+
+        stored += 1;
+    }
+
+    Ok(stored)
+}
@@ -0,0 +1,288 @@
+use std::sync::Arc;
+
+use axum::{
+    Json, Router,
+    extract::{Query, State},
+    http::StatusCode,
+    response::IntoResponse,
+    routing::post,
+};
+use axum_extra::TypedHeader;
+use chrono::Utc;
+use headers::{Authorization, authorization::Bearer};
+use rust_decimal::Decimal;
+use sea_orm::{
+    ActiveModelTrait, ActiveValue::Set, ColumnTrait, DatabaseConnection, DatabaseTransaction,
+    EntityTrait, QueryFilter, QueryOrder, TransactionTrait, sea_query::Expr,
+};
+use serde_json::json;
+use tracing::info;
+
+use crate::{
+    models::{item_composition, product_save_items},
+    product_management::transmit_item_composition::transmit_item_composition,
+    sales::status,
+    stock_management::route_stock_master::error_response,
+    types::{
+        product_management_payload_types::{ItemComponentRef, ItemCompositionQuery, ItemCompositionReq},
+        salespayloadtype::AuthUser,
+    },
+    utils::bearer::bearer_resolver,
+    vscu::device::Device,
+};
+
+pub fn item_composition_router(db: Arc<DatabaseConnection>) -> Router {
+    Router::new()
+        .route("/", post(save_composition).get(list_composition).delete(remove_component))
+        .with_state(db)
+}
+
+/// Add components to a composite item (or change their quantity) and send
+/// each changed line to `items/saveItemComposition`
+async fn save_composition(
+    TypedHeader(auth): TypedHeader<Authorization<Bearer>>,
+    State(db): State<Arc<DatabaseConnection>>,
+    Json(payload): Json<ItemCompositionReq>,
+) -> impl IntoResponse {
+    let user: AuthUser = match bearer_resolver(auth.token(), db.as_ref()).await {
+        Ok(val) => match serde_json::from_value(val) {
+            Ok(u) => u,
+            Err(e) => return error_response(&format!("Failed to parse user: {e}"), StatusCode::INTERNAL_SERVER_ERROR),
+        },
+        Err(e) => return error_response(&e, StatusCode::UNAUTHORIZED),
+    };
+
+    let device = match Device::from_user(&user) {
+        Ok(d) => d,
+        Err(e) => return error_response(&e, StatusCode::INTERNAL_SERVER_ERROR),
+    };
+
+    if payload.components.is_empty() {
+        return error_response("A composition needs at least one component", StatusCode::BAD_REQUEST);
+    }
+
+    for component in &payload.components {
+        if component.cpst_item_cd == payload.item_cd {
+            return error_response("An item cannot be a component of itself", StatusCode::BAD_REQUEST);
+        }
+        if component.cpst_qty <= Decimal::ZERO {
+            return error_response(
+                &format!("Quantity for {} must be positive", component.cpst_item_cd),
+                StatusCode::BAD_REQUEST,
+            );
+        }
+        if component.cpst_qty.normalize().scale() > 2 {
+            return error_response(
+                &format!("Quantity for {} allows at most 2 decimals", component.cpst_item_cd),
+                StatusCode::BAD_REQUEST,
+            );
+        }
+    }
+
+    // Parent and components must all be registered items of the branch
+    let mut item_cds = vec![payload.item_cd.clone()];
+    item_cds.extend(payload.components.iter().map(|c| c.cpst_item_cd.clone()));
+
+    let known: Vec<String> = match product_save_items::Entity::find()
+        .filter(product_save_items::Column::Tin.eq(&user.pin))
+        .filter(product_save_items::Column::BhfId.eq(&user.branch_id))
+        .filter(product_save_items::Column::ItemCd.is_in(item_cds.clone()))
+        .all(db.as_ref())
+        .await
+    {
+        Ok(items) => items.into_iter().map(|i| i.item_cd).collect(),
+        Err(e) => return error_response(&format!("Failed to fetch items: {e}"), StatusCode::INTERNAL_SERVER_ERROR),
+    };
+
+    let unknown: Vec<&String> = item_cds.iter().filter(|cd| !known.contains(cd)).collect();
+    if !unknown.is_empty() {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(json!({
+                "resultCd": "400",
+                "resultMsg": "Unknown item codes",
+                "itemCd": unknown,
+            })),
+        );
+    }
+
+    let txn = match db.begin().await {
+        Ok(t) => t,
+        Err(e) => return error_response(&format!("Failed to start transaction: {e}"), StatusCode::INTERNAL_SERVER_ERROR),
+    };
+
+    let changed = match store_components(&txn, &user, &payload).await {
+        Ok(ids) => ids,
+        Err(e) => {
+            let _ = txn.rollback().await;
+            return error_response(&format!("Failed to store composition: {e}"), StatusCode::INTERNAL_SERVER_ERROR);
+        }
+    };
+
+    if let Err(e) = txn.commit().await {
+        return error_response(&format!("Transaction commit failed: {e}"), StatusCode::INTERNAL_SERVER_ERROR);
+    }
+
+    info!("Composition of {} has {} changed components", payload.item_cd, changed.len());
+
+    let queued = changed.len();
+    let db_clone = db.clone();
+    tokio::spawn(async move {
+        for id in changed {
+            transmit_item_composition(db_clone.as_ref(), &device, id).await;
+        }
+    });
+
+    (
+        StatusCode::OK,
+        Json(json!({
+            "resultCd": "000",
+            "resultMsg": "Item composition saved and queued for KRA",
+            "itemCd": payload.item_cd,
+            "queued": queued,
+        })),
+    )
+}
+
+/// Insert new components and update changed quantities, putting removed
+/// components back. Returns the ids of
+/// the lines that have to be transmitted.
+async fn store_components(
+    txn: &DatabaseTransaction,
+    user: &AuthUser,
+    payload: &ItemCompositionReq,
+) -> Result<Vec<i32>, sea_orm::DbErr> {
+    let mut changed = Vec::new();
+
+    for component in &payload.components {
+        let existing = item_composition::Entity::find()
+            .filter(item_composition::Column::Tin.eq(&user.pin))
+            .filter(item_composition::Column::BhfId.eq(&user.branch_id))
+            .filter(item_composition::Column::ItemCd.eq(&payload.item_cd))
+            .filter(item_composition::Column::CpstItemCd.eq(&component.cpst_item_cd))
+            .one(txn)
+            .await?;
+
+        let saved = match existing {
+            Some(record)
+                if record.cpst_qty == component.cpst_qty && record.use_yn == "Y" && record.status != status::REJECTED =>
+            {
+                continue
+            }
+            Some(record) => {
+                let mut model: item_composition::ActiveModel = record.into();
+                model.cpst_qty = Set(component.cpst_qty);
+                model.use_yn = Set("Y".to_string());
+                model.regr_nm = Set(payload.regr_nm.clone());
+                model.regr_id = Set(payload.regr_id.clone());
+                model.status = Set(status::RECEIVED.to_string());
+                model.retry_count = Set(0);
+                model.next_retry_at = Set(None);
+                model.updated_at = Set(Some(Utc::now().naive_utc()));
+                model.update(txn).await?
+            }
+            None => {
+                item_composition::ActiveModel {
+                    tin: Set(user.pin.clone()),
+                    bhf_id: Set(user.branch_id.clone()),
+                    item_cd: Set(payload.item_cd.clone()),
+                    cpst_item_cd: Set(component.cpst_item_cd.clone()),
+                    cpst_qty: Set(component.cpst_qty),
+                    regr_nm: Set(payload.regr_nm.clone()),
+                    regr_id: Set(payload.regr_id.clone()),
+                    status: Set(status::RECEIVED.to_string()),
+                    ..Default::default()
+                }
+                .insert(txn)
+                .await?
+            }
+        };
+
+        changed.push(saved.id);
+    }
+
+    Ok(changed)
+}
+
+/// Remove a component from its composite item: later sales no longer take it
+/// out of stock. KRA has no call to remove a component, so nothing is sent.
+async fn remove_component(
+    TypedHeader(auth): TypedHeader<Authorization<Bearer>>,
+    State(db): State<Arc<DatabaseConnection>>,
+    Query(query): Query<ItemComponentRef>,
+) -> impl IntoResponse {
+    let user: AuthUser = match bearer_resolver(auth.token(), db.as_ref()).await {
+        Ok(val) => match serde_json::from_value(val) {
+            Ok(u) => u,
+            Err(e) => return error_response(&format!("Failed to parse user: {e}"), StatusCode::INTERNAL_SERVER_ERROR),
+        },
+        Err(e) => return error_response(&e, StatusCode::UNAUTHORIZED),
+    };
+
+    let removed = item_composition::Entity::update_many()
+        .col_expr(item_composition::Column::UseYn, Expr::value("N"))
+        .col_expr(item_composition::Column::UpdatedAt, Expr::value(Utc::now().naive_utc()))
+        .filter(item_composition::Column::Tin.eq(&user.pin))
+        .filter(item_composition::Column::BhfId.eq(&user.branch_id))
+        .filter(item_composition::Column::ItemCd.eq(&query.item_cd))
+        .filter(item_composition::Column::CpstItemCd.eq(&query.cpst_item_cd))
+        .filter(item_composition::Column::UseYn.eq("Y"))
+        .exec(db.as_ref())
+        .await;
+
+    match removed {
+        Ok(res) if res.rows_affected == 0 => error_response(
+            &format!("{} is not a component of {}", query.cpst_item_cd, query.item_cd),
+            StatusCode::NOT_FOUND,
+        ),
+        Ok(_) => (
+            StatusCode::OK,
+            Json(json!({
+                "resultCd": "000",
+                "resultMsg": "Component removed",
+                "itemCd": query.item_cd,
+                "cpstItemCd": query.cpst_item_cd,
+            })),
+        ),
+        Err(e) => error_response(&format!("Failed to remove component: {e}"), StatusCode::INTERNAL_SERVER_ERROR),
+    }
+}
+
+/// Composition lines of the branch, optionally for one composite item
+async fn list_composition(
+    TypedHeader(auth): TypedHeader<Authorization<Bearer>>,
+    State(db): State<Arc<DatabaseConnection>>,
+    Query(query): Query<ItemCompositionQuery>,
+) -> impl IntoResponse {
+    let user: AuthUser = match bearer_resolver(auth.token(), db.as_ref()).await {
+        Ok(val) => match serde_json::from_value(val) {
+            Ok(u) => u,
+            Err(e) => return error_response(&format!("Failed to parse user: {e}"), StatusCode::INTERNAL_SERVER_ERROR),
+        },
+        Err(e) => return error_response(&e, StatusCode::UNAUTHORIZED),
+    };
+
+    let mut select = item_composition::Entity::find()
+        .filter(item_composition::Column::Tin.eq(&user.pin))
+        .filter(item_composition::Column::BhfId.eq(&user.branch_id));
+    if let Some(item_cd) = query.item_cd {
+        select = select.filter(item_composition::Column::ItemCd.eq(item_cd));
+    }
+
+    match select
+        .order_by_asc(item_composition::Column::ItemCd)
+        .order_by_asc(item_composition::Column::CpstItemCd)
+        .all(db.as_ref())
+        .await
+    {
+        Ok(rows) => (
+            StatusCode::OK,
+            Json(json!({
+                "resultCd": "000",
+                "resultMsg": "Success",
+                "data": rows,
+            })),
+        ),
+        Err(e) => error_response(&format!("Failed to fetch item composition: {e}"), StatusCode::INTERNAL_SERVER_ERROR),
+    }
+}
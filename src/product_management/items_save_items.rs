@@ -4,18 +4,21 @@ use axum::{Json, Router, extract::State, response::IntoResponse, routing::post};
 use axum_extra::TypedHeader;
 use headers::{Authorization, authorization::Bearer};
 use reqwest::StatusCode;
-use sea_orm::{ActiveModelTrait, ActiveValue::Set, DatabaseConnection, EntityTrait, TransactionTrait};
+use sea_orm::{ActiveModelTrait, ActiveValue::Set, ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter, TransactionTrait};
 use serde_json::json;
 use tracing::{info, error};
 use crate::{
-    models::product_save_items::{ActiveModel, Entity}, 
+    models::{
+        item_class::{Column as ItemClassColumn, Entity as ItemClassEntity},
+        product_save_items::{ActiveModel, Entity},
+    },
     stock_management::route_stock_master::error_response, 
     types::{
-        product_management_payload_types::ItemSaveReq, 
+        product_management_payload_types::{Item, ItemSaveReq},
         salespayloadtype::AuthUser
     }, 
     utils::{bearer::bearer_resolver, crypto::{decrypt, decrypt_deterministic}},
-    
+    vscu::client::VscuClient,
 };
 
 pub fn items_save_items_router(db: Arc<DatabaseConnection>) -> Router {
@@ -52,9 +55,29 @@ async fn save_item(
         }
     };
 
-    // 2️⃣ START TRANSACTION & INSERT ALL ITEMS
     let items = payload.0;
     let items_count = items.len();
+
+    // 2️⃣ CHECK CLASSIFICATION CODES AGAINST THE SYNCED KRA TREE
+    match unknown_item_classes(db.as_ref(), &items).await {
+        Ok(unknown) if !unknown.is_empty() => {
+            return (
+                StatusCode::BAD_REQUEST,
+                Json(json!({
+                    "resultCd": "400",
+                    "resultMsg": "Unknown item classification codes",
+                    "itemClsCd": unknown
+                })),
+            );
+        }
+        Ok(_) => {}
+        Err(e) => return error_response(
+            &format!("Failed to check item classes: {e}"),
+            StatusCode::INTERNAL_SERVER_ERROR
+        ),
+    }
+
+    // 3️⃣ START TRANSACTION & INSERT ALL ITEMS
     
     let mut txn = match db.begin().await {
         Ok(t) => t,
@@ -121,7 +144,7 @@ async fn save_item(
         }
     }
 
-    // 3️⃣ COMMIT TRANSACTION
+    // 4️⃣ COMMIT TRANSACTION
     match txn.commit().await {
         Ok(_) => {
             info!("Transaction committed successfully. {} items inserted.", inserted_ids.len());
@@ -134,14 +157,15 @@ async fn save_item(
         }
     }
 
-    // 4️⃣ BACKGROUND PROCESSING - Send to KRA
+    // 5️⃣ BACKGROUND PROCESSING - Send to KRA
     // Spawn async task so we don't block the response
     let db_clone = db.clone();
+    let vscu = VscuClient::for_user(&user);
     tokio::spawn(async move {
-        process_kra_submissions(db_clone, inserted_ids).await;
+        process_kra_submissions(db_clone, vscu, inserted_ids).await;
     });
 
-    // 5️⃣ RETURN SUCCESS IMMEDIATELY
+    // 6️⃣ RETURN SUCCESS IMMEDIATELY
     (
         StatusCode::OK,
         Json(json!({
@@ -152,8 +176,30 @@ async fn save_item(
     )
 }
 
+/// Item class codes in `items` that are not in the synced `item_class` table.
+/// Nothing is rejected until the classification tree has been synced at least once.
+async fn unknown_item_classes(db: &DatabaseConnection, items: &[Item]) -> Result<Vec<String>, sea_orm::DbErr> {
+    if ItemClassEntity::find().one(db).await?.is_none() {
+        return Ok(Vec::new());
+    }
+
+    let mut codes: Vec<String> = items.iter().map(|i| i.item_cls_cd.clone()).collect();
+    codes.sort();
+    codes.dedup();
+
+    let known: Vec<String> = ItemClassEntity::find()
+        .filter(ItemClassColumn::ItemClsCd.is_in(codes.clone()))
+        .all(db)
+        .await?
+        .into_iter()
+        .map(|c| c.item_cls_cd)
+        .collect();
+
+    Ok(codes.into_iter().filter(|c| !known.contains(c)).collect())
+}
+
 // FIXED: Changed return type to () instead of Result<()> to avoid Send trait issues
-async fn process_kra_submissions(db: Arc<DatabaseConnection>, inserted_ids: Vec<i64>) {
+async fn process_kra_submissions(db: Arc<DatabaseConnection>, vscu: VscuClient, inserted_ids: Vec<i64>) {
     for id in inserted_ids {
         // Fetch the record we just inserted
         let record = match Entity::find_by_id(id).one(db.as_ref()).await {
@@ -231,53 +277,23 @@ async fn process_kra_submissions(db: Arc<DatabaseConnection>, inserted_ids: Vec<
             "modrId": record.modr_id,
         });
 
-        // Send to KRA
-        let client = match reqwest::Client::builder()
-            .timeout(tokio::time::Duration::from_secs(30))
-            .build()
-        {
-            Ok(c) => c,
-            Err(e) => {
-                error!("Failed to build HTTP client: {}", e);
-                continue;
-            }
-        };
-
-        match client
-            .post("http://192.168.1.71:8088/items/saveItems")
-            .json(&kra_payload)
-            .send()
-            .await
-        {
-            Ok(response) => {
-                if !response.status().is_success() {
-                    error!("KRA returned error status: {} for record {}", response.status(), id);
-                    let _ = mark_as_failed_with_retry(db.as_ref(), id, 0).await;
-                    continue;
-                }
+        // Send to the device's VSCU
+        match vscu.save_items(&kra_payload).await {
+            Ok(kra_response) => {
+                info!("Received response from KRA for item ID: {}", id);
 
-                match response.json::<serde_json::Value>().await {
-                    Ok(kra_response) => {
-                        info!("Received response from KRA for item ID: {}", id);
-                        
-                        // Update record with response and status
-                        if let Err(e) = update_record_with_response(
-                            db.as_ref(),
-                            id,
-                            kra_response,
-                            "TRANSMITTED"
-                        ).await {
-                            error!("Failed to update record {} with KRA response: {}", id, e);
-                        }
-                    }
-                    Err(e) => {
-                        error!("Failed to parse KRA response for record {}: {}", id, e);
-                        let _ = mark_as_failed_with_retry(db.as_ref(), id, 0).await;
-                    }
+                // Update record with response and status
+                if let Err(e) = update_record_with_response(
+                    db.as_ref(),
+                    id,
+                    kra_response,
+                    "TRANSMITTED"
+                ).await {
+                    error!("Failed to update record {} with KRA response: {}", id, e);
                 }
             }
             Err(e) => {
-                error!("Failed to send to KRA endpoint for record {}: {}", id, e);
+                error!("Failed to transmit record {}: {}", id, e);
                 let _ = mark_as_failed_with_retry(db.as_ref(), id, 0).await;
             }
         }
@@ -1,38 +1,207 @@
 use std::sync::Arc;
 
-use axum::{Json, Router, extract::State, response::IntoResponse, routing::post};
-use sea_orm::{DatabaseConnection, TransactionTrait};
-use tracing::info;
+use axum::{Json, Router, extract::State, http::StatusCode, response::IntoResponse, routing::post};
+use axum_extra::TypedHeader;
+use headers::{Authorization, authorization::Bearer};
+use sea_orm::{
+    ActiveValue::Set, ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter, QueryOrder,
+    TransactionTrait, sea_query::OnConflict,
+};
+use serde_json::json;
+use tracing::{info, error};
 
-use crate::{stock_management::route_stock_master::error_response, types::info::VerificationInfo};
+use crate::{
+    models::product_save_items::{ActiveModel, Column, Entity},
+    stock_management::route_stock_master::error_response,
+    types::{
+        product_management_payload_types::{ItemSelectReq, KraItem},
+        salespayloadtype::AuthUser,
+    },
+    utils::{
+        bearer::bearer_resolver,
+        sync_state::{last_req_dt, mark_synced, now_req_dt},
+    },
+    vscu::{device::Device, result::ResultClass},
+};
 
-pub fn items_save_items_router(db: Arc<DatabaseConnection>) -> Router {
+pub const RESOURCE: &str = "items";
+
+pub fn items_select_items_router(db: Arc<DatabaseConnection>) -> Router {
     Router::new()
-        .route("/", post(save_item).get(get_items))
+        .route("/", post(select_items))
         .with_state(db)
 }
 
-async fn save_item(
+/// Pull the device's items from VSCU `items/selectItems`, reconcile them into
+/// `item_master` and return the merged list
+async fn select_items(
+    TypedHeader(auth): TypedHeader<Authorization<Bearer>>,
     State(db): State<Arc<DatabaseConnection>>,
-    Json(payload): Json<VerificationInfo>,
+    payload: Option<Json<ItemSelectReq>>,
 ) -> impl IntoResponse {
-    // // Handler logic here
-    // info!("Received payload: {:?}", payload);
-    // info!("Database connection established. processing starting...");
+    let token = auth.token();
+    let payload = payload.map(|Json(p)| p).unwrap_or_default();
+
+    // 1️⃣ AUTH FIRST
+    let user: AuthUser = match bearer_resolver(token, db.as_ref()).await {
+        Ok(val) => match serde_json::from_value(val) {
+            Ok(u) => u,
+            Err(e) => {
+                return (
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    Json(json!({ "message": format!("Failed to parse user: {}", e) })),
+                )
+            }
+        },
+        Err(e) => {
+            return (
+                StatusCode::UNAUTHORIZED,
+                Json(json!({ "message": e })),
+            )
+        }
+    };
+
+    let device = match Device::from_user(&user) {
+        Ok(d) => d,
+        Err(e) => return error_response(&e, StatusCode::INTERNAL_SERVER_ERROR),
+    };
+
+    // 2️⃣ ASK THE VSCU FOR EVERYTHING CHANGED SINCE lastReqDt
+    let since = match payload.last_req_dt {
+        Some(dt) => dt,
+        None => match last_req_dt(db.as_ref(), device.credentials_id, RESOURCE).await {
+            Ok(dt) => dt,
+            Err(e) => return error_response(&format!("Failed to read sync state: {e}"), StatusCode::INTERNAL_SERVER_ERROR),
+        },
+    };
+    let started_at = now_req_dt();
+
+    let res = match device.client.select_items(&device.select_request(&since)).await {
+        Ok(r) => r,
+        Err(e) => {
+            error!("selectItems failed: {}", e);
+            return error_response(&e.to_string(), StatusCode::BAD_GATEWAY);
+        }
+    };
+
+    if !res.is_empty_search() && res.class() != ResultClass::Success {
+        return (
+            StatusCode::BAD_GATEWAY,
+            Json(json!({
+                "resultCd": res.result_cd,
+                "resultMsg": res.result_msg,
+            })),
+        );
+    }
+
+    // 3️⃣ RECONCILE INTO item_master
+    let kra_items = res.data.map(|d| d.item_list).unwrap_or_default();
+    let synced = kra_items.len();
 
-    // let mut initalize =match db.begin().await {
-    //     Ok(t) => t,
-    //     Err(e) => return error_response(&format!("Failed to start transaction: {e}"), StatusCode::INTERNAL_SERVER_ERROR),
-    // }
+    if let Err(e) = reconcile_items(db.as_ref(), &user, kra_items).await {
+        return error_response(&format!("Failed to reconcile items: {e}"), StatusCode::INTERNAL_SERVER_ERROR);
+    }
 
-    // let data = Ac
+    if let Err(e) = mark_synced(db.as_ref(), device.credentials_id, RESOURCE, &started_at).await {
+        error!("Failed to record items sync for device {}: {}", device.credentials_id, e);
+    }
 
+    info!("Reconciled {} items from VSCU for device {}", synced, device.credentials_id);
 
-    //use reqwest to fetch this data instead of creating a whole complex thing since i do not intend to store the data in this particulat handler
+    // 4️⃣ RETURN THE MERGED LIST
+    match Entity::find()
+        .filter(Column::Tin.eq(&user.pin))
+        .filter(Column::BhfId.eq(&user.branch_id))
+        .order_by_asc(Column::ItemCd)
+        .all(db.as_ref())
+        .await
+    {
+        Ok(items) => (
+            StatusCode::OK,
+            Json(json!({
+                "resultCd": "000",
+                "resultMsg": "Success",
+                "lastReqDt": started_at,
+                "synced": synced,
+                "data": items,
+            })),
+        ),
+        Err(e) => error_response(&format!("Failed to fetch items: {e}"), StatusCode::INTERNAL_SERVER_ERROR),
+    }
 }
 
-async fn get_items(
-    State(db): State<Arc<DatabaseConnection>>,
-) -> impl IntoResponse {
-    // Handler logic here
-}
\ No newline at end of file
+/// Upsert KRA's view of each item. Items we did not know about are added as
+/// SYNCED; local rows keep their transmission status and get KRA's values.
+async fn reconcile_items(
+    db: &DatabaseConnection,
+    user: &AuthUser,
+    kra_items: Vec<KraItem>,
+) -> Result<(), sea_orm::DbErr> {
+    let txn = db.begin().await?;
+
+    for item in kra_items {
+        let model = ActiveModel {
+            tin: Set(user.pin.clone()),
+            bhf_id: Set(user.branch_id.clone()),
+            status: Set("SYNCED".to_string()),
+            item_cd: Set(item.item_cd),
+            item_cls_cd: Set(item.item_cls_cd),
+            item_ty_cd: Set(item.item_ty_cd),
+            item_nm: Set(item.item_nm),
+            item_std_nm: Set(item.item_std_nm),
+            orgn_nat_cd: Set(item.orgn_nat_cd),
+            pkg_unit_cd: Set(item.pkg_unit_cd),
+            qty_unit_cd: Set(item.qty_unit_cd),
+            tax_ty_cd: Set(item.tax_ty_cd),
+            btch_no: Set(item.btch_no.unwrap_or_default()),
+            bcd: Set(item.bcd.unwrap_or_default()),
+            dft_prc: Set(item.dft_prc),
+            grp_prc_l1: Set(item.grp_prc_l1),
+            grp_prc_l2: Set(item.grp_prc_l2),
+            grp_prc_l3: Set(item.grp_prc_l3),
+            grp_prc_l4: Set(item.grp_prc_l4),
+            grp_prc_l5: Set(item.grp_prc_l5),
+            add_info: Set(item.add_info),
+            sfty_qty: Set(item.sfty_qty),
+            isrc_aplcb_yn: Set(item.isrc_aplcb_yn),
+            use_yn: Set(item.use_yn),
+            regr_nm: Set("VSCU".to_string()),
+            regr_id: Set("VSCU".to_string()),
+            modr_nm: Set("VSCU".to_string()),
+            modr_id: Set("VSCU".to_string()),
+            ..Default::default()
+        };
+
+        Entity::insert(model)
+            .on_conflict(
+                OnConflict::columns([Column::Tin, Column::BhfId, Column::ItemCd])
+                    .update_columns([
+                        Column::ItemClsCd,
+                        Column::ItemTyCd,
+                        Column::ItemNm,
+                        Column::ItemStdNm,
+                        Column::OrgnNatCd,
+                        Column::PkgUnitCd,
+                        Column::QtyUnitCd,
+                        Column::TaxTyCd,
+                        Column::BtchNo,
+                        Column::Bcd,
+                        Column::DftPrc,
+                        Column::GrpPrcL1,
+                        Column::GrpPrcL2,
+                        Column::GrpPrcL3,
+                        Column::GrpPrcL4,
+                        Column::GrpPrcL5,
+                        Column::AddInfo,
+                        Column::SftyQty,
+                        Column::IsrcAplcbYn,
+                        Column::UseYn,
+                    ])
+                    .to_owned(),
+            )
+            .exec(&txn)
+            .await?;
+    }
+
+    txn.commit().await
+}
@@ -1,2 +1,6 @@
 pub mod items_save_items;
-
+pub mod items_select_items;
+pub mod route_item_classes;
+pub mod sync_item_classes;
+pub mod item_composition;
+pub mod transmit_item_composition;
@@ -0,0 +1,70 @@
+use std::sync::Arc;
+
+use axum::{
+    Json, Router,
+    extract::{Query, State},
+    http::StatusCode,
+    response::IntoResponse,
+    routing::get,
+};
+use axum_extra::TypedHeader;
+use headers::{Authorization, authorization::Bearer};
+use sea_orm::{
+    ColumnTrait, Condition, DatabaseConnection, EntityTrait, QueryFilter, QueryOrder, QuerySelect,
+    sea_query::{Expr, extension::postgres::PgExpr},
+};
+use serde_json::json;
+
+use crate::{
+    models::item_class::{Column, Entity},
+    stock_management::route_stock_master::error_response,
+    types::product_management_payload_types::ItemClassQuery,
+    utils::bearer::bearer_resolver,
+};
+
+pub fn item_classes_router(db: Arc<DatabaseConnection>) -> Router {
+    Router::new()
+        .route("/", get(search_item_classes))
+        .with_state(db)
+}
+
+/// Search the cached classification tree by code prefix or name
+/// (`?q=1010&lvl=4&limit=50`)
+async fn search_item_classes(
+    TypedHeader(auth): TypedHeader<Authorization<Bearer>>,
+    State(db): State<Arc<DatabaseConnection>>,
+    Query(query): Query<ItemClassQuery>,
+) -> impl IntoResponse {
+    if let Err(e) = bearer_resolver(auth.token(), db.as_ref()).await {
+        return error_response(&e, StatusCode::UNAUTHORIZED);
+    }
+
+    let mut select = Entity::find()
+        .filter(Column::UseYn.eq("Y"))
+        .order_by_asc(Column::ItemClsCd)
+        .limit(query.limit.unwrap_or(100).min(500));
+
+    if let Some(q) = query.q.as_deref().map(str::trim).filter(|q| !q.is_empty()) {
+        select = select.filter(
+            Condition::any()
+                .add(Column::ItemClsCd.starts_with(q))
+                .add(Expr::col(Column::ItemClsNm).ilike(format!("%{}%", q))),
+        );
+    }
+
+    if let Some(lvl) = query.lvl {
+        select = select.filter(Column::ItemClsLvl.eq(lvl));
+    }
+
+    match select.all(db.as_ref()).await {
+        Ok(classes) => (
+            StatusCode::OK,
+            Json(json!({
+                "resultCd": "000",
+                "resultMsg": "Success",
+                "data": classes,
+            })),
+        ),
+        Err(e) => error_response(&format!("Failed to fetch item classes: {e}"), StatusCode::INTERNAL_SERVER_ERROR),
+    }
+}
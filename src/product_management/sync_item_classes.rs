@@ -0,0 +1,88 @@
+use sea_orm::{
+    ActiveValue::Set, DatabaseConnection, EntityTrait, TransactionTrait, sea_query::OnConflict,
+};
+use chrono::Utc;
+use tracing::{info, warn};
+
+use crate::{
+    models::item_class::{ActiveModel, Column, Entity},
+    types::product_management_payload_types::ItemClassItem,
+    utils::sync_state::{last_req_dt, mark_synced, now_req_dt},
+    vscu::{device::Device, result::ResultClass},
+};
+
+pub const RESOURCE: &str = "item_classes";
+
+/// Pull item classifications changed since the device's last sync and upsert them
+pub async fn sync_item_classes(db: &DatabaseConnection, device: &Device) -> Result<usize, String> {
+    let since = last_req_dt(db, device.credentials_id, RESOURCE)
+        .await
+        .map_err(|e| e.to_string())?;
+    let started_at = now_req_dt();
+
+    let res = device
+        .client
+        .select_item_classes(&device.select_request(&since))
+        .await
+        .map_err(|e| e.to_string())?;
+
+    if res.is_empty_search() {
+        info!("No item class changes for device {} since {}", device.credentials_id, since);
+        mark_synced(db, device.credentials_id, RESOURCE, &started_at)
+            .await
+            .map_err(|e| e.to_string())?;
+        return Ok(0);
+    }
+
+    if res.class() != ResultClass::Success {
+        warn!("selectItemsClass failed for device {}: {} {}", device.credentials_id, res.result_cd, res.result_msg);
+        return Err(format!("{} {}", res.result_cd, res.result_msg));
+    }
+
+    let classes = res.data.map(|d| d.item_cls_list).unwrap_or_default();
+    let count = classes.len();
+
+    store_item_classes(db, classes).await.map_err(|e| e.to_string())?;
+    mark_synced(db, device.credentials_id, RESOURCE, &started_at)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    info!("Synced {} item classes for device {}", count, device.credentials_id);
+    Ok(count)
+}
+
+async fn store_item_classes(db: &DatabaseConnection, classes: Vec<ItemClassItem>) -> Result<(), sea_orm::DbErr> {
+    let now = Utc::now().naive_utc();
+    let txn = db.begin().await?;
+
+    for class in classes {
+        let model = ActiveModel {
+            item_cls_cd: Set(class.item_cls_cd),
+            item_cls_nm: Set(class.item_cls_nm),
+            item_cls_lvl: Set(class.item_cls_lvl),
+            tax_ty_cd: Set(class.tax_ty_cd),
+            mjr_tg_yn: Set(class.mjr_tg_yn),
+            use_yn: Set(class.use_yn),
+            updated_at: Set(now),
+            ..Default::default()
+        };
+
+        Entity::insert(model)
+            .on_conflict(
+                OnConflict::column(Column::ItemClsCd)
+                    .update_columns([
+                        Column::ItemClsNm,
+                        Column::ItemClsLvl,
+                        Column::TaxTyCd,
+                        Column::MjrTgYn,
+                        Column::UseYn,
+                        Column::UpdatedAt,
+                    ])
+                    .to_owned(),
+            )
+            .exec(&txn)
+            .await?;
+    }
+
+    txn.commit().await
+}
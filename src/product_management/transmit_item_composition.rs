@@ -0,0 +1,149 @@
+use chrono::Utc;
+use rust_decimal::prelude::ToPrimitive;
+use sea_orm::{
+    ActiveModelTrait, ActiveValue::Set, ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter,
+};
+use serde_json::{Value, json};
+use tracing::{info, error, warn};
+
+use crate::{
+    models::item_composition::{ActiveModel, Column, Entity, Model},
+    sales::status,
+    utils::retry::{MAX_RETRIES, claim, is_due, next_retry_at, retryable},
+    vscu::{device::Device, result::{ResultClass, VscuResponse}},
+};
+
+/// Send a stored composition line to `items/saveItemComposition`
+pub async fn transmit_item_composition(db: &DatabaseConnection, device: &Device, id: i32) {
+    match Entity::find_by_id(id).one(db).await {
+        Ok(Some(record)) => send_and_record(db, device, record).await,
+        Ok(None) => error!("Item composition {} not found", id),
+        Err(e) => error!("Failed to fetch item composition {}: {}", id, e),
+    }
+}
+
+/// Retry FAILED/stuck composition lines whose backoff has elapsed, and ones
+/// whose first send never happened
+pub async fn retry_failed_item_compositions(db: &DatabaseConnection) -> Result<(), sea_orm::DbErr> {
+    let records = Entity::find()
+        .filter(retryable(&["updated_at", "created_at"]))
+        .filter(Column::RetryCount.lt(MAX_RETRIES))
+        .all(db)
+        .await?;
+
+    for record in records {
+        if !is_due(record.next_retry_at.as_deref()) {
+            continue;
+        }
+
+        let device = match Device::find_by_identity(db, &record.tin, &record.bhf_id).await {
+            Ok(d) => d,
+            Err(e) => {
+                error!("Cannot retry item composition {}: {}", record.id, e);
+                continue;
+            }
+        };
+
+        info!("🔄 Retrying item composition {} (attempt {}/{})", record.id, record.retry_count + 1, MAX_RETRIES);
+        send_and_record(db, &device, record).await;
+    }
+
+    Ok(())
+}
+
+async fn send_and_record(db: &DatabaseConnection, device: &Device, record: Model) {
+    let id = record.id;
+    let retry_count = record.retry_count;
+
+    let record = match claim::<Entity, _>(db, id, &[status::RECEIVED, status::FAILED]).await {
+        Ok(Some(r)) => r,
+        Ok(None) => {
+            info!("Item composition {} is already being sent, skipped", id);
+            return;
+        }
+        Err(e) => {
+            error!("Failed to lock item composition {}: {}", id, e);
+            return;
+        }
+    };
+
+    let kra_payload = json!({
+        "tin": device.tin,
+        "bhfId": device.bhf_id,
+        "itemCd": record.item_cd,
+        "cpstItemCd": record.cpst_item_cd,
+        "cpstQty": record.cpst_qty.to_f64(),
+        "regrId": record.regr_id,
+        "regrNm": record.regr_nm,
+    });
+
+    let outcome = match device.client.save_item_composition(&kra_payload).await {
+        Ok(res) => record_response(db, id, retry_count, &res).await,
+        Err(e) => {
+            error!("Failed to transmit item composition {}: {}", id, e);
+            schedule_retry(db, id, retry_count, None).await
+        }
+    };
+
+    if let Err(e) = outcome {
+        error!("Failed to update item composition {}: {}", id, e);
+    }
+}
+
+async fn record_response(
+    db: &DatabaseConnection,
+    id: i32,
+    retry_count: i64,
+    res: &VscuResponse<Value>,
+) -> Result<(), sea_orm::DbErr> {
+    let response = serde_json::to_value(res).ok();
+
+    let new_status = match res.class() {
+        ResultClass::Success => status::TRANSMITTED,
+        ResultClass::Rejected => {
+            warn!("Item composition {} rejected by KRA: {} {}", id, res.result_cd, res.result_msg);
+            status::REJECTED
+        }
+        ResultClass::Retryable => return schedule_retry(db, id, retry_count, response).await,
+    };
+
+    let mut model: ActiveModel = find(db, id).await?.into();
+    model.status = Set(new_status.to_string());
+    model.response = Set(response);
+    model.next_retry_at = Set(None);
+    model.updated_at = Set(Some(Utc::now().naive_utc()));
+    model.update(db).await?;
+
+    info!("Item composition {} is {}", id, new_status);
+    Ok(())
+}
+
+async fn schedule_retry(
+    db: &DatabaseConnection,
+    id: i32,
+    retry_count: i64,
+    response: Option<Value>,
+) -> Result<(), sea_orm::DbErr> {
+    let new_retry_count = retry_count + 1;
+    let next = next_retry_at(new_retry_count);
+
+    let mut model: ActiveModel = find(db, id).await?.into();
+    model.status = Set(status::FAILED.to_string());
+    model.retry_count = Set(new_retry_count);
+    model.next_retry_at = Set(Some(next.clone()));
+    model.updated_at = Set(Some(Utc::now().naive_utc()));
+    if response.is_some() {
+        model.response = Set(response);
+    }
+    model.update(db).await?;
+
+    info!("Item composition {} FAILED ({}/{}), next retry at {}", id, new_retry_count, MAX_RETRIES, next);
+    Ok(())
+}
+
+async fn find(db: &DatabaseConnection, id: i32) -> Result<Model, sea_orm::DbErr> {
+    Entity::find_by_id(id)
+        .one(db)
+        .await?
+        .ok_or(sea_orm::DbErr::RecordNotFound(format!("ID {}", id)))
+}
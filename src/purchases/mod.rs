@@ -0,0 +1,4 @@
+pub mod routing;
+pub mod status;
+pub mod sync_purchases;
+pub mod transmit_purchases;
@@ -0,0 +1,436 @@
+use std::sync::Arc;
+
+use axum::{
+    Json, Router,
+    extract::{Path, Query, State},
+    http::StatusCode,
+    response::IntoResponse,
+    routing::{get, post},
+};
+use axum_extra::TypedHeader;
+use chrono::{Local, Utc};
+use headers::{Authorization, authorization::Bearer};
+use sea_orm::{
+    ActiveModelTrait, ActiveValue::Set, ColumnTrait, DatabaseConnection, DatabaseTransaction,
+    EntityTrait, QueryFilter, QueryOrder, QuerySelect, TransactionTrait,
+};
+use serde_json::{Value, json};
+use tracing::info;
+
+use crate::{
+    models::{purchase, purchase_item},
+    purchases::{
+        status::{APPROVED, CANCELLED, PENDING},
+        sync_purchases::sync_purchases,
+        transmit_purchases::transmit_purchase,
+    },
+    sales::status,
+    stock_management::{
+        route_stock_master::error_response,
+        route_stock_movements::{StockError, record_movement},
+        transmit_stock_master::transmit_stock_master,
+        transmit_stock_movements::transmit_stock_movement,
+    },
+    types::{
+        purchases::{PurchaseAcceptReq, PurchaseQuery, PurchaseRejectReq},
+        salespayloadtype::AuthUser,
+        stock_management::{ItemDetail, StockItem},
+    },
+    utils::{bearer::bearer_resolver, counters::Counter},
+    vscu::device::Device,
+};
+
+/// sarTyCd for goods coming in through a purchase
+const STOCK_IN_PURCHASE: &str = "02";
+
+pub fn purchases_router(db: Arc<DatabaseConnection>) -> Router {
+    Router::new()
+        .route("/", get(list_purchases))
+        .route("/sync", post(sync_now))
+        .route("/{id}/accept", post(accept_purchase))
+        .route("/{id}/reject", post(reject_purchase))
+        .with_state(db)
+}
+
+async fn authorize(
+    token: &str,
+    db: &DatabaseConnection,
+) -> Result<(AuthUser, Device), (StatusCode, Json<Value>)> {
+    let user: AuthUser = match bearer_resolver(token, db).await {
+        Ok(val) => serde_json::from_value(val).map_err(|e| {
+            error_response(&format!("Failed to parse user: {e}"), StatusCode::INTERNAL_SERVER_ERROR)
+        })?,
+        Err(e) => return Err(error_response(&e, StatusCode::UNAUTHORIZED)),
+    };
+
+    let device = Device::from_user(&user).map_err(|e| error_response(&e, StatusCode::INTERNAL_SERVER_ERROR))?;
+
+    Ok((user, device))
+}
+
+/// Purchases of the device, newest supplier invoice first
+async fn list_purchases(
+    TypedHeader(auth): TypedHeader<Authorization<Bearer>>,
+    State(db): State<Arc<DatabaseConnection>>,
+    Query(query): Query<PurchaseQuery>,
+) -> impl IntoResponse {
+    let (user, _) = match authorize(auth.token(), db.as_ref()).await {
+        Ok(found) => found,
+        Err(res) => return res,
+    };
+
+    let mut select = purchase::Entity::find().filter(purchase::Column::CredentialsId.eq(user.id));
+    if let Some(status) = query.status {
+        select = select.filter(purchase::Column::Status.eq(status));
+    }
+
+    match select
+        .order_by_desc(purchase::Column::CfmDt)
+        .find_with_related(purchase_item::Entity)
+        .all(db.as_ref())
+        .await
+    {
+        Ok(rows) => {
+            let data: Vec<_> = rows
+                .into_iter()
+                .map(|(purchase, items)| json!({ "purchase": purchase, "itemList": items }))
+                .collect();
+
+            (
+                StatusCode::OK,
+                Json(json!({
+                    "resultCd": "000",
+                    "resultMsg": "Success",
+                    "data": data,
+                })),
+            )
+        }
+        Err(e) => error_response(&format!("Failed to fetch purchases: {e}"), StatusCode::INTERNAL_SERVER_ERROR),
+    }
+}
+
+/// Pull new supplier sales now instead of waiting for the background sync
+async fn sync_now(
+    TypedHeader(auth): TypedHeader<Authorization<Bearer>>,
+    State(db): State<Arc<DatabaseConnection>>,
+) -> impl IntoResponse {
+    let (_, device) = match authorize(auth.token(), db.as_ref()).await {
+        Ok(found) => found,
+        Err(res) => return res,
+    };
+
+    match sync_purchases(db.as_ref(), &device).await {
+        Ok(stored) => (
+            StatusCode::OK,
+            Json(json!({
+                "resultCd": "000",
+                "resultMsg": "Purchases synced",
+                "stored": stored,
+            })),
+        ),
+        Err(e) => error_response(&format!("Purchase sync failed: {e}"), StatusCode::BAD_GATEWAY),
+    }
+}
+
+/// Accept a supplier sale, optionally booking the goods in as stock
+async fn accept_purchase(
+    TypedHeader(auth): TypedHeader<Authorization<Bearer>>,
+    State(db): State<Arc<DatabaseConnection>>,
+    Path(id): Path<i32>,
+    Json(payload): Json<PurchaseAcceptReq>,
+) -> impl IntoResponse {
+    let (user, device) = match authorize(auth.token(), db.as_ref()).await {
+        Ok(found) => found,
+        Err(res) => return res,
+    };
+
+    let txn = match db.begin().await {
+        Ok(t) => t,
+        Err(e) => return error_response(&format!("Failed to start transaction: {e}"), StatusCode::INTERNAL_SERVER_ERROR),
+    };
+
+    let record = match load_pending(&txn, user.id, id).await {
+        Ok(r) => r,
+        Err(res) => {
+            let _ = txn.rollback().await;
+            return res;
+        }
+    };
+
+    // 1️⃣ MAP SUPPLIER LINES TO OUR ITEM CODES
+    let mut items = match purchase_item::Entity::find()
+        .filter(purchase_item::Column::PurchaseId.eq(id))
+        .order_by_asc(purchase_item::Column::ItemSeq)
+        .all(&txn)
+        .await
+    {
+        Ok(items) => items,
+        Err(e) => {
+            let _ = txn.rollback().await;
+            return error_response(&format!("Failed to load purchase lines: {e}"), StatusCode::INTERNAL_SERVER_ERROR);
+        }
+    };
+
+    for item in items.iter_mut() {
+        let item_cd = payload
+            .item_map
+            .iter()
+            .find(|m| m.item_seq == item.item_seq)
+            .map(|m| m.item_cd.clone())
+            .or_else(|| item.spplr_item_cd.clone());
+
+        let mut model: purchase_item::ActiveModel = item.clone().into();
+        model.item_cd = Set(item_cd);
+        match model.update(&txn).await {
+            Ok(updated) => *item = updated,
+            Err(e) => {
+                let _ = txn.rollback().await;
+                return error_response(&format!("Failed to map purchase line: {e}"), StatusCode::INTERNAL_SERVER_ERROR);
+            }
+        }
+    }
+
+    if payload.create_stock_in
+        && let Some(item) = items.iter().find(|i| i.item_cd.is_none())
+    {
+        let _ = txn.rollback().await;
+        return error_response(
+            &format!("Line {} has no item code, map it with itemMap to book it in as stock", item.item_seq),
+            StatusCode::BAD_REQUEST,
+        );
+    }
+
+    // 2️⃣ RECORD THE DECISION
+    let pchs_dt = Local::now().format("%Y%m%d").to_string();
+    let invc_no = match next_invc_no(&txn, user.id).await {
+        Ok(n) => n,
+        Err(e) => {
+            let _ = txn.rollback().await;
+            return error_response(&format!("Failed to allocate invoice number: {e}"), StatusCode::INTERNAL_SERVER_ERROR);
+        }
+    };
+
+    let mut model: purchase::ActiveModel = record.clone().into();
+    model.pchs_stts_cd = Set(APPROVED.to_string());
+    model.invc_no = Set(Some(invc_no));
+    model.pchs_ty_cd = Set(Some(payload.pchs_ty_cd.clone().unwrap_or_else(|| "N".to_string())));
+    if payload.pmt_ty_cd.is_some() {
+        model.pmt_ty_cd = Set(payload.pmt_ty_cd.clone());
+    }
+    model.pchs_dt = Set(Some(pchs_dt.clone()));
+    model.remark = Set(payload.remark.clone());
+    model.regr_nm = Set(Some(payload.regr_nm.clone()));
+    model.regr_id = Set(Some(payload.regr_id.clone()));
+    model.status = Set(status::RECEIVED.to_string());
+    model.updated_at = Set(Some(Utc::now().naive_utc()));
+
+    // 3️⃣ OPTIONAL STOCK-IN MOVEMENT
+    let mut stock = None;
+    if payload.create_stock_in {
+        let movement = stock_in_movement(&record, &items, &payload, &pchs_dt);
+        match record_movement(&txn, &user, &movement).await {
+            Ok((saved, master_ids)) => {
+                model.stock_movement_id = Set(Some(saved.id));
+                stock = Some((saved.id, master_ids));
+            }
+            Err(StockError::Invalid(msg)) => {
+                let _ = txn.rollback().await;
+                return error_response(&msg, StatusCode::BAD_REQUEST);
+            }
+            Err(StockError::Db(e)) => {
+                let _ = txn.rollback().await;
+                return error_response(&format!("Failed to record stock-in: {e}"), StatusCode::INTERNAL_SERVER_ERROR);
+            }
+        }
+    }
+
+    if let Err(e) = model.update(&txn).await {
+        let _ = txn.rollback().await;
+        return error_response(&format!("Failed to update purchase: {e}"), StatusCode::INTERNAL_SERVER_ERROR);
+    }
+
+    if let Err(e) = txn.commit().await {
+        return error_response(&format!("Transaction commit failed: {e}"), StatusCode::INTERNAL_SERVER_ERROR);
+    }
+
+    info!("Purchase {} accepted as invoice {}", id, invc_no);
+
+    // 4️⃣ SEND TO KRA: the purchase first, then the stock it brought in
+    let db_clone = db.clone();
+    tokio::spawn(async move {
+        transmit_purchase(db_clone.as_ref(), &device, id).await;
+        if let Some((movement_id, master_ids)) = stock {
+            transmit_stock_movement(db_clone.as_ref(), &device, movement_id).await;
+            transmit_stock_master(db_clone, device, master_ids).await;
+        }
+    });
+
+    (
+        StatusCode::OK,
+        Json(json!({
+            "resultCd": "000",
+            "resultMsg": "Purchase accepted and queued for KRA",
+            "invcNo": invc_no,
+        })),
+    )
+}
+
+/// Reject a supplier sale
+async fn reject_purchase(
+    TypedHeader(auth): TypedHeader<Authorization<Bearer>>,
+    State(db): State<Arc<DatabaseConnection>>,
+    Path(id): Path<i32>,
+    Json(payload): Json<PurchaseRejectReq>,
+) -> impl IntoResponse {
+    let (user, device) = match authorize(auth.token(), db.as_ref()).await {
+        Ok(found) => found,
+        Err(res) => return res,
+    };
+
+    let txn = match db.begin().await {
+        Ok(t) => t,
+        Err(e) => return error_response(&format!("Failed to start transaction: {e}"), StatusCode::INTERNAL_SERVER_ERROR),
+    };
+
+    let record = match load_pending(&txn, user.id, id).await {
+        Ok(r) => r,
+        Err(res) => {
+            let _ = txn.rollback().await;
+            return res;
+        }
+    };
+
+    let invc_no = match next_invc_no(&txn, user.id).await {
+        Ok(n) => n,
+        Err(e) => {
+            let _ = txn.rollback().await;
+            return error_response(&format!("Failed to allocate invoice number: {e}"), StatusCode::INTERNAL_SERVER_ERROR);
+        }
+    };
+
+    let mut model: purchase::ActiveModel = record.into();
+    model.pchs_stts_cd = Set(CANCELLED.to_string());
+    model.invc_no = Set(Some(invc_no));
+    model.pchs_ty_cd = Set(Some("N".to_string()));
+    model.pchs_dt = Set(Some(Local::now().format("%Y%m%d").to_string()));
+    model.remark = Set(payload.remark);
+    model.regr_nm = Set(Some(payload.regr_nm));
+    model.regr_id = Set(Some(payload.regr_id));
+    model.status = Set(status::RECEIVED.to_string());
+    model.updated_at = Set(Some(Utc::now().naive_utc()));
+
+    if let Err(e) = model.update(&txn).await {
+        let _ = txn.rollback().await;
+        return error_response(&format!("Failed to update purchase: {e}"), StatusCode::INTERNAL_SERVER_ERROR);
+    }
+
+    if let Err(e) = txn.commit().await {
+        return error_response(&format!("Transaction commit failed: {e}"), StatusCode::INTERNAL_SERVER_ERROR);
+    }
+
+    info!("Purchase {} rejected as invoice {}", id, invc_no);
+
+    let db_clone = db.clone();
+    tokio::spawn(async move {
+        transmit_purchase(db_clone.as_ref(), &device, id).await;
+    });
+
+    (
+        StatusCode::OK,
+        Json(json!({
+            "resultCd": "000",
+            "resultMsg": "Purchase rejected and queued for KRA",
+            "invcNo": invc_no,
+        })),
+    )
+}
+
+/// The device's purchase `id`, if it is still waiting for a decision. The
+/// row stays locked until the caller's transaction ends, so a concurrent
+/// decision waits and then finds it decided.
+async fn load_pending(
+    txn: &DatabaseTransaction,
+    credentials_id: i32,
+    id: i32,
+) -> Result<purchase::Model, (StatusCode, Json<Value>)> {
+    let record = purchase::Entity::find_by_id(id)
+        .filter(purchase::Column::CredentialsId.eq(credentials_id))
+        .lock_exclusive()
+        .one(txn)
+        .await
+        .map_err(|e| error_response(&format!("Failed to fetch purchase: {e}"), StatusCode::INTERNAL_SERVER_ERROR))?
+        .ok_or_else(|| error_response("Purchase not found", StatusCode::NOT_FOUND))?;
+
+    if record.status != PENDING {
+        return Err(error_response(
+            &format!("Purchase was already decided (status {})", record.status),
+            StatusCode::CONFLICT,
+        ));
+    }
+
+    Ok(record)
+}
+
+/// Last purchase invoice number handed out per device
+const PURCHASE_INVOICES: Counter = Counter {
+    table: "purchase_counters",
+    keys: &["credentials_id"],
+    number: "last_invc_no",
+};
+
+/// Next purchase invoice number of the device, from its counter that stays
+/// locked until the decision commits, so concurrent decisions wait instead of
+/// colliding on `uq_purchase_device_invc_no`
+async fn next_invc_no(txn: &DatabaseTransaction, credentials_id: i32) -> Result<i64, sea_orm::DbErr> {
+    PURCHASE_INVOICES.allocate(txn, &[credentials_id.into()], 1).await
+}
+
+fn stock_in_movement(
+    record: &purchase::Model,
+    items: &[purchase_item::Model],
+    payload: &PurchaseAcceptReq,
+    ocrn_dt: &str,
+) -> StockItem {
+    StockItem {
+        tin: None,
+        bhf_id: None,
+        org_sar_no: 0,
+        reg_ty_cd: "M".to_string(),
+        cust_tin: Some(record.spplr_tin.clone()),
+        cust_nm: Some(record.spplr_nm.clone()),
+        cust_bhf_id: Some(record.spplr_bhf_id.clone()),
+        sar_ty_cd: STOCK_IN_PURCHASE.to_string(),
+        ocrn_dt: ocrn_dt.to_string(),
+        tot_item_cnt: items.len() as u32,
+        tot_taxbl_amt: record.tot_taxbl_amt,
+        tot_tax_amt: record.tot_tax_amt,
+        tot_amt: record.tot_amt,
+        remark: payload.remark.clone(),
+        regr_nm: payload.regr_nm.clone(),
+        regr_id: payload.regr_id.clone(),
+        modr_nm: payload.regr_nm.clone(),
+        modr_id: payload.regr_id.clone(),
+        item_list: items
+            .iter()
+            .map(|item| ItemDetail {
+                item_seq: item.item_seq as u32,
+                item_cd: item.item_cd.clone().unwrap_or_default(),
+                item_cls_cd: item.item_cls_cd.clone(),
+                item_nm: item.item_nm.clone(),
+                bcd: item.bcd.clone(),
+                pkg_unit_cd: item.pkg_unit_cd.clone(),
+                pkg: item.pkg,
+                qty_unit_cd: item.qty_unit_cd.clone(),
+                qty: item.qty,
+                item_expr_dt: None,
+                prc: item.prc,
+                sply_amt: item.sply_amt,
+                tot_dc_amt: item.dc_amt,
+                taxbl_amt: item.taxbl_amt,
+                tax_ty_cd: item.tax_ty_cd.clone(),
+                tax_amt: item.tax_amt,
+                tot_amt: item.tot_amt,
+            })
+            .collect(),
+    }
+}
@@ -0,0 +1,17 @@
+// ========== purchase.status values ==========
+//
+// PENDING ──(accept / reject)──► RECEIVED ──► PROCESSING ──► TRANSMITTED
+//                                                 │   ▲
+//                                                 │   └──── FAILED
+//                                                 └───────► REJECTED
+//
+// Everything after PENDING uses the sales values in `sales::status`.
+
+/// Pulled from KRA, waiting for the branch to accept or reject it
+pub const PENDING: &str = "PENDING";
+
+// ========== KRA purchase status codes (pchsSttsCd) ==========
+
+pub const WAIT_FOR_APPROVAL: &str = "01";
+pub const APPROVED: &str = "02";
+pub const CANCELLED: &str = "04";
@@ -0,0 +1,133 @@
+use sea_orm::{
+    ActiveModelTrait, ActiveValue::Set, ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter,
+    TransactionTrait,
+};
+use tracing::{info, warn};
+
+use crate::{
+    models::{purchase, purchase_item},
+    purchases::status::{PENDING, WAIT_FOR_APPROVAL},
+    types::purchases::SupplierSale,
+    utils::sync_state::{last_req_dt, mark_synced, now_req_dt},
+    vscu::{device::Device, result::ResultClass},
+};
+
+pub const RESOURCE: &str = "purchases";
+
+/// Pull supplier sales addressed to the device since its last sync and store
+/// the ones we have not seen yet. Returns how many new purchases were stored.
+pub async fn sync_purchases(db: &DatabaseConnection, device: &Device) -> Result<usize, String> {
+    let since = last_req_dt(db, device.credentials_id, RESOURCE)
+        .await
+        .map_err(|e| e.to_string())?;
+    let started_at = now_req_dt();
+
+    let res = device
+        .client
+        .select_purchase_sales(&device.select_request(&since))
+        .await
+        .map_err(|e| e.to_string())?;
+
+    if res.is_empty_search() {
+        info!("No supplier sales for device {} since {}", device.credentials_id, since);
+        mark_synced(db, device.credentials_id, RESOURCE, &started_at)
+            .await
+            .map_err(|e| e.to_string())?;
+        return Ok(0);
+    }
+
+    if res.class() != ResultClass::Success {
+        warn!("selectTrnsPurchaseSales failed for device {}: {} {}", device.credentials_id, res.result_cd, res.result_msg);
+        return Err(format!("{} {}", res.result_cd, res.result_msg));
+    }
+
+    let sales = res.data.map(|d| d.sale_list).unwrap_or_default();
+    let stored = store_sales(db, device.credentials_id, sales)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    mark_synced(db, device.credentials_id, RESOURCE, &started_at)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    info!("Stored {} new purchases for device {}", stored, device.credentials_id);
+    Ok(stored)
+}
+
+/// Insert unseen supplier sales with their lines; a sale KRA sends again
+/// keeps whatever decision was already made on it
+async fn store_sales(
+    db: &DatabaseConnection,
+    credentials_id: i32,
+    sales: Vec<SupplierSale>,
+) -> Result<usize, sea_orm::DbErr> {
+    let txn = db.begin().await?;
+    let mut stored = 0;
+
+    for sale in sales {
+        let known = purchase::Entity::find()
+            .filter(purchase::Column::CredentialsId.eq(credentials_id))
+            .filter(purchase::Column::SpplrTin.eq(&sale.spplr_tin))
+            .filter(purchase::Column::SpplrBhfId.eq(&sale.spplr_bhf_id))
+            .filter(purchase::Column::SpplrInvcNo.eq(sale.spplr_invc_no))
+            .one(&txn)
+            .await?;
+
+        if known.is_some() {
+            continue;
+        }
+
+        let header = purchase::ActiveModel {
+            credentials_id: Set(credentials_id),
+            spplr_tin: Set(sale.spplr_tin.clone()),
+            spplr_nm: Set(sale.spplr_nm.clone()),
+            spplr_bhf_id: Set(sale.spplr_bhf_id.clone()),
+            spplr_invc_no: Set(sale.spplr_invc_no),
+            rcpt_ty_cd: Set(sale.rcpt_ty_cd.clone()),
+            pmt_ty_cd: Set(sale.pmt_ty_cd.clone()),
+            cfm_dt: Set(sale.cfm_dt.clone()),
+            sales_dt: Set(sale.sales_dt.clone()),
+            tot_item_cnt: Set(sale.tot_item_cnt),
+            tot_taxbl_amt: Set(sale.tot_taxbl_amt),
+            tot_tax_amt: Set(sale.tot_tax_amt),
+            tot_amt: Set(sale.tot_amt),
+            supplier_sale: Set(serde_json::to_value(&sale).unwrap_or_default()),
+            pchs_stts_cd: Set(WAIT_FOR_APPROVAL.to_string()),
+            status: Set(PENDING.to_string()),
+            ..Default::default()
+        }
+        .insert(&txn)
+        .await?;
+
+        for item in sale.item_list {
+            purchase_item::ActiveModel {
+                purchase_id: Set(header.id),
+                item_seq: Set(item.item_seq),
+                spplr_item_cd: Set(item.item_cd),
+                item_cls_cd: Set(item.item_cls_cd),
+                item_nm: Set(item.item_nm),
+                bcd: Set(item.bcd),
+                pkg_unit_cd: Set(item.pkg_unit_cd),
+                pkg: Set(item.pkg),
+                qty_unit_cd: Set(item.qty_unit_cd),
+                qty: Set(item.qty),
+                prc: Set(item.prc),
+                sply_amt: Set(item.sply_amt),
+                dc_rt: Set(item.dc_rt),
+                dc_amt: Set(item.dc_amt),
+                tax_ty_cd: Set(item.tax_ty_cd),
+                taxbl_amt: Set(item.taxbl_amt),
+                tax_amt: Set(item.tax_amt),
+                tot_amt: Set(item.tot_amt),
+                ..Default::default()
+            }
+            .insert(&txn)
+            .await?;
+        }
+
+        stored += 1;
+    }
+
+    txn.commit().await?;
+    Ok(stored)
+}
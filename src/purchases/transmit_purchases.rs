@@ -0,0 +1,236 @@
+use chrono::Utc;
+use sea_orm::{
+    ActiveModelTrait, ActiveValue::Set, ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter,
+    QueryOrder,
+};
+use serde_json::{Value, json};
+use tracing::{info, error, warn};
+
+use crate::{
+    models::{
+        purchase::{ActiveModel, Column, Entity, Model},
+        purchase_item,
+    },
+    sales::status,
+    utils::retry::{MAX_RETRIES, claim, is_due, next_retry_at, retryable},
+    vscu::{device::Device, result::{ResultClass, VscuResponse}},
+};
+
+/// Per tax type totals copied as-is from the supplier's sale
+const TAX_FIELDS: [&str; 15] = [
+    "taxblAmtA", "taxblAmtB", "taxblAmtC", "taxblAmtD", "taxblAmtE",
+    "taxRtA", "taxRtB", "taxRtC", "taxRtD", "taxRtE",
+    "taxAmtA", "taxAmtB", "taxAmtC", "taxAmtD", "taxAmtE",
+];
+
+/// Send an accepted/rejected purchase to `trnsPurchase/savePurchases`
+pub async fn transmit_purchase(db: &DatabaseConnection, device: &Device, id: i32) {
+    match Entity::find_by_id(id).one(db).await {
+        Ok(Some(record)) => send_and_record(db, device, record).await,
+        Ok(None) => error!("Purchase {} not found", id),
+        Err(e) => error!("Failed to fetch purchase {}: {}", id, e),
+    }
+}
+
+/// Retry FAILED/stuck purchases whose backoff has elapsed, and decisions
+/// whose first send never happened
+pub async fn retry_failed_purchases(db: &DatabaseConnection) -> Result<(), sea_orm::DbErr> {
+    let records = Entity::find()
+        .filter(retryable(&["updated_at", "created_at"]))
+        .filter(Column::RetryCount.lt(MAX_RETRIES))
+        .all(db)
+        .await?;
+
+    for record in records {
+        if !is_due(record.next_retry_at.as_deref()) {
+            continue;
+        }
+
+        let device = match Device::find(db, record.credentials_id).await {
+            Ok(d) => d,
+            Err(e) => {
+                error!("Cannot retry purchase {}: {}", record.id, e);
+                continue;
+            }
+        };
+
+        info!("🔄 Retrying purchase {} (attempt {}/{})", record.id, record.retry_count + 1, MAX_RETRIES);
+        send_and_record(db, &device, record).await;
+    }
+
+    Ok(())
+}
+
+async fn send_and_record(db: &DatabaseConnection, device: &Device, record: Model) {
+    let id = record.id;
+    let retry_count = record.retry_count;
+
+    let items = match purchase_item::Entity::find()
+        .filter(purchase_item::Column::PurchaseId.eq(id))
+        .order_by_asc(purchase_item::Column::ItemSeq)
+        .all(db)
+        .await
+    {
+        Ok(items) => items,
+        Err(e) => {
+            error!("Failed to load lines of purchase {}: {}", id, e);
+            return;
+        }
+    };
+
+    let record = match claim::<Entity, _>(db, id, &[status::RECEIVED, status::FAILED]).await {
+        Ok(Some(r)) => r,
+        Ok(None) => {
+            info!("Purchase {} is already being sent, skipped", id);
+            return;
+        }
+        Err(e) => {
+            error!("Failed to lock purchase {}: {}", id, e);
+            return;
+        }
+    };
+
+    let kra_payload = build_payload(device, &record, &items);
+
+    let outcome = match device.client.save_purchases(&kra_payload).await {
+        Ok(res) => record_response(db, id, retry_count, &res).await,
+        Err(e) => {
+            error!("Failed to transmit purchase {}: {}", id, e);
+            schedule_retry(db, id, retry_count, None).await
+        }
+    };
+
+    if let Err(e) = outcome {
+        error!("Failed to update purchase {}: {}", id, e);
+    }
+}
+
+fn build_payload(device: &Device, record: &Model, items: &[purchase_item::Model]) -> Value {
+    let item_list: Vec<Value> = items
+        .iter()
+        .map(|item| {
+            json!({
+                "itemSeq": item.item_seq,
+                "itemCd": item.item_cd,
+                "itemClsCd": item.item_cls_cd,
+                "itemNm": item.item_nm,
+                "bcd": item.bcd,
+                "spplrItemClsCd": item.item_cls_cd,
+                "spplrItemCd": item.spplr_item_cd,
+                "spplrItemNm": item.item_nm,
+                "pkgUnitCd": item.pkg_unit_cd,
+                "pkg": item.pkg,
+                "qtyUnitCd": item.qty_unit_cd,
+                "qty": item.qty,
+                "prc": item.prc,
+                "splyAmt": item.sply_amt,
+                "dcRt": item.dc_rt,
+                "dcAmt": item.dc_amt,
+                "taxblAmt": item.taxbl_amt,
+                "taxTyCd": item.tax_ty_cd,
+                "taxAmt": item.tax_amt,
+                "totAmt": item.tot_amt,
+                "itemExprDt": null,
+            })
+        })
+        .collect();
+
+    let regr_nm = record.regr_nm.clone().unwrap_or_default();
+    let regr_id = record.regr_id.clone().unwrap_or_default();
+
+    let mut payload = json!({
+        "tin": device.tin,
+        "bhfId": device.bhf_id,
+        "invcNo": record.invc_no,
+        "orgInvcNo": 0,
+        "spplrTin": record.spplr_tin,
+        "spplrBhfId": record.spplr_bhf_id,
+        "spplrNm": record.spplr_nm,
+        "spplrInvcNo": record.spplr_invc_no,
+        "regTyCd": "M",
+        "pchsTyCd": record.pchs_ty_cd,
+        "rcptTyCd": record.rcpt_ty_cd,
+        "pmtTyCd": record.pmt_ty_cd,
+        "pchsSttsCd": record.pchs_stts_cd,
+        "cfmDt": record.cfm_dt,
+        "pchsDt": record.pchs_dt,
+        "wrhsDt": null,
+        "cnclReqDt": null,
+        "cnclDt": null,
+        "rfdDt": null,
+        "totItemCnt": record.tot_item_cnt,
+        "totTaxblAmt": record.tot_taxbl_amt,
+        "totTaxAmt": record.tot_tax_amt,
+        "totAmt": record.tot_amt,
+        "remark": record.remark,
+        "regrNm": regr_nm,
+        "regrId": regr_id,
+        "modrNm": regr_nm,
+        "modrId": regr_id,
+        "itemList": item_list,
+    });
+
+    for field in TAX_FIELDS {
+        payload[field] = record.supplier_sale.get(field).cloned().unwrap_or(json!(0));
+    }
+
+    payload
+}
+
+async fn record_response(
+    db: &DatabaseConnection,
+    id: i32,
+    retry_count: i64,
+    res: &VscuResponse<Value>,
+) -> Result<(), sea_orm::DbErr> {
+    let response = serde_json::to_value(res).ok();
+
+    let new_status = match res.class() {
+        ResultClass::Success => status::TRANSMITTED,
+        ResultClass::Rejected => {
+            warn!("Purchase {} rejected by KRA: {} {}", id, res.result_cd, res.result_msg);
+            status::REJECTED
+        }
+        ResultClass::Retryable => return schedule_retry(db, id, retry_count, response).await,
+    };
+
+    let mut model: ActiveModel = find(db, id).await?.into();
+    model.status = Set(new_status.to_string());
+    model.response = Set(response);
+    model.next_retry_at = Set(None);
+    model.updated_at = Set(Some(Utc::now().naive_utc()));
+    model.update(db).await?;
+
+    info!("Purchase {} is {}", id, new_status);
+    Ok(())
+}
+
+async fn schedule_retry(
+    db: &DatabaseConnection,
+    id: i32,
+    retry_count: i64,
+    response: Option<Value>,
+) -> Result<(), sea_orm::DbErr> {
+    let new_retry_count = retry_count + 1;
+    let next = next_retry_at(new_retry_count);
+
+    let mut model: ActiveModel = find(db, id).await?.into();
+    model.status = Set(status::FAILED.to_string());
+    model.retry_count = Set(new_retry_count);
+    model.next_retry_at = Set(Some(next.clone()));
+    model.updated_at = Set(Some(Utc::now().naive_utc()));
+    if response.is_some() {
+        model.response = Set(response);
+    }
+    model.update(db).await?;
+
+    info!("Purchase {} FAILED ({}/{}), next retry at {}", id, new_retry_count, MAX_RETRIES, next);
+    Ok(())
+}
+
+async fn find(db: &DatabaseConnection, id: i32) -> Result<Model, sea_orm::DbErr> {
+    Entity::find_by_id(id)
+        .one(db)
+        .await?
+        .ok_or(sea_orm::DbErr::RecordNotFound(format!("ID {}", id)))
+}
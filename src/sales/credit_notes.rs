@@ -0,0 +1,381 @@
+use std::collections::HashMap;
+
+use chrono::Local;
+use rust_decimal::Decimal;
+use sea_orm::{ColumnTrait, ConnectionTrait, DbErr, EntityTrait, QueryFilter, sea_query::Expr};
+
+use crate::{
+    models::sales_uploads::{Column, Entity, Model},
+    sales::{
+        status::{REJECTED, TRANSMITTED},
+        tax::{TaxRates, compute_taxes, kra_round},
+        validate::FieldError,
+    },
+    types::salespayloadtype::{CreditNoteReq, ReceiptInfo, TrnsSalesSaveWrItem, TrnsSalesSaveWrReq},
+    utils::sync_state::now_req_dt,
+};
+
+/// rcptTyCd of a normal sale and of a credit note
+const SALE: &str = "S";
+const CREDIT_NOTE: &str = "R";
+
+/// salesSttsCd of an approved transaction
+const APPROVED: &str = "02";
+
+/// Partial refunds are rounded one by one, so together they may pass the
+/// original total by a cent
+const ROUNDING: Decimal = Decimal::from_parts(1, 0, 0, false, 2);
+
+/// Quantity of each item code over the lines of a stored invoice
+pub fn quantities(record: &Model) -> HashMap<String, Decimal> {
+    let mut qty: HashMap<String, Decimal> = HashMap::new();
+    for line in lines_of(record) {
+        *qty.entry(line.itemCd).or_default() += line.qty;
+    }
+    qty
+}
+
+fn lines_of(record: &Model) -> Vec<TrnsSalesSaveWrItem> {
+    serde_json::from_value(record.item_list.clone()).unwrap_or_default()
+}
+
+/// Credit notes against invoice `org_invc_no` that still count, i.e. all
+/// but the ones KRA rejected
+async fn credit_notes_of<C: ConnectionTrait>(conn: &C, api_key: &str, org_invc_no: i64) -> Result<Vec<Model>, DbErr> {
+    Entity::find()
+        .filter(Column::ApiKey.eq(api_key))
+        .filter(Column::OrgInvcNo.eq(org_invc_no))
+        .filter(Column::RcptTyCd.eq(CREDIT_NOTE))
+        .filter(Column::Status.ne(REJECTED))
+        .all(conn)
+        .await
+}
+
+/// Quantity per item code already given back on invoice `org_invc_no`
+pub async fn refunded_quantities<C: ConnectionTrait>(
+    conn: &C,
+    api_key: &str,
+    org_invc_no: i64,
+) -> Result<HashMap<String, Decimal>, DbErr> {
+    let mut refunded: HashMap<String, Decimal> = HashMap::new();
+    for (item_cd, qty) in credit_notes_of(conn, api_key, org_invc_no).await?.iter().flat_map(quantities) {
+        *refunded.entry(item_cd).or_default() += qty;
+    }
+    Ok(refunded)
+}
+
+/// Recompute `refunded_amt` of invoice `org_invc_no` from its credit notes
+pub async fn refresh_refunded_amt<C: ConnectionTrait>(conn: &C, api_key: &str, org_invc_no: i64) -> Result<(), DbErr> {
+    let refunded: Decimal = credit_notes_of(conn, api_key, org_invc_no)
+        .await?
+        .iter()
+        .map(|note| note.tot_amt)
+        .sum();
+
+    Entity::update_many()
+        .col_expr(Column::RefundedAmt, Expr::value(refunded))
+        .filter(Column::ApiKey.eq(api_key))
+        .filter(Column::InvcNo.eq(org_invc_no))
+        .exec(conn)
+        .await?;
+
+    Ok(())
+}
+
+/// Credit note giving back `req.items` of `original` (everything not yet
+/// refunded when no items are listed), priced and taxed like the original.
+pub fn build_credit_note(
+    original: &Model,
+    req: CreditNoteReq,
+    refunded: &HashMap<String, Decimal>,
+) -> Result<TrnsSalesSaveWrReq, String> {
+    let sold = quantities(original);
+    let wanted: Vec<(String, Decimal)> = if req.items.is_empty() {
+        let mut left: Vec<(String, Decimal)> = sold
+            .iter()
+            .map(|(item_cd, qty)| (item_cd.clone(), *qty - refunded.get(item_cd).copied().unwrap_or_default()))
+            .filter(|(_, qty)| *qty > Decimal::ZERO)
+            .collect();
+        left.sort();
+        left
+    } else {
+        req.items.into_iter().map(|i| (i.item_cd, i.qty)).collect()
+    };
+
+    if wanted.is_empty() {
+        return Err(format!("Nothing is left to refund on invoice #{}", original.invc_no));
+    }
+
+    let original_lines = lines_of(original);
+    let mut item_list = Vec::new();
+    for (item_cd, mut qty) in wanted {
+        let matching: Vec<&TrnsSalesSaveWrItem> = original_lines.iter().filter(|l| l.itemCd == item_cd).collect();
+        if matching.is_empty() {
+            return Err(format!("Item {} is not on invoice #{}", item_cd, original.invc_no));
+        }
+
+        // Spread the quantity over the invoice's lines of the item; anything
+        // beyond them stays on the last one for check_credit_notes to report
+        for (n, line) in matching.iter().enumerate() {
+            let take = if n + 1 == matching.len() { qty } else { qty.min(line.qty) };
+            if take <= Decimal::ZERO {
+                break;
+            }
+            qty -= take;
+            item_list.push(refund_line(line, take));
+        }
+    }
+
+    for (seq, line) in item_list.iter_mut().enumerate() {
+        line.itemSeq = seq as i64 + 1;
+    }
+
+    let receipt: ReceiptInfo = serde_json::from_value(original.receipt.clone())
+        .map_err(|e| format!("Invoice #{} has an unreadable receipt: {}", original.invc_no, e))?;
+    let now = now_req_dt();
+
+    let mut note = TrnsSalesSaveWrReq {
+        tin: None,
+        bhfId: None,
+        trdInvcNo: req.trd_invc_no,
+        generated_invc_no: None,
+        invcNo: None,
+        orgInvcNo: original.invc_no,
+        custTin: original.cust_tin.clone(),
+        custNm: original.cust_nm.clone(),
+        retry_count: None,
+        next_retry_at: None,
+        salesTyCd: original.sales_ty_cd.clone(),
+        rcptTyCd: CREDIT_NOTE.to_string(),
+        pmtTyCd: original.pmt_ty_cd.clone(),
+        salesSttsCd: APPROVED.to_string(),
+        cfmDt: now.clone(),
+        salesDt: Local::now().format("%Y%m%d").to_string(),
+        stockRlsDt: now.clone(),
+        cnclReqDt: None,
+        cnclDt: None,
+        rfdDt: Some(req.rfd_dt.unwrap_or(now)),
+        rfdRsnCd: Some(req.rfd_rsn_cd),
+        totItemCnt: 0,
+        taxblAmtA: Decimal::ZERO,
+        taxblAmtB: Decimal::ZERO,
+        taxblAmtC: Decimal::ZERO,
+        taxblAmtD: Decimal::ZERO,
+        taxblAmtE: Decimal::ZERO,
+        taxRtA: Decimal::ZERO,
+        taxRtB: Decimal::ZERO,
+        taxRtC: Decimal::ZERO,
+        taxRtD: Decimal::ZERO,
+        taxRtE: Decimal::ZERO,
+        taxAmtA: Decimal::ZERO,
+        taxAmtB: Decimal::ZERO,
+        taxAmtC: Decimal::ZERO,
+        taxAmtD: Decimal::ZERO,
+        taxAmtE: Decimal::ZERO,
+        totTaxblAmt: Decimal::ZERO,
+        totTaxAmt: Decimal::ZERO,
+        totAmt: Decimal::ZERO,
+        prchrAcptcYn: original.prchr_acptc_yn.clone(),
+        remark: req.remark,
+        regrId: req.regr_id.clone(),
+        regrNm: req.regr_nm.clone(),
+        modrId: req.regr_id,
+        modrNm: req.regr_nm,
+        receipt,
+        itemList: item_list,
+        response: None,
+    };
+
+    compute_taxes(&mut note, &TaxRates::of_sale(original));
+    Ok(note)
+}
+
+/// `qty` of an invoice line, with packages and a fixed discount shared out pro rata
+fn refund_line(line: &TrnsSalesSaveWrItem, qty: Decimal) -> TrnsSalesSaveWrItem {
+    let mut refund = line.clone();
+    refund.qty = qty;
+    if !line.qty.is_zero() {
+        refund.pkg = line.pkg * qty / line.qty;
+        if line.dcRt.is_zero() {
+            refund.dcAmt = kra_round(line.dcAmt * qty / line.qty);
+        }
+    }
+    refund
+}
+
+/// Check the credit notes among `invoices` against their original sales:
+/// the original must be a sale of this device signed by KRA, and neither
+/// the quantity of any item nor the total may exceed what is left to refund.
+///
+/// Run it while holding the device's invoice counter so no other credit note
+/// for the same sale can be stored in between.
+pub async fn check_credit_notes<C: ConnectionTrait>(
+    conn: &C,
+    api_key: &str,
+    invoices: &[(usize, &TrnsSalesSaveWrReq)],
+) -> Result<Vec<FieldError>, DbErr> {
+    let mut errors = Vec::new();
+    // Credit notes earlier in the same payload count against the same sale
+    let mut pending: HashMap<i64, (HashMap<String, Decimal>, Decimal)> = HashMap::new();
+
+    for &(index, note) in invoices.iter().filter(|(_, s)| s.rcptTyCd == CREDIT_NOTE) {
+        let mut fail = |field: String, message: String| errors.push(FieldError { index, field, message });
+
+        let original = Entity::find()
+            .filter(Column::ApiKey.eq(api_key))
+            .filter(Column::InvcNo.eq(note.orgInvcNo))
+            .one(conn)
+            .await?;
+
+        let Some(original) = original else {
+            fail("orgInvcNo".to_string(), format!("Invoice #{} is not a sale of this device", note.orgInvcNo));
+            continue;
+        };
+        if original.rcpt_ty_cd != SALE {
+            fail("orgInvcNo".to_string(), format!("Invoice #{} is a credit note, not a sale", original.invc_no));
+            continue;
+        }
+        if original.status != TRANSMITTED {
+            fail(
+                "orgInvcNo".to_string(),
+                format!("Invoice #{} is not signed by KRA yet (status {})", original.invc_no, original.status),
+            );
+            continue;
+        }
+
+        let sold = quantities(&original);
+        let earlier = credit_notes_of(conn, api_key, original.invc_no).await?;
+        let mut refunded: HashMap<String, Decimal> = HashMap::new();
+        for (item_cd, qty) in earlier.iter().flat_map(quantities) {
+            *refunded.entry(item_cd).or_default() += qty;
+        }
+        let (pending_qty, pending_amt) = pending.entry(original.invc_no).or_default();
+        for (item_cd, qty) in pending_qty.iter() {
+            *refunded.entry(item_cd.clone()).or_default() += *qty;
+        }
+
+        for (i, line) in note.itemList.iter().enumerate() {
+            let Some(sold_qty) = sold.get(&line.itemCd) else {
+                fail(
+                    format!("itemList[{i}].itemCd"),
+                    format!("Item {} is not on invoice #{}", line.itemCd, original.invc_no),
+                );
+                continue;
+            };
+
+            let done = refunded.entry(line.itemCd.clone()).or_default();
+            if *done + line.qty > *sold_qty {
+                fail(
+                    format!("itemList[{i}].qty"),
+                    format!(
+                        "Refunds {} of {} but only {} of the {} sold on invoice #{} are left",
+                        line.qty,
+                        line.itemCd,
+                        *sold_qty - *done,
+                        sold_qty,
+                        original.invc_no
+                    ),
+                );
+            }
+            *done += line.qty;
+            *pending_qty.entry(line.itemCd.clone()).or_default() += line.qty;
+        }
+
+        let refunded_amt = earlier.iter().map(|n| n.tot_amt).sum::<Decimal>() + *pending_amt;
+        if refunded_amt + note.totAmt > original.tot_amt + ROUNDING {
+            fail(
+                "totAmt".to_string(),
+                format!(
+                    "Refunds {} but only {} of invoice #{} is left",
+                    note.totAmt,
+                    original.tot_amt - refunded_amt,
+                    original.invc_no
+                ),
+            );
+        }
+        *pending_amt += note.totAmt;
+    }
+
+    Ok(errors)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        sales::test_support::{dec, invoice, line, stored},
+        types::salespayloadtype::CreditNoteItem,
+    };
+
+    /// Invoice #5: bread on lines 1 and 3 (2 + 3), salt on line 2
+    fn original() -> Model {
+        let mut sale = invoice(1);
+        sale.itemList = vec![
+            line(1, "BREAD", "2", "58"),
+            line(2, "SALT", "1", "10"),
+            line(3, "BREAD", "3", "58"),
+        ];
+        stored(&sale, 5)
+    }
+
+    fn request(items: &[(&str, &str)]) -> CreditNoteReq {
+        CreditNoteReq {
+            trd_invc_no: Some(900),
+            rfd_rsn_cd: "01".to_string(),
+            rfd_dt: Some("20260219090000".to_string()),
+            remark: None,
+            regr_id: "u1".to_string(),
+            regr_nm: "u1".to_string(),
+            items: items
+                .iter()
+                .map(|(item_cd, qty)| CreditNoteItem { item_cd: item_cd.to_string(), qty: dec(qty) })
+                .collect(),
+        }
+    }
+
+    fn refunded_lines(note: &TrnsSalesSaveWrReq) -> Vec<(i64, &str, Decimal)> {
+        note.itemList.iter().map(|l| (l.itemSeq, l.itemCd.as_str(), l.qty)).collect()
+    }
+
+    #[test]
+    fn spreads_a_partial_quantity_over_repeated_lines() {
+        let note = build_credit_note(&original(), request(&[("BREAD", "4")]), &HashMap::new()).unwrap();
+
+        assert_eq!(refunded_lines(&note), vec![(1, "BREAD", dec("2")), (2, "BREAD", dec("2"))]);
+        assert_eq!(note.rcptTyCd, "R");
+        assert_eq!(note.orgInvcNo, 5);
+        assert_eq!(note.totItemCnt, 2);
+        assert_eq!(note.totAmt, dec("232.00"));
+        assert_eq!(note.taxAmtB, dec("32.00"));
+    }
+
+    #[test]
+    fn refunds_what_is_left_when_no_items_are_listed() {
+        let refunded = HashMap::from([("BREAD".to_string(), dec("1"))]);
+        let note = build_credit_note(&original(), request(&[]), &refunded).unwrap();
+
+        assert_eq!(
+            refunded_lines(&note),
+            vec![(1, "BREAD", dec("2")), (2, "BREAD", dec("2")), (3, "SALT", dec("1"))]
+        );
+        assert_eq!(note.totAmt, dec("242.00"));
+    }
+
+    #[test]
+    fn refuses_items_not_on_the_invoice() {
+        assert!(build_credit_note(&original(), request(&[("MILK", "1")]), &HashMap::new()).is_err());
+    }
+
+    #[test]
+    fn shares_packages_and_a_fixed_discount_out_pro_rata() {
+        let mut sold = line(1, "BREAD", "3", "58");
+        sold.pkg = dec("3");
+        sold.dcAmt = dec("10.00");
+
+        let refund = refund_line(&sold, dec("1"));
+
+        assert_eq!(refund.qty, dec("1"));
+        assert_eq!(refund.pkg, dec("1"));
+        assert_eq!(refund.dcAmt, dec("3.33"));
+    }
+}
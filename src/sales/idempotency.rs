@@ -0,0 +1,151 @@
+use axum::{Json, http::{HeaderMap, StatusCode}};
+use chrono::Utc;
+use sea_orm::{
+    ActiveModelTrait, ActiveValue::Set, ColumnTrait, DatabaseConnection, DbErr, EntityTrait, QueryFilter,
+    sea_query::OnConflict,
+};
+use serde::Serialize;
+use serde_json::{Value, json};
+use sha2::{Digest, Sha256};
+
+use crate::{
+    models::idempotency_keys::{ActiveModel, Column, Entity},
+};
+
+/// Header a POS sets to make resubmitting the same sales safe
+const IDEMPOTENCY_HEADER: &str = "idempotency-key";
+
+/// What to do with a request carrying an Idempotency-Key
+pub enum Claim {
+    /// First request with this key: process it and `complete` row `id`
+    New(i32),
+    /// The key was used before: answer what the first request got
+    Replay(StatusCode, Value),
+}
+
+/// The Idempotency-Key header, if the POS sent a usable one
+pub fn idempotency_key(headers: &HeaderMap) -> Option<String> {
+    headers
+        .get(IDEMPOTENCY_HEADER)
+        .and_then(|v| v.to_str().ok())
+        .map(str::trim)
+        .filter(|k| !k.is_empty())
+        .map(str::to_string)
+}
+
+/// SHA-256 of the request as received, to tell a genuine resubmission from
+/// a different request reusing the key
+pub fn request_hash<T: Serialize>(payload: &T) -> String {
+    let body = serde_json::to_vec(payload).unwrap_or_default();
+    format!("{:x}", Sha256::digest(body))
+}
+
+/// Register `idem_key` for this device, or find the answer of the request
+/// that registered it first
+pub async fn claim(
+    db: &DatabaseConnection,
+    api_key: &str,
+    idem_key: &str,
+    hash: &str,
+) -> Result<Claim, (StatusCode, Json<Value>)> {
+    let row = ActiveModel {
+        api_key: Set(api_key.to_string()),
+        idem_key: Set(idem_key.to_string()),
+        request_hash: Set(hash.to_string()),
+        status_code: Set(None),
+        response: Set(None),
+        created_at: Set(Utc::now().naive_utc()),
+        ..Default::default()
+    };
+
+    let inserted = Entity::insert(row)
+        .on_conflict(OnConflict::columns([Column::ApiKey, Column::IdemKey]).do_nothing().to_owned())
+        .exec(db)
+        .await;
+
+    match inserted {
+        Ok(res) => return Ok(Claim::New(res.last_insert_id)),
+        Err(DbErr::RecordNotInserted) => {}
+        Err(e) => return Err(failure(StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to store Idempotency-Key: {e}"))),
+    }
+
+    let existing = Entity::find()
+        .filter(Column::ApiKey.eq(api_key))
+        .filter(Column::IdemKey.eq(idem_key))
+        .one(db)
+        .await
+        .map_err(|e| failure(StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to fetch Idempotency-Key: {e}")))?
+        // deleted by a failed first attempt in the meantime
+        .ok_or_else(|| failure(StatusCode::CONFLICT, "Request with this Idempotency-Key failed, retry it".to_string()))?;
+
+    if existing.request_hash != hash {
+        return Err(failure(
+            StatusCode::UNPROCESSABLE_ENTITY,
+            "Idempotency-Key was already used for a different payload".to_string(),
+        ));
+    }
+
+    match (existing.status_code, existing.response) {
+        (Some(code), Some(response)) => Ok(Claim::Replay(
+            StatusCode::from_u16(code as u16).unwrap_or(StatusCode::OK),
+            response,
+        )),
+        _ => Err(failure(
+            StatusCode::CONFLICT,
+            "A request with this Idempotency-Key is still being processed".to_string(),
+        )),
+    }
+}
+
+/// Store the answer of claimed row `id`. Server errors release the key
+/// instead, so the POS can retry with it.
+pub async fn complete(db: &DatabaseConnection, id: i32, status: StatusCode, response: &Value) -> Result<(), DbErr> {
+    if status.is_server_error() {
+        Entity::delete_by_id(id).exec(db).await?;
+        return Ok(());
+    }
+
+    let row = ActiveModel {
+        id: Set(id),
+        status_code: Set(Some(status.as_u16() as i32)),
+        response: Set(Some(response.clone())),
+        ..Default::default()
+    };
+    row.update(db).await?;
+
+    Ok(())
+}
+
+fn failure(status: StatusCode, message: String) -> (StatusCode, Json<Value>) {
+    (status, Json(json!({ "message": message })))
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::http::HeaderValue;
+
+    use super::*;
+    use crate::sales::test_support::invoice;
+
+    #[test]
+    fn reads_a_trimmed_idempotency_key() {
+        let mut headers = HeaderMap::new();
+        assert_eq!(idempotency_key(&headers), None);
+
+        headers.insert("Idempotency-Key", HeaderValue::from_static("  pos-1-0042 "));
+        assert_eq!(idempotency_key(&headers).as_deref(), Some("pos-1-0042"));
+
+        headers.insert("Idempotency-Key", HeaderValue::from_static("   "));
+        assert_eq!(idempotency_key(&headers), None);
+    }
+
+    #[test]
+    fn hashes_equal_requests_alike_and_different_ones_apart() {
+        let first = request_hash(&[invoice(1)]);
+
+        assert_eq!(first.len(), 64);
+        assert_eq!(first, request_hash(&[invoice(1)]));
+        assert_ne!(first, request_hash(&[invoice(2)]));
+        assert_ne!(first, request_hash(&[invoice(1), invoice(2)]));
+    }
+}
@@ -0,0 +1,23 @@
+use sea_orm::{ConnectionTrait, DbErr};
+
+use crate::utils::counters::Counter;
+
+/// Last invoice number handed out per device (api key)
+pub const INVOICES: Counter = Counter {
+    table: "invoice_counters",
+    keys: &["api_key"],
+    number: "last_invc_no",
+};
+
+/// Lock the invoice counter of the device behind `api_key` until the
+/// surrounding transaction ends, so no other request inserts sales for it
+/// while earlier invoices are looked up
+pub async fn lock_counter<C: ConnectionTrait>(txn: &C, api_key: &str) -> Result<i64, DbErr> {
+    INVOICES.lock(txn, &[api_key.into()]).await
+}
+
+/// Reserve `count` consecutive invoice numbers for the device behind `api_key`
+/// in the transaction that inserts the sales, and return the first one
+pub async fn allocate<C: ConnectionTrait>(txn: &C, api_key: &str, count: i64) -> Result<i64, DbErr> {
+    INVOICES.allocate(txn, &[api_key.into()], count).await
+}
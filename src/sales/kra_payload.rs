@@ -0,0 +1,128 @@
+use rust_decimal::Decimal;
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::models::sales_uploads::Model;
+
+/// Body of `trnsSales/saveSales` for a stored sale. Amounts go out as JSON
+/// numbers straight from the stored `Decimal`s.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct KraSalesPayload<'a> {
+    pub tin: String,
+    pub bhf_id: String,
+    pub trd_invc_no: Option<i32>,
+    pub invc_no: i64,
+    pub org_invc_no: i64,
+    pub cust_tin: &'a str,
+    pub cust_nm: &'a str,
+    pub sales_ty_cd: &'a str,
+    pub rcpt_ty_cd: &'a str,
+    pub pmt_ty_cd: &'a str,
+    pub sales_stts_cd: &'a str,
+    pub cfm_dt: &'a str,
+    pub sales_dt: &'a str,
+    pub stock_rls_dt: &'a str,
+    pub cncl_req_dt: Option<&'a str>,
+    pub cncl_dt: Option<&'a str>,
+    pub rfd_dt: Option<&'a str>,
+    pub rfd_rsn_cd: Option<&'a str>,
+    pub tot_item_cnt: i32,
+    #[serde(with = "rust_decimal::serde::float")]
+    pub taxbl_amt_a: Decimal,
+    #[serde(with = "rust_decimal::serde::float")]
+    pub taxbl_amt_b: Decimal,
+    #[serde(with = "rust_decimal::serde::float")]
+    pub taxbl_amt_c: Decimal,
+    #[serde(with = "rust_decimal::serde::float")]
+    pub taxbl_amt_d: Decimal,
+    #[serde(with = "rust_decimal::serde::float")]
+    pub taxbl_amt_e: Decimal,
+    #[serde(with = "rust_decimal::serde::float")]
+    pub tax_rt_a: Decimal,
+    #[serde(with = "rust_decimal::serde::float")]
+    pub tax_rt_b: Decimal,
+    #[serde(with = "rust_decimal::serde::float")]
+    pub tax_rt_c: Decimal,
+    #[serde(with = "rust_decimal::serde::float")]
+    pub tax_rt_d: Decimal,
+    #[serde(with = "rust_decimal::serde::float")]
+    pub tax_rt_e: Decimal,
+    #[serde(with = "rust_decimal::serde::float")]
+    pub tax_amt_a: Decimal,
+    #[serde(with = "rust_decimal::serde::float")]
+    pub tax_amt_b: Decimal,
+    #[serde(with = "rust_decimal::serde::float")]
+    pub tax_amt_c: Decimal,
+    #[serde(with = "rust_decimal::serde::float")]
+    pub tax_amt_d: Decimal,
+    #[serde(with = "rust_decimal::serde::float")]
+    pub tax_amt_e: Decimal,
+    #[serde(with = "rust_decimal::serde::float")]
+    pub tot_taxbl_amt: Decimal,
+    #[serde(with = "rust_decimal::serde::float")]
+    pub tot_tax_amt: Decimal,
+    #[serde(with = "rust_decimal::serde::float")]
+    pub tot_amt: Decimal,
+    pub prchr_acptc_yn: &'a str,
+    pub remark: Option<&'a str>,
+    pub regr_id: &'a str,
+    pub regr_nm: &'a str,
+    pub modr_id: &'a str,
+    pub modr_nm: &'a str,
+    pub receipt: &'a Value,
+    pub item_list: &'a Value,
+}
+
+impl<'a> KraSalesPayload<'a> {
+    /// `record` as sent by this device, identified by its decrypted TIN and branch
+    pub fn new(record: &'a Model, tin: String, bhf_id: String) -> Self {
+        Self {
+            tin,
+            bhf_id,
+            trd_invc_no: record.trd_invc_no,
+            invc_no: record.invc_no,
+            org_invc_no: record.org_invc_no,
+            cust_tin: &record.cust_tin,
+            cust_nm: &record.cust_nm,
+            sales_ty_cd: &record.sales_ty_cd,
+            rcpt_ty_cd: &record.rcpt_ty_cd,
+            pmt_ty_cd: &record.pmt_ty_cd,
+            sales_stts_cd: &record.sales_stts_cd,
+            cfm_dt: &record.cfm_dt,
+            sales_dt: &record.sales_dt,
+            stock_rls_dt: &record.stock_rls_dt,
+            cncl_req_dt: record.cncl_req_dt.as_deref(),
+            cncl_dt: record.cncl_dt.as_deref(),
+            rfd_dt: record.rfd_dt.as_deref(),
+            rfd_rsn_cd: record.rfd_rsn_cd.as_deref(),
+            tot_item_cnt: record.tot_item_cnt,
+            taxbl_amt_a: record.taxbl_amt_a,
+            taxbl_amt_b: record.taxbl_amt_b,
+            taxbl_amt_c: record.taxbl_amt_c,
+            taxbl_amt_d: record.taxbl_amt_d,
+            taxbl_amt_e: record.taxbl_amt_e,
+            tax_rt_a: record.tax_rt_a,
+            tax_rt_b: record.tax_rt_b,
+            tax_rt_c: record.tax_rt_c,
+            tax_rt_d: record.tax_rt_d,
+            tax_rt_e: record.tax_rt_e,
+            tax_amt_a: record.tax_amt_a,
+            tax_amt_b: record.tax_amt_b,
+            tax_amt_c: record.tax_amt_c,
+            tax_amt_d: record.tax_amt_d,
+            tax_amt_e: record.tax_amt_e,
+            tot_taxbl_amt: record.tot_taxbl_amt,
+            tot_tax_amt: record.tot_tax_amt,
+            tot_amt: record.tot_amt,
+            prchr_acptc_yn: &record.prchr_acptc_yn,
+            remark: record.remark.as_deref(),
+            regr_id: &record.regr_id,
+            regr_nm: &record.regr_nm,
+            modr_id: &record.modr_id,
+            modr_nm: &record.modr_nm,
+            receipt: &record.receipt,
+            item_list: &record.item_list,
+        }
+    }
+}
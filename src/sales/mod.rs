@@ -1 +1,12 @@
-pub mod routing;
\ No newline at end of file
+pub mod routing;
+pub mod status;
+pub mod validate;
+pub mod tax;
+pub mod invoice_numbers;
+pub mod idempotency;
+pub mod query;
+pub mod credit_notes;
+pub mod kra_payload;
+
+#[cfg(test)]
+pub mod test_support;
@@ -0,0 +1,173 @@
+use std::sync::Arc;
+
+use axum::{
+    Json,
+    extract::{Path, Query, State},
+    http::StatusCode,
+    response::IntoResponse,
+};
+use axum_extra::extract::TypedHeader;
+use headers::{Authorization, authorization::Bearer};
+use sea_orm::{ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter, QueryOrder, QuerySelect};
+use serde_json::{Value, json};
+
+use crate::{
+    models::sales_uploads::{Column, Entity, Model},
+    types::salespayloadtype::SalesListQuery,
+    utils::bearer::bearer_resolver,
+};
+
+const DEFAULT_PAGE_SIZE: u64 = 50;
+const MAX_PAGE_SIZE: u64 = 200;
+
+/// Sales of the device, newest invoice first, a page at a time
+/// (`?status=FAILED&salesDtFrom=20260201&salesDtTo=20260228&limit=50`).
+/// Pass `nextCursor` back as `cursor` for the next page; it is null on the last one.
+pub async fn list_sales(
+    TypedHeader(auth): TypedHeader<Authorization<Bearer>>,
+    State(db): State<Arc<DatabaseConnection>>,
+    Query(query): Query<SalesListQuery>,
+) -> impl IntoResponse {
+    if let Err(e) = bearer_resolver(auth.token(), db.as_ref()).await {
+        return (
+            StatusCode::UNAUTHORIZED,
+            Json(json!({ "message": e })),
+        );
+    }
+
+    let limit = query.limit.unwrap_or(DEFAULT_PAGE_SIZE).clamp(1, MAX_PAGE_SIZE);
+
+    let mut select = Entity::find().filter(Column::ApiKey.eq(auth.token()));
+    if let Some(status) = query.status {
+        select = select.filter(Column::Status.eq(status));
+    }
+    if let Some(from) = query.sales_dt_from {
+        select = select.filter(Column::SalesDt.gte(from));
+    }
+    if let Some(to) = query.sales_dt_to {
+        select = select.filter(Column::SalesDt.lte(to));
+    }
+    if let Some(cust_tin) = query.cust_tin {
+        select = select.filter(Column::CustTin.eq(cust_tin));
+    }
+    if let Some(rcpt_ty_cd) = query.rcpt_ty_cd {
+        select = select.filter(Column::RcptTyCd.eq(rcpt_ty_cd));
+    }
+    if let Some(from) = query.invc_no_from {
+        select = select.filter(Column::InvcNo.gte(from));
+    }
+    if let Some(to) = query.invc_no_to {
+        select = select.filter(Column::InvcNo.lte(to));
+    }
+    if let Some(cursor) = query.cursor {
+        select = select.filter(Column::InvcNo.lt(cursor));
+    }
+
+    // One row more than asked for tells whether there is another page
+    let mut rows = match select
+        .order_by_desc(Column::InvcNo)
+        .limit(limit + 1)
+        .all(db.as_ref())
+        .await
+    {
+        Ok(rows) => rows,
+        Err(e) => {
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({ "message": format!("Failed to fetch sales: {e}") })),
+            )
+        }
+    };
+
+    let next_cursor = if rows.len() as u64 > limit {
+        rows.truncate(limit as usize);
+        rows.last().map(|r| r.invc_no)
+    } else {
+        None
+    };
+
+    let data: Vec<Value> = rows.iter().map(summary).collect();
+
+    (
+        StatusCode::OK,
+        Json(json!({
+            "message": "success",
+            "data": data,
+            "nextCursor": next_cursor,
+        })),
+    )
+}
+
+/// One sale of the device by invoice number, with its lines, receipt and
+/// the VSCU's answer
+pub async fn get_sale(
+    TypedHeader(auth): TypedHeader<Authorization<Bearer>>,
+    State(db): State<Arc<DatabaseConnection>>,
+    Path(invc_no): Path<i64>,
+) -> impl IntoResponse {
+    if let Err(e) = bearer_resolver(auth.token(), db.as_ref()).await {
+        return (
+            StatusCode::UNAUTHORIZED,
+            Json(json!({ "message": e })),
+        );
+    }
+
+    match Entity::find()
+        .filter(Column::ApiKey.eq(auth.token()))
+        .filter(Column::InvcNo.eq(invc_no))
+        .one(db.as_ref())
+        .await
+    {
+        Ok(Some(record)) => (
+            StatusCode::OK,
+            Json(json!({
+                "message": "success",
+                "data": detail(&record),
+            })),
+        ),
+        Ok(None) => (
+            StatusCode::NOT_FOUND,
+            Json(json!({ "message": "Sale not found" })),
+        ),
+        Err(e) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(json!({ "message": format!("Failed to fetch sale: {e}") })),
+        ),
+    }
+}
+
+/// List entry: what identifies a sale and where it stands
+fn summary(record: &Model) -> Value {
+    json!({
+        "id": record.id,
+        "invcNo": record.invc_no,
+        "trdInvcNo": record.trd_invc_no,
+        "orgInvcNo": record.org_invc_no,
+        "salesDt": record.sales_dt,
+        "custTin": record.cust_tin,
+        "custNm": record.cust_nm,
+        "salesTyCd": record.sales_ty_cd,
+        "rcptTyCd": record.rcpt_ty_cd,
+        "pmtTyCd": record.pmt_ty_cd,
+        "totItemCnt": record.tot_item_cnt,
+        "totTaxblAmt": record.tot_taxbl_amt,
+        "totTaxAmt": record.tot_tax_amt,
+        "totAmt": record.tot_amt,
+        "refundedAmt": record.refunded_amt,
+        "status": record.status,
+        "rcptNo": record.rcpt_no,
+        "createdAt": record.created_at,
+    })
+}
+
+/// The whole stored record minus the device's own secrets
+fn detail(record: &Model) -> Value {
+    let mut value = serde_json::to_value(record).unwrap_or_default();
+    if let Some(fields) = value.as_object_mut() {
+        // bearer token and encrypted PIN / branch id
+        for secret in ["api_key", "tin", "bhf_id"] {
+            fields.remove(secret);
+        }
+    }
+    value
+}
@@ -1,20 +1,23 @@
 
-use std::sync::Arc;
+use std::{collections::HashMap, sync::Arc};
 
 use axum::{
-    Json, Router, extract::State, http::StatusCode, response::IntoResponse, routing::post
+    Json, Router, extract::{Path, Query, State}, http::{HeaderMap, StatusCode}, response::IntoResponse, routing::{get, post}
 };
 use axum_extra::extract::TypedHeader;
 use headers::{Authorization, authorization::Bearer};
-use sea_orm::{ActiveModelTrait, ActiveValue::Set, ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter, QueryOrder, QuerySelect, TransactionTrait};
-use serde_json::json;
+use sea_orm::{ActiveModelTrait, ActiveValue::Set, ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter, TransactionTrait};
+use serde_json::{Value, json};
 use tracing::{info, error};
-use reqwest;
 use chrono::Utc;
 use crate::{
-    models::sales_uploads::{ActiveModel, Column, Entity},
-    types::salespayloadtype::{AuthUser, InvoicePayload},
-    utils::{bearer::bearer_resolver, crypto::{decrypt, decrypt_deterministic}},
+    customers::verify::check_pins,
+    stock_management::consume_components::{RecordedMovement, SoldInvoice, consume_components, return_stock, transmit_movements},
+    models::sales_uploads::{ActiveModel, Column, Entity, Model},
+    sales::{credit_notes::{build_credit_note, check_credit_notes, refresh_refunded_amt, refunded_quantities}, kra_payload::KraSalesPayload, query::{get_sale, list_sales}, idempotency::{Claim, claim, complete, idempotency_key, request_hash}, invoice_numbers::{allocate, lock_counter}, status::{self, apply_kra_response, signed_receipt}, tax::{compute_taxes, kra_round, tax_rates}, validate::{valid_codes, validate_sales}},
+    types::{customers::PinCheckQuery, salespayloadtype::{AuthUser, CreditNoteReq, InvoicePayload, SalesQuery}},
+    utils::{bearer::bearer_resolver, crypto::{decrypt, decrypt_deterministic}, retry},
+    vscu::{client::VscuClient, device::Device, result::ResultClass},
 };
 
 
@@ -25,13 +28,20 @@ use crate::{
 
 pub fn sales_route(db: Arc<DatabaseConnection>) -> Router {
     Router::new()
-        .route("/", post(handle_payload_post))
+        .route("/", get(list_sales).post(handle_payload_post))
+        .route("/calculate", post(calculate_preview))
+        .route("/{invc_no}", get(get_sale))
+        .route("/{id}/status", get(sale_status))
+        .route("/{invc_no}/credit-note", post(create_credit_note))
         .with_state(db)
 }
 
 pub async fn handle_payload_post(
     TypedHeader(auth): TypedHeader<Authorization<Bearer>>,
     State(db): State<Arc<DatabaseConnection>>,
+    headers: HeaderMap,
+    Query(query): Query<PinCheckQuery>,
+    Query(sales_query): Query<SalesQuery>,
     Json(payload): Json<InvoicePayload>,
 ) -> impl IntoResponse {
 
@@ -57,6 +67,191 @@ pub async fn handle_payload_post(
         }
     };
 
+    let hash = request_hash(&payload);
+    submit_idempotent(db.clone(), token, &headers, hash, submit_sales(db, token, user, query, sales_query, payload)).await
+}
+
+/// Credit note for part or all of the goods of sale `invc_no`, built from
+/// the stored sale so the POS only names what comes back
+pub async fn create_credit_note(
+    TypedHeader(auth): TypedHeader<Authorization<Bearer>>,
+    State(db): State<Arc<DatabaseConnection>>,
+    headers: HeaderMap,
+    Path(invc_no): Path<i64>,
+    Json(req): Json<CreditNoteReq>,
+) -> impl IntoResponse {
+    let token = auth.token();
+
+    let user: AuthUser = match bearer_resolver(token, db.as_ref()).await {
+        Ok(val) => match serde_json::from_value(val) {
+            Ok(u) => u,
+            Err(e) => {
+                return (
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    Json(json!({ "message": format!("Failed to parse user: {}", e) })),
+                )
+            }
+        },
+        Err(e) => {
+            return (
+                StatusCode::UNAUTHORIZED,
+                Json(json!({ "message": e })),
+            )
+        }
+    };
+
+    let hash = request_hash(&(invc_no, &req));
+    let work = async {
+        let original = match Entity::find()
+            .filter(Column::ApiKey.eq(token))
+            .filter(Column::InvcNo.eq(invc_no))
+            .one(db.as_ref())
+            .await
+        {
+            Ok(Some(r)) => r,
+            Ok(None) => {
+                return (
+                    StatusCode::NOT_FOUND,
+                    Json(json!({ "message": format!("Invoice #{} not found", invc_no) })),
+                )
+            }
+            Err(e) => {
+                return (
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    Json(json!({ "message": format!("Failed to fetch invoice: {e}") })),
+                )
+            }
+        };
+
+        let refunded = match refunded_quantities(db.as_ref(), token, invc_no).await {
+            Ok(r) => r,
+            Err(e) => {
+                return (
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    Json(json!({ "message": format!("Failed to fetch earlier credit notes: {e}") })),
+                )
+            }
+        };
+
+        // Limits are checked again under the counter lock in submit_sales
+        let note = match build_credit_note(&original, req, &refunded) {
+            Ok(n) => n,
+            Err(e) => {
+                return (
+                    StatusCode::UNPROCESSABLE_ENTITY,
+                    Json(json!({ "message": e })),
+                )
+            }
+        };
+
+        submit_sales(db.clone(), token, user, PinCheckQuery::default(), SalesQuery::default(), InvoicePayload(vec![note])).await
+    };
+
+    submit_idempotent(db.clone(), token, &headers, hash, work).await
+}
+
+/// Run `work` behind the request's Idempotency-Key, if it has one. `hash`
+/// identifies the request as the client sent it.
+async fn submit_idempotent(
+    db: Arc<DatabaseConnection>,
+    token: &str,
+    headers: &HeaderMap,
+    hash: String,
+    work: impl Future<Output = (StatusCode, Json<Value>)>,
+) -> (StatusCode, Json<Value>) {
+    // A resubmission with the same Idempotency-Key gets the first answer again
+    let claimed = match idempotency_key(headers) {
+        Some(key) => match claim(db.as_ref(), token, &key, &hash).await {
+            Ok(Claim::New(id)) => Some(id),
+            Ok(Claim::Replay(code, response)) => {
+                info!("Replaying answer for Idempotency-Key {}", key);
+                return (code, Json(response));
+            }
+            Err(res) => return res,
+        },
+        None => None,
+    };
+
+    let (code, Json(response)) = work.await;
+
+    if let Some(id) = claimed
+        && let Err(e) = complete(db.as_ref(), id, code, &response).await
+    {
+        error!("Failed to store the answer for Idempotency-Key row {}: {}", id, e);
+    }
+
+    (code, Json(response))
+}
+
+/// Validate, store and transmit the invoices of an authenticated `/sales` request
+async fn submit_sales(
+    db: Arc<DatabaseConnection>,
+    token: &str,
+    user: AuthUser,
+    query: PinCheckQuery,
+    sales_query: SalesQuery,
+    mut payload: InvoicePayload,
+) -> (StatusCode, Json<Value>) {
+    if sales_query.calculate_tax {
+        let rates = match tax_rates(db.as_ref()).await {
+            Ok(r) => r,
+            Err(e) => {
+                return (
+                    StatusCode::SERVICE_UNAVAILABLE,
+                    Json(json!({ "message": e })),
+                )
+            }
+        };
+        for sale in payload.0.iter_mut() {
+            compute_taxes(sale, &rates);
+        }
+    }
+
+    let codes = match valid_codes(db.as_ref()).await {
+        Ok(c) => c,
+        Err(e) => {
+            return (
+                StatusCode::SERVICE_UNAVAILABLE,
+                Json(json!({ "message": e })),
+            )
+        }
+    };
+
+    // Reject bad totals, dates and codes here instead of waiting for KRA to
+    let errors = validate_sales(&payload.0, &codes);
+    if !errors.is_empty() {
+        return (
+            StatusCode::UNPROCESSABLE_ENTITY,
+            Json(json!({
+                "message": "Invalid sales payload",
+                "errors": errors,
+            })),
+        );
+    }
+
+    // Optionally look the customer PINs up with KRA before storing anything
+    let pins: Vec<&str> = payload.0.iter().map(|s| s.custTin.as_str()).collect();
+    let pin_problems = match Device::from_user(&user) {
+        Ok(device) => match check_pins(&device, query.pin_check, &pins).await {
+            Ok(problems) => problems,
+            Err(unknown) => {
+                return (
+                    StatusCode::UNPROCESSABLE_ENTITY,
+                    Json(json!({
+                        "message": "Unknown customer PIN",
+                        "pinCheck": unknown,
+                    })),
+                )
+            }
+        },
+        Err(e) => {
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({ "message": e })),
+            )
+        }
+    };
+
     // 2️⃣ START TRANSACTION
     let txn = match db.begin().await {
         Ok(t) => t,
@@ -68,46 +263,101 @@ pub async fn handle_payload_post(
         }
     };
 
-    // first get to fetch the entire records with api if none start from 0
-
-    // 3️⃣ FETCH LAST INVOICE NUMBER FOR THIS API KEY
-   let last_sale = match Entity::find()
-    .filter(Column::ApiKey.eq(token.clone()))
-    .order_by_desc(Column::GeneratedInvcNo)
-    .limit(1)
-    .all(db.as_ref())
-    .await
-{
-    Ok(mut rows) => rows.pop(),
-    Err(err) => {
+    // 3️⃣ LOCK THE DEVICE'S INVOICE COUNTER, then look for invoices the POS already sent
+    if let Err(err) = lock_counter(&txn, token).await {
         let _ = txn.rollback().await;
         return (
             StatusCode::INTERNAL_SERVER_ERROR,
             Json(json!({
-                "message": format!("Failed to fetch last sale: {err}")
+                "message": format!("Failed to lock invoice counter: {err}")
             })),
         );
     }
-};
 
+    let trd_invc_nos: Vec<i32> = payload.0.iter().filter_map(|s| s.trdInvcNo).collect();
+    let known: HashMap<i32, Model> = match Entity::find()
+        .filter(Column::ApiKey.eq(token))
+        .filter(Column::TrdInvcNo.is_in(trd_invc_nos))
+        .all(&txn)
+        .await
+    {
+        Ok(rows) => rows.into_iter().filter_map(|r| Some((r.trd_invc_no?, r))).collect(),
+        Err(err) => {
+            let _ = txn.rollback().await;
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({
+                    "message": format!("Failed to look up earlier invoices: {err}")
+                })),
+            );
+        }
+    };
 
-   
+    let is_known = |trd_invc_no: Option<i32>| trd_invc_no.is_some_and(|n| known.contains_key(&n));
+    let fresh_invoices: Vec<(usize, &_)> = payload.0.iter().enumerate().filter(|(_, s)| !is_known(s.trdInvcNo)).collect();
+    let fresh = fresh_invoices.len();
 
-    let mut current_invoice_number = if let Some(record) = last_sale {
-        record.generated_invc_no
-    } else {
-        0
+    // Credit notes may not give back more than their sale, counting earlier ones
+    match check_credit_notes(&txn, token, &fresh_invoices).await {
+        Ok(errors) if errors.is_empty() => {}
+        Ok(errors) => {
+            let _ = txn.rollback().await;
+            return (
+                StatusCode::UNPROCESSABLE_ENTITY,
+                Json(json!({
+                    "message": "Invalid credit note",
+                    "errors": errors,
+                })),
+            );
+        }
+        Err(err) => {
+            let _ = txn.rollback().await;
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({
+                    "message": format!("Failed to check credit notes: {err}")
+                })),
+            );
+        }
+    }
+
+    let mut current_invoice_number = match allocate(&txn, token, fresh as i64).await {
+        Ok(first) => first - 1,
+        Err(err) => {
+            let _ = txn.rollback().await;
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({
+                    "message": format!("Failed to allocate invoice numbers: {err}")
+                })),
+            );
+        }
     };
 
     let mut inserted_ids: Vec<i32> = Vec::new();
+    let mut moved_stock: Vec<RecordedMovement> = Vec::new();
+    let mut results: Vec<serde_json::Value> = Vec::new();
 
     // 4️⃣ INSERT PAYLOAD - INCREMENT FOR EACH ITEM
     for item in payload.0.iter() {
+        // Resubmitted invoice: answer with the original instead of a duplicate
+        if let Some(original) = item.trdInvcNo.and_then(|n| known.get(&n)) {
+            info!("trdInvcNo {} was already stored as invoice #{}", original.trd_invc_no.unwrap_or_default(), original.invc_no);
+            results.push(json!({
+                "id": original.id,
+                "invcNo": original.invc_no,
+                "status": original.status,
+                "duplicate": true,
+                "data": signed_receipt(original),
+            }));
+            continue;
+        }
+
         current_invoice_number += 1;
 
         let model = ActiveModel {
             api_key: Set(token.to_string()),
-            status: Set("RECEIVED".to_string()),
+            status: Set(status::RECEIVED.to_string()),
 
             tin: Set(user.pin.clone()),
             bhf_id: Set(user.branch_id.clone()),
@@ -121,11 +371,11 @@ pub async fn handle_payload_post(
             cust_tin: Set(item.custTin.clone()),
             cust_nm: Set(item.custNm.clone()),
 
-            taxbl_amt_a: Set(item.taxblAmtA),
-            taxbl_amt_b: Set(item.taxblAmtB),
-            taxbl_amt_c: Set(item.taxblAmtC),
-            taxbl_amt_d: Set(item.taxblAmtD),
-            taxbl_amt_e: Set(item.taxblAmtE),
+            taxbl_amt_a: Set(kra_round(item.taxblAmtA)),
+            taxbl_amt_b: Set(kra_round(item.taxblAmtB)),
+            taxbl_amt_c: Set(kra_round(item.taxblAmtC)),
+            taxbl_amt_d: Set(kra_round(item.taxblAmtD)),
+            taxbl_amt_e: Set(kra_round(item.taxblAmtE)),
 
             tax_rt_a: Set(item.taxRtA),
             tax_rt_b: Set(item.taxRtB),
@@ -133,15 +383,15 @@ pub async fn handle_payload_post(
             tax_rt_d: Set(item.taxRtD),
             tax_rt_e: Set(item.taxRtE),
 
-            tax_amt_a: Set(item.taxAmtA),
-            tax_amt_b: Set(item.taxAmtB),
-            tax_amt_c: Set(item.taxAmtC),
-            tax_amt_d: Set(item.taxAmtD),
-            tax_amt_e: Set(item.taxAmtE),
+            tax_amt_a: Set(kra_round(item.taxAmtA)),
+            tax_amt_b: Set(kra_round(item.taxAmtB)),
+            tax_amt_c: Set(kra_round(item.taxAmtC)),
+            tax_amt_d: Set(kra_round(item.taxAmtD)),
+            tax_amt_e: Set(kra_round(item.taxAmtE)),
 
-            tot_taxbl_amt: Set(item.totTaxblAmt),
-            tot_tax_amt: Set(item.totTaxAmt),
-            tot_amt: Set(item.totAmt),
+            tot_taxbl_amt: Set(kra_round(item.totTaxblAmt)),
+            tot_tax_amt: Set(kra_round(item.totTaxAmt)),
+            tot_amt: Set(kra_round(item.totAmt)),
 
             regr_id: Set(item.regrId.clone()),
             regr_nm: Set(item.regrNm.clone()),
@@ -171,6 +421,42 @@ pub async fn handle_payload_post(
         match model.insert(&txn).await {
             Ok(inserted) => {
                 inserted_ids.push(inserted.id);
+                results.push(json!({
+                    "id": inserted.id,
+                    "invcNo": current_invoice_number,
+                    "trdInvcNo": item.trdInvcNo,
+                    "status": status::RECEIVED,
+                    "statusUrl": format!("/sales/{}/status", inserted.id),
+                }));
+                let moved = SoldInvoice {
+                    sale_id: inserted.id,
+                    invc_no: current_invoice_number,
+                    org_invc_no: item.orgInvcNo,
+                    sales_dt: item.salesDt.clone(),
+                    cust_tin: Some(item.custTin.clone()).filter(|t| !t.is_empty()),
+                    cust_nm: Some(item.custNm.clone()).filter(|n| !n.is_empty()),
+                    regr_nm: item.regrNm.clone(),
+                    regr_id: item.regrId.clone(),
+                    lines: item.itemList.iter().map(|l| (l.itemCd.clone(), l.qty)).collect(),
+                };
+                // Components of composite items leave stock with the sale and
+                // come back with a credit note, in the same transaction
+                let recorded = match item.rcptTyCd.as_str() {
+                    "S" => consume_components(&txn, &user, &moved).await,
+                    "R" => return_stock(&txn, &user, &moved).await,
+                    _ => Ok(None),
+                };
+                match recorded {
+                    Ok(Some(movement)) => moved_stock.push(movement),
+                    Ok(None) => {}
+                    Err(e) => {
+                        let _ = txn.rollback().await;
+                        return (
+                            StatusCode::INTERNAL_SERVER_ERROR,
+                            Json(json!({ "message": format!("Failed to move stock of invoice #{}: {e}", current_invoice_number) })),
+                        );
+                    }
+                }
                 info!("Inserted invoice #{} with ID {} for api_key: {}", 
                       current_invoice_number, inserted.id, token);
             }
@@ -184,6 +470,23 @@ pub async fn handle_payload_post(
         }
     }
 
+    // Keep the refunded total of every sale given back from in step
+    let mut refunded_sales: Vec<i64> = payload.0.iter()
+        .filter(|s| s.rcptTyCd == "R" && !is_known(s.trdInvcNo))
+        .map(|s| s.orgInvcNo)
+        .collect();
+    refunded_sales.sort();
+    refunded_sales.dedup();
+    for org_invc_no in refunded_sales {
+        if let Err(e) = refresh_refunded_amt(&txn, token, org_invc_no).await {
+            let _ = txn.rollback().await;
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({ "message": format!("Failed to update refunded amount: {e}") })),
+            );
+        }
+    }
+
     // 5️⃣ COMMIT TRANSACTION in a wierd syntax
     if let Err(e) = txn.commit().await {
         return (
@@ -194,229 +497,204 @@ pub async fn handle_payload_post(
 
     info!("Successfully inserted {} invoices. Starting KRA transmission...", inserted_ids.len());
 
-    // 6️⃣ TRANSMIT TO KRA ENDPOINT
-for id in inserted_ids {
-        // Fetch the record we just inserted
-        let record = match Entity::find_by_id(id).one(db.as_ref()).await {
-            Ok(Some(r)) => {
-        println!("records: {:?}", r);
-        r
-    },
+    // Stock moved with the invoices is sent once they are committed
+    if !moved_stock.is_empty() {
+        match Device::from_user(&user) {
+            Ok(device) => {
+                tokio::spawn(transmit_movements(db.clone(), device, moved_stock));
+            }
+            Err(e) => error!("Cannot send stock moved with the invoices: {}", e),
+        }
+    }
+
+    // 6️⃣ TRANSMIT TO KRA IN THE BACKGROUND, the POS polls /sales/{id}/status
+    if !inserted_ids.is_empty() {
+        tokio::spawn(transmit_sales(db.clone(), user.clone(), inserted_ids));
+    }
+
+    // Nothing new when every invoice was a resubmission
+    let code = if fresh > 0 { StatusCode::ACCEPTED } else { StatusCode::OK };
+
+    (
+        code,
+        Json(json!({
+            "message": "accepted",
+            "resultMsg": "Sales stored, transmission to KRA runs in the background",
+            "invoices_created": fresh,
+            "last_invoice_number": current_invoice_number,
+            "pinCheck": pin_problems,
+            "results": results
+        })),
+    )
+}
+
+/// Send freshly stored sales to the device's VSCU one after the other.
+/// Anything that does not go through is left to the retry worker.
+async fn transmit_sales(db: Arc<DatabaseConnection>, user: AuthUser, ids: Vec<i32>) {
+    let vscu = VscuClient::for_user(&user);
+
+    for id in ids {
+        // Take the record unless the retry worker already got to it
+        let record = match retry::claim::<Entity, _>(db.as_ref(), id, &[status::RECEIVED]).await {
+            Ok(Some(r)) => r,
             Ok(None) => {
-                error!("Record with ID {} not found after insert", id);
+                info!("Record {} is already being sent, skipped", id);
                 continue;
             }
             Err(e) => {
-                error!("Failed to fetch record {}: {}", id, e);
+                error!("Failed to claim record {}: {}", id, e);
                 continue;
             }
         };
 
+        // TIN is stored with the deterministic cipher, branch_id with the other one
+        let decrypted_tin = match decrypt_deterministic(&record.tin) {
+            Ok(d) => d,
+            Err(e) => {
+                error!("Failed to decrypt TIN for record {}: {}", id, e);
+                mark_as_failed_with_retry(db.as_ref(), id, 0).await.ok();
+                continue;
+            }
+        };
 
-        // Update status to PROCESSING (lock)
-        if let Err(e) = update_status(db.as_ref(), id, "PROCESSING").await {
-            error!("Failed to update status to PROCESSING for ID {}: {}", id, e);
-            continue;
-        }
-// info!("Fetched tin: {:?}", &record.tin);
-//         // Decrypt TIN and BHF_ID
-let decrypted_tin = match decrypt_deterministic(&record.tin) {
-    Ok(d) => {
-        println!("Decrypt tin OK: {}", d);
-        d
-    }
-    Err(e) => {
-        error!("Failed to decrypt TIN for record {}: {}", id, e);
-        mark_as_failed_with_retry(db.as_ref(), id, 0).await.ok();
-        continue;
-    }
-};
-
-// info!("Decrypted TIN: {:?}", decrypted_tin);
-
+        let decrypted_bhf_id = match decrypt(&record.bhf_id) {
+            Ok(d) => d,
+            Err(e) => {
+                error!("Failed to decrypt BHF_ID for record {}: {}", id, e);
+                mark_as_failed_with_retry(db.as_ref(), id, 0).await.ok();
+                continue;
+            }
+        };
 
-    let decrypted_bhf_id = match decrypt(&record.bhf_id) {  // Note: use decrypt, not decrypt_deterministic
-    Ok(d) => d,
-    Err(e) => {
-        error!("Failed to decrypt BHF_ID for record {}: {}", id, e);
-        mark_as_failed_with_retry(db.as_ref(), id, 0).await.ok();
-        continue;
-    }
-};
-    
-//     match decrypt_deterministic() {
-//     Ok(d) => {
-//         println!("Decrypt id OK: {}", d);
-//         d
-//     }
-//     Err(e) => {
-//         error!("Failed to decrypt BHF_ID for record {}: {}", id, e);
-//         mark_as_failed_with_retry(db.as_ref(), id, 0).await.ok();
-//         continue;
-//     }
-// };
-
-
-
-        
-        
-   
-println!("dec1 (unwrapped): {}", decrypted_tin);
-println!("dec2 (unwrapped): {}", decrypted_bhf_id);
         // Build KRA payload
-        let kra_payload = json!({
-            "tin": decrypted_tin,
-            "bhfId": decrypted_bhf_id,
-            "trdInvcNo": record.trd_invc_no,
-            "invcNo": record.invc_no,
-            "orgInvcNo": record.org_invc_no,
-            "custTin": record.cust_tin,
-            "custNm": record.cust_nm,
-            "salesTyCd": record.sales_ty_cd,
-            "rcptTyCd": record.rcpt_ty_cd,
-            "pmtTyCd": record.pmt_ty_cd,
-            "salesSttsCd": record.sales_stts_cd,
-            "cfmDt": record.cfm_dt,
-            "salesDt": record.sales_dt,
-            "stockRlsDt": record.stock_rls_dt,
-            "cnclReqDt": record.cncl_req_dt,
-            "cnclDt": record.cncl_dt,
-            "rfdDt": record.rfd_dt,
-            "rfdRsnCd": record.rfd_rsn_cd,
-            "totItemCnt": record.tot_item_cnt,
-            "taxblAmtA": record.taxbl_amt_a,
-            "taxblAmtB": record.taxbl_amt_b,
-            "taxblAmtC": record.taxbl_amt_c,
-            "taxblAmtD": record.taxbl_amt_d,
-            "taxblAmtE": record.taxbl_amt_e,
-            "taxRtA": record.tax_rt_a,
-            "taxRtB": record.tax_rt_b,
-            "taxRtC": record.tax_rt_c,
-            "taxRtD": record.tax_rt_d,
-            "taxRtE": record.tax_rt_e,
-            "taxAmtA": record.tax_amt_a,
-            "taxAmtB": record.tax_amt_b,
-            "taxAmtC": record.tax_amt_c,
-            "taxAmtD": record.tax_amt_d,
-            "taxAmtE": record.tax_amt_e,
-            "totTaxblAmt": record.tot_taxbl_amt,
-            "totTaxAmt": record.tot_tax_amt,
-            "totAmt": record.tot_amt,
-            "prchrAcptcYn": record.prchr_acptc_yn,
-            "remark": record.remark,
-            "regrId": record.regr_id,
-            "regrNm": record.regr_nm,
-            "modrId": record.modr_id,
-            "modrNm": record.modr_nm,
-            "receipt": record.receipt,
-            "itemList": record.item_list,
-        });
-println!("jar hitter {:?}",&kra_payload);
+        let kra_payload = KraSalesPayload::new(&record, decrypted_tin, decrypted_bhf_id);
         info!("Sending payload to KRA for invoice #{}", record.invc_no);
 
-        // Send to KRA endpoint with timeout
-        let client = reqwest::Client::builder()
-            .timeout(tokio::time::Duration::from_secs(30))
-            .build()
-            .unwrap();
+        // Send to the device's VSCU
+        match vscu.save_sales(&kra_payload).await {
+            Ok(kra_response) => {
+                info!("Received response from KRA for invoice #{}", record.invc_no);
 
-        match client
-            .post("http://192.168.1.71:8088/trnsSales/saveSales")
-            .json(&kra_payload)
-            .send()
-            .await
-        {
-            Ok(response) => {
-                if !response.status().is_success() {
-                    error!("KRA returned error status: {} for record {}", response.status(), id);
-                    mark_as_failed_with_retry(db.as_ref(), id, 0).await.ok();
-                    continue;
-                }
-
-                match response.json::<serde_json::Value>().await {
-                    Ok(kra_response) => {
-                        info!("Received response from KRA for invoice #{}", record.invc_no);
-                        
-                        // Update record with response and status
-                        if let Err(e) = update_record_with_response(
-                            db.as_ref(),
-                            id,
-                            kra_response,
-                            "TRANSMITTED"
-                        ).await {
-                            error!("Failed to update record {} with KRA response: {}", id, e);
-                        }
+                // Let the result code decide where the record goes next
+                match apply_kra_response(db.as_ref(), id, &kra_response).await {
+                    Ok(ResultClass::Retryable) => {
+                        mark_as_failed_with_retry(db.as_ref(), id, 0).await.ok();
                     }
+                    Ok(_) => {}
                     Err(e) => {
-                        error!("Failed to parse KRA response for record {}: {}", id, e);
-                        mark_as_failed_with_retry(db.as_ref(), id, 0).await.ok();
+                        error!("Failed to update record {} with KRA response: {}", id, e);
                     }
                 }
             }
             Err(e) => {
-                error!("Failed to send to KRA endpoint for record {}: {}", id, e);
+                error!("Failed to transmit record {}: {}", id, e);
                 mark_as_failed_with_retry(db.as_ref(), id, 0).await.ok();
             }
         }
     }
+}
+
+/// Where invoice `id` of this device stands, with the KRA signature once
+/// it has one. Polled by the POS after `POST /sales` answered 202.
+pub async fn sale_status(
+    TypedHeader(auth): TypedHeader<Authorization<Bearer>>,
+    State(db): State<Arc<DatabaseConnection>>,
+    Path(id): Path<i32>,
+) -> impl IntoResponse {
+    if let Err(e) = bearer_resolver(auth.token(), db.as_ref()).await {
+        return (
+            StatusCode::UNAUTHORIZED,
+            Json(json!({ "message": e })),
+        );
+    }
+
+    let record = match Entity::find_by_id(id)
+        .filter(Column::ApiKey.eq(auth.token()))
+        .one(db.as_ref())
+        .await
+    {
+        Ok(Some(record)) => record,
+        Ok(None) => {
+            return (
+                StatusCode::NOT_FOUND,
+                Json(json!({ "message": "Sale not found" })),
+            )
+        }
+        Err(e) => {
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({ "message": format!("Failed to fetch sale: {e}") })),
+            )
+        }
+    };
+
+    // Last VSCU answer, if the invoice was sent at all
+    let answer = |key: &str| record.response.as_ref().and_then(|r| r.get(key)).cloned();
 
     (
         StatusCode::OK,
         Json(json!({
-            "message": "success",
-            "resultMsg": "Sales uploaded and transmitted successfully",
-            "invoices_created": payload.0.len(),
-            "last_invoice_number": current_invoice_number
+            "id": record.id,
+            "invcNo": record.invc_no,
+            "trdInvcNo": record.trd_invc_no,
+            "status": record.status,
+            "resultCd": answer("resultCd"),
+            "resultMsg": answer("resultMsg"),
+            "retryCount": record.retry_count,
+            "nextRetryAt": record.next_retry_at,
+            "data": signed_receipt(&record),
         })),
     )
 }
 
-// Helper function to update status
-async fn update_status(
-    db: &DatabaseConnection,
-    id: i32,
-    new_status: &str,
-) -> Result<(), sea_orm::DbErr> {
-    use sea_orm::ActiveValue::Set;
-    
-    let record = Entity::find_by_id(id)
-        .one(db)
-        .await?
-        .ok_or(sea_orm::DbErr::RecordNotFound(format!("ID {}", id)))?;
-
-    let mut active_model: ActiveModel = record.into();
-    active_model.status = Set(new_status.to_string());
-    active_model.update(db).await?;
+/// The payload with its tax amounts and totals computed from the lines, as
+/// `POST /sales?calculateTax=true` would store it. Nothing is stored or sent.
+pub async fn calculate_preview(
+    TypedHeader(auth): TypedHeader<Authorization<Bearer>>,
+    State(db): State<Arc<DatabaseConnection>>,
+    Json(mut payload): Json<InvoicePayload>,
+) -> impl IntoResponse {
+    if let Err(e) = bearer_resolver(auth.token(), db.as_ref()).await {
+        return (
+            StatusCode::UNAUTHORIZED,
+            Json(json!({ "message": e })),
+        );
+    }
 
-    info!("Updated record {} status to {}", id, new_status);
-    Ok(())
-}
+    let rates = match tax_rates(db.as_ref()).await {
+        Ok(r) => r,
+        Err(e) => {
+            return (
+                StatusCode::SERVICE_UNAVAILABLE,
+                Json(json!({ "message": e })),
+            )
+        }
+    };
 
-// Helper function to update record with KRA response
-async fn update_record_with_response(
-    db: &DatabaseConnection,
-    id: i32,
-    kra_response: serde_json::Value,
-    new_status: &str,
-) -> Result<(), sea_orm::DbErr> {
-    use sea_orm::ActiveValue::Set;
-    
-    let record = Entity::find_by_id(id)
-        .one(db)
-        .await?
-        .ok_or(sea_orm::DbErr::RecordNotFound(format!("ID {}", id)))?;
+    for sale in payload.0.iter_mut() {
+        compute_taxes(sale, &rates);
+    }
 
-    let mut active_model: ActiveModel = record.into();
-    active_model.status = Set(new_status.to_string());
-    active_model.response = Set(Some(kra_response));
-    active_model.update(db).await?;
+    let codes = match valid_codes(db.as_ref()).await {
+        Ok(c) => c,
+        Err(e) => {
+            return (
+                StatusCode::SERVICE_UNAVAILABLE,
+                Json(json!({ "message": e })),
+            )
+        }
+    };
 
-    info!("Updated record {} with KRA response and status {}", id, new_status);
-    Ok(())
+    (
+        StatusCode::OK,
+        Json(json!({
+            "message": "success",
+            "data": payload.0,
+            "errors": validate_sales(&payload.0, &codes),
+        })),
+    )
 }
 
-
-
 // Add this new helper function
 async fn mark_as_failed_with_retry(
     db: &DatabaseConnection,
@@ -436,7 +714,7 @@ async fn mark_as_failed_with_retry(
     let next_retry = Utc::now() + ChronoDuration::minutes(backoff_minutes);
 
     let mut active_model: ActiveModel = record.into();
-    active_model.status = Set("FAILED".to_string());
+    active_model.status = Set(status::FAILED.to_string());
     active_model.retry_count = Set(Some(current_retry_count));
     active_model.next_retry_at = Set(Some(next_retry.to_rfc3339()));
     active_model.update(db).await?;
@@ -0,0 +1,111 @@
+use sea_orm::{ActiveModelTrait, ActiveValue::Set, DatabaseConnection, EntityTrait};
+use tracing::{info, warn};
+
+use crate::{
+    models::sales_uploads::{ActiveModel, Entity, Model},
+    sales::credit_notes::refresh_refunded_amt,
+    types::salespayloadtype::{TrnsSalesSaveResData, TrnsSalesSaveWrRes},
+    vscu::result::{ResultClass, classify},
+};
+
+// ========== sales.status values ==========
+//
+// RECEIVED ──► PROCESSING ──► TRANSMITTED
+//                  │   ▲
+//                  │   └──── FAILED   (retryable, picked up by the retry worker)
+//                  └───────► REJECTED (KRA refused the invoice, needs a human)
+//
+// A sender claims a row by moving it to PROCESSING with `claimed_at` set
+// (utils::retry::claim); the retry worker only takes a PROCESSING row over
+// once that claim is older than utils::retry::LEASE_SECS.
+//
+// Branch records posted without a bearer token are stored LOCAL_ONLY: there
+// is no device to send them with, so no sender or retry ever picks them up.
+
+pub const RECEIVED: &str = "RECEIVED";
+pub const PROCESSING: &str = "PROCESSING";
+pub const TRANSMITTED: &str = "TRANSMITTED";
+pub const FAILED: &str = "FAILED";
+pub const REJECTED: &str = "REJECTED";
+pub const LOCAL_ONLY: &str = "LOCAL_ONLY";
+
+/// The KRA signature stored for a sale, in the shape the VSCU returned it.
+/// None until the sale was transmitted successfully.
+pub fn signed_receipt(record: &Model) -> Option<TrnsSalesSaveResData> {
+    Some(TrnsSalesSaveResData {
+        rcptNo: record.rcpt_no?,
+        intrlData: record.intrl_data.clone()?,
+        rcptSign: record.rcpt_sign.clone()?,
+        totRcptNo: record.tot_rcpt_no?,
+        VSCURcptPbctDate: record.rcpt_pbct_dt.clone()?,
+        sdcId: record.sdc_id.clone()?,
+        mrcNo: record.mrc_no.clone()?,
+    })
+}
+
+/// Store the VSCU answer for a sale and move it to TRANSMITTED or REJECTED.
+///
+/// Retryable answers are only stored; scheduling the next attempt is left to
+/// the caller, which knows the current retry count.
+pub async fn apply_kra_response(
+    db: &DatabaseConnection,
+    id: i32,
+    kra_response: &TrnsSalesSaveWrRes,
+) -> Result<ResultClass, sea_orm::DbErr> {
+    let class = classify(&kra_response.resultCd);
+
+    let record = Entity::find_by_id(id)
+        .one(db)
+        .await?
+        .ok_or(sea_orm::DbErr::RecordNotFound(format!("ID {}", id)))?;
+
+    // A rejected credit note no longer counts against its sale
+    let rejected_credit_note = (record.rcpt_ty_cd == "R").then(|| (record.api_key.clone(), record.org_invc_no));
+
+    let mut active_model: ActiveModel = record.into();
+    active_model.response = Set(serde_json::to_value(kra_response).ok());
+
+    match class {
+        ResultClass::Success => {
+            active_model.status = Set(TRANSMITTED.to_string());
+            active_model.next_retry_at = Set(None);
+
+            if let Some(data) = &kra_response.data {
+                active_model.rcpt_no = Set(Some(data.rcptNo));
+                active_model.tot_rcpt_no = Set(Some(data.totRcptNo));
+                active_model.rcpt_sign = Set(Some(data.rcptSign.clone()));
+                active_model.intrl_data = Set(Some(data.intrlData.clone()));
+                active_model.sdc_id = Set(Some(data.sdcId.clone()));
+                active_model.mrc_no = Set(Some(data.mrcNo.clone()));
+                active_model.rcpt_pbct_dt = Set(Some(data.VSCURcptPbctDate.clone()));
+            }
+        }
+        ResultClass::Rejected => {
+            active_model.status = Set(REJECTED.to_string());
+            active_model.next_retry_at = Set(None);
+        }
+        ResultClass::Retryable => {}
+    }
+
+    active_model.update(db).await?;
+
+    if class == ResultClass::Rejected
+        && let Some((api_key, org_invc_no)) = rejected_credit_note
+    {
+        refresh_refunded_amt(db, &api_key, org_invc_no).await?;
+    }
+
+    match class {
+        ResultClass::Success => info!("Record {} accepted by KRA", id),
+        ResultClass::Rejected => warn!(
+            "Record {} rejected by KRA: {} {}",
+            id, kra_response.resultCd, kra_response.resultMsg
+        ),
+        ResultClass::Retryable => warn!(
+            "Record {} got retryable result from KRA: {} {}",
+            id, kra_response.resultCd, kra_response.resultMsg
+        ),
+    }
+
+    Ok(class)
+}
@@ -0,0 +1,164 @@
+use std::str::FromStr;
+
+use rust_decimal::{Decimal, RoundingStrategy};
+use sea_orm::{ColumnTrait, ConnectionTrait, EntityTrait, QueryFilter};
+
+use crate::{
+    models::{code_detail::{Column, Entity}, sales_uploads::Model},
+    types::salespayloadtype::TrnsSalesSaveWrReq,
+};
+
+/// KRA code class holding the tax types; `userDfnCd1` is the rate in percent
+const TAX_CODE_CLASS: &str = "04";
+
+const TAX_TYPES: [&str; 5] = ["A", "B", "C", "D", "E"];
+
+/// Rate in percent of tax types A..E
+#[derive(Debug, Clone, Copy)]
+pub struct TaxRates([Decimal; 5]);
+
+impl TaxRates {
+    /// Rates an earlier invoice was issued with, for documents that have to
+    /// match it (credit notes) even after a rate changed
+    pub fn of_sale(sale: &Model) -> Self {
+        TaxRates([sale.tax_rt_a, sale.tax_rt_b, sale.tax_rt_c, sale.tax_rt_d, sale.tax_rt_e])
+    }
+
+    /// Rate in percent of `tax_ty_cd`, None for an unknown tax type
+    pub fn rate(&self, tax_ty_cd: &str) -> Option<Decimal> {
+        tax_index(tax_ty_cd).map(|idx| self.0[idx])
+    }
+}
+
+fn tax_index(tax_ty_cd: &str) -> Option<usize> {
+    TAX_TYPES.iter().position(|ty| *ty == tax_ty_cd)
+}
+
+/// Tax rates from the synced KRA code list. Fails when a tax type is missing,
+/// i.e. the code lists have not been synced yet.
+pub async fn tax_rates<C: ConnectionTrait>(db: &C) -> Result<TaxRates, String> {
+    let codes = Entity::find()
+        .filter(Column::CdCls.eq(TAX_CODE_CLASS))
+        .all(db)
+        .await
+        .map_err(|e| format!("Failed to fetch tax rates: {e}"))?;
+
+    let mut rates = [Decimal::ZERO; 5];
+    for (idx, ty) in TAX_TYPES.iter().enumerate() {
+        let code = codes
+            .iter()
+            .find(|c| c.cd == *ty)
+            .ok_or_else(|| format!("Tax type {ty} is missing from the KRA code list, sync /codes first"))?;
+        rates[idx] = Decimal::from_str(code.user_dfn_cd1.as_deref().unwrap_or("0").trim())
+            .map_err(|_| format!("Tax type {ty} has an invalid rate {:?}", code.user_dfn_cd1))?;
+    }
+
+    Ok(TaxRates(rates))
+}
+
+/// Fill in the amounts of an invoice from its lines' prices, quantities,
+/// discounts and tax types. eTIMS prices include tax, so a line's taxable
+/// amount is its total and the tax is the part of it at `rate / (100 + rate)`.
+///
+/// Per line: `splyAmt`, `dcAmt` (from `dcRt` when given), `totAmt`,
+/// `taxblAmt`, `taxAmt`. Per invoice: `taxblAmtA..E`, `taxRtA..E`,
+/// `taxAmtA..E`, `totTaxblAmt`, `totTaxAmt`, `totAmt` and `totItemCnt`.
+/// Lines with an unknown tax type are left for the validator to report.
+pub fn compute_taxes(sale: &mut TrnsSalesSaveWrReq, rates: &TaxRates) {
+    let mut taxbl = [Decimal::ZERO; 5];
+    let mut tax = [Decimal::ZERO; 5];
+
+    for item in sale.itemList.iter_mut() {
+        item.splyAmt = kra_round(item.prc * item.qty);
+        if item.dcRt > Decimal::ZERO {
+            item.dcAmt = kra_round(item.splyAmt * item.dcRt / Decimal::ONE_HUNDRED);
+        }
+        item.totAmt = kra_round(item.splyAmt - item.dcAmt);
+
+        let Some(idx) = tax_index(&item.taxTyCd) else {
+            continue;
+        };
+        let rate = rates.0[idx];
+
+        item.taxblAmt = item.totAmt;
+        item.taxAmt = included_tax(item.taxblAmt, rate);
+
+        taxbl[idx] += item.taxblAmt;
+        tax[idx] += item.taxAmt;
+    }
+
+    let [taxbl_a, taxbl_b, taxbl_c, taxbl_d, taxbl_e] = taxbl.map(kra_round);
+    let [tax_a, tax_b, tax_c, tax_d, tax_e] = tax.map(kra_round);
+    let [rt_a, rt_b, rt_c, rt_d, rt_e] = rates.0;
+
+    sale.taxblAmtA = taxbl_a;
+    sale.taxblAmtB = taxbl_b;
+    sale.taxblAmtC = taxbl_c;
+    sale.taxblAmtD = taxbl_d;
+    sale.taxblAmtE = taxbl_e;
+
+    sale.taxRtA = rt_a;
+    sale.taxRtB = rt_b;
+    sale.taxRtC = rt_c;
+    sale.taxRtD = rt_d;
+    sale.taxRtE = rt_e;
+
+    sale.taxAmtA = tax_a;
+    sale.taxAmtB = tax_b;
+    sale.taxAmtC = tax_c;
+    sale.taxAmtD = tax_d;
+    sale.taxAmtE = tax_e;
+
+    sale.totTaxblAmt = taxbl_a + taxbl_b + taxbl_c + taxbl_d + taxbl_e;
+    sale.totTaxAmt = tax_a + tax_b + tax_c + tax_d + tax_e;
+    sale.totAmt = sale.itemList.iter().map(|item| item.totAmt).sum();
+    sale.totItemCnt = sale.itemList.len() as i64;
+}
+
+/// Tax contained in the tax-inclusive `amount` at `rate` percent
+pub fn included_tax(amount: Decimal, rate: Decimal) -> Decimal {
+    kra_round(amount * rate / (Decimal::ONE_HUNDRED + rate))
+}
+
+/// Round an amount to cents the way KRA does: half away from zero
+pub fn kra_round(value: Decimal) -> Decimal {
+    value.round_dp_with_strategy(2, RoundingStrategy::MidpointAwayFromZero)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sales::test_support::{dec, invoice, line};
+
+    fn rates() -> TaxRates {
+        TaxRates([dec("0"), dec("16"), dec("0"), dec("0"), dec("8")])
+    }
+
+    #[test]
+    fn takes_tax_type_b_out_of_a_tax_inclusive_price() {
+        let mut sale = invoice(1);
+        let mut bread = line(1, "KE1NTXU0000001", "1", "116");
+        (bread.splyAmt, bread.taxblAmt, bread.taxAmt, bread.totAmt) = Default::default();
+        sale.itemList = vec![bread];
+        (sale.taxblAmtB, sale.taxAmtB, sale.totTaxAmt, sale.totAmt) = Default::default();
+
+        compute_taxes(&mut sale, &rates());
+
+        assert_eq!(sale.itemList[0].taxblAmt, dec("116.00"));
+        assert_eq!(sale.itemList[0].taxAmt, dec("16.00"));
+        assert_eq!(sale.taxblAmtB, dec("116.00"));
+        assert_eq!(sale.taxAmtB, dec("16.00"));
+        assert_eq!(sale.taxRtB, dec("16"));
+        assert_eq!(sale.totTaxAmt, dec("16.00"));
+        assert_eq!(sale.totAmt, dec("116.00"));
+        assert_eq!(sale.totItemCnt, 1);
+    }
+
+    #[test]
+    fn rounds_half_cents_away_from_zero() {
+        assert_eq!(kra_round(dec("0.125")), dec("0.13"));
+        assert_eq!(kra_round(dec("0.135")), dec("0.14"));
+        assert_eq!(kra_round(dec("-0.125")), dec("-0.13"));
+        assert_eq!(kra_round(dec("0.124")), dec("0.12"));
+    }
+}
@@ -0,0 +1,149 @@
+//! Invoices shared by the unit tests of the sales modules
+
+use std::str::FromStr;
+
+use rust_decimal::Decimal;
+use serde_json::json;
+
+use crate::{
+    models::sales_uploads::Model,
+    sales::validate::ValidCodes,
+    types::salespayloadtype::{TrnsSalesSaveWrItem, TrnsSalesSaveWrReq},
+};
+
+pub fn dec(value: &str) -> Decimal {
+    Decimal::from_str(value).unwrap()
+}
+
+/// Line `seq` selling `qty` of `item_cd` at `prc` (tax included), with its
+/// amounts filled in for tax type B at 16%
+pub fn line(seq: i64, item_cd: &str, qty: &str, prc: &str) -> TrnsSalesSaveWrItem {
+    let mut line: TrnsSalesSaveWrItem = serde_json::from_value(json!({
+        "itemSeq": seq, "itemCd": item_cd, "itemClsCd": "5059690800", "itemNm": item_cd,
+        "bcd": null, "pkgUnitCd": "NT", "pkg": 1, "qtyUnitCd": "U", "qty": 0, "prc": 0,
+        "splyAmt": 0, "dcRt": 0, "dcAmt": 0, "isrccCd": null, "isrccNm": null,
+        "taxTyCd": "B", "taxblAmt": 0, "taxAmt": 0, "totAmt": 0
+    }))
+    .unwrap();
+
+    line.qty = dec(qty);
+    line.prc = dec(prc);
+    line.splyAmt = line.qty * line.prc;
+    line.taxblAmt = line.splyAmt;
+    line.totAmt = line.splyAmt;
+    line.taxAmt = (line.totAmt * dec("16") / dec("116")).round_dp(2);
+    line
+}
+
+/// A consistent invoice: a single type B line of 2 × 58.00 = 116.00, 16.00 of it tax
+pub fn invoice(trd_invc_no: i32) -> TrnsSalesSaveWrReq {
+    let mut sale: TrnsSalesSaveWrReq = serde_json::from_value(json!({
+        "tin": null, "bhfId": null, "trdInvcNo": trd_invc_no, "generated_invc_no": null, "invcNo": null,
+        "orgInvcNo": 0, "custTin": "", "custNm": "Walk in", "retry_count": null, "next_retry_at": null,
+        "salesTyCd": "N", "rcptTyCd": "S", "pmtTyCd": "01", "salesSttsCd": "02",
+        "cfmDt": "20260218120000", "salesDt": "20260218", "stockRlsDt": "20260218120000",
+        "cnclReqDt": null, "cnclDt": null, "rfdDt": null, "rfdRsnCd": null,
+        "totItemCnt": 1,
+        "taxblAmtA": 0, "taxblAmtB": 116, "taxblAmtC": 0, "taxblAmtD": 0, "taxblAmtE": 0,
+        "taxRtA": 0, "taxRtB": 16, "taxRtC": 0, "taxRtD": 0, "taxRtE": 8,
+        "taxAmtA": 0, "taxAmtB": 16, "taxAmtC": 0, "taxAmtD": 0, "taxAmtE": 0,
+        "totTaxblAmt": 116, "totTaxAmt": 16, "totAmt": 116,
+        "prchrAcptcYn": "N", "remark": null, "regrId": "u1", "regrNm": "u1", "modrId": "u1", "modrNm": "u1",
+        "receipt": {
+            "custTin": "", "custMblNo": null, "rptNo": 1, "trdeNm": "", "adrs": "",
+            "topMsg": "", "btmMsg": "", "prchrAcptcYn": "N"
+        },
+        "itemList": [],
+        "response": null
+    }))
+    .unwrap();
+
+    sale.itemList.push(line(1, "KE1NTXU0000001", "2", "58"));
+    sale
+}
+
+/// A slice of the KRA code list covering the codes of [`invoice`]
+pub fn valid_codes() -> ValidCodes {
+    let classes: [(&str, &[&str]); 7] = [
+        ("04", &["A", "B", "C", "D", "E"]),
+        ("07", &["01", "02", "03", "04", "05", "06", "07"]),
+        ("10", &["KG", "L", "U"]),
+        ("11", &["01", "02", "03", "04", "05", "06"]),
+        ("14", &["N", "C", "T", "P"]),
+        ("17", &["BG", "BX", "NT"]),
+        ("37", &["S", "R"]),
+    ];
+    ValidCodes::from_codes(
+        classes.into_iter().flat_map(|(cd_cls, cds)| cds.iter().map(move |cd| (cd_cls.to_string(), cd.to_string()))),
+    )
+}
+
+/// `sale` as stored and signed under invoice number `invc_no`
+pub fn stored(sale: &TrnsSalesSaveWrReq, invc_no: i64) -> Model {
+    Model {
+        id: invc_no as i32,
+        api_key: "test-key".to_string(),
+        status: "TRANSMITTED".to_string(),
+        generated_invc_no: invc_no,
+        created_at: chrono::Utc::now().naive_utc(),
+        updated_at: None,
+        tin: String::new(),
+        bhf_id: String::new(),
+        trd_invc_no: sale.trdInvcNo,
+        retry_count: None,
+        next_retry_at: None,
+        claimed_at: None,
+        invc_no,
+        org_invc_no: sale.orgInvcNo,
+        cust_tin: sale.custTin.clone(),
+        cust_nm: sale.custNm.clone(),
+        sales_ty_cd: sale.salesTyCd.clone(),
+        rcpt_ty_cd: sale.rcptTyCd.clone(),
+        pmt_ty_cd: sale.pmtTyCd.clone(),
+        sales_stts_cd: sale.salesSttsCd.clone(),
+        cfm_dt: sale.cfmDt.clone(),
+        sales_dt: sale.salesDt.clone(),
+        stock_rls_dt: sale.stockRlsDt.clone(),
+        cncl_req_dt: None,
+        cncl_dt: None,
+        rfd_dt: None,
+        rfd_rsn_cd: None,
+        tot_item_cnt: sale.totItemCnt as i32,
+        taxbl_amt_a: sale.taxblAmtA,
+        taxbl_amt_b: sale.taxblAmtB,
+        taxbl_amt_c: sale.taxblAmtC,
+        taxbl_amt_d: sale.taxblAmtD,
+        taxbl_amt_e: sale.taxblAmtE,
+        tax_rt_a: sale.taxRtA,
+        tax_rt_b: sale.taxRtB,
+        tax_rt_c: sale.taxRtC,
+        tax_rt_d: sale.taxRtD,
+        tax_rt_e: sale.taxRtE,
+        tax_amt_a: sale.taxAmtA,
+        tax_amt_b: sale.taxAmtB,
+        tax_amt_c: sale.taxAmtC,
+        tax_amt_d: sale.taxAmtD,
+        tax_amt_e: sale.taxAmtE,
+        tot_taxbl_amt: sale.totTaxblAmt,
+        tot_tax_amt: sale.totTaxAmt,
+        tot_amt: sale.totAmt,
+        refunded_amt: Decimal::ZERO,
+        component_usage: None,
+        prchr_acptc_yn: sale.prchrAcptcYn.clone(),
+        remark: None,
+        regr_id: sale.regrId.clone(),
+        regr_nm: sale.regrNm.clone(),
+        modr_id: sale.modrId.clone(),
+        modr_nm: sale.modrNm.clone(),
+        receipt: serde_json::to_value(&sale.receipt).unwrap(),
+        item_list: serde_json::to_value(&sale.itemList).unwrap(),
+        response: None,
+        rcpt_no: Some(invc_no),
+        tot_rcpt_no: Some(invc_no),
+        rcpt_sign: Some("SIGNATURE".to_string()),
+        intrl_data: Some("INTERNALDATA".to_string()),
+        sdc_id: Some("KRACU0100000001".to_string()),
+        mrc_no: Some("WIS00000001".to_string()),
+        rcpt_pbct_dt: Some("20260218120000".to_string()),
+    }
+}
@@ -0,0 +1,306 @@
+use std::collections::HashMap;
+
+use chrono::{NaiveDate, NaiveDateTime};
+use rust_decimal::Decimal;
+use sea_orm::{ColumnTrait, ConnectionTrait, EntityTrait, QueryFilter, QueryOrder};
+use serde::Serialize;
+
+use crate::{
+    models::code_detail::{Column, Entity},
+    sales::tax::kra_round,
+    types::salespayloadtype::{TrnsSalesSaveWrItem, TrnsSalesSaveWrReq},
+};
+
+/// Largest difference between a declared amount and the one computed from
+/// the lines that is still put down to rounding
+const TOLERANCE: Decimal = Decimal::from_parts(1, 0, 0, false, 2);
+
+// KRA code classes the codes of an invoice are checked against
+const TAX_TYPES: &str = "04";
+const PAYMENT_TYPES: &str = "07";
+const QUANTITY_UNITS: &str = "10";
+const SALES_STATUSES: &str = "11";
+const SALES_TYPES: &str = "14";
+const PACKING_UNITS: &str = "17";
+const RECEIPT_TYPES: &str = "37";
+
+const CODE_CLASSES: [&str; 7] =
+    [TAX_TYPES, PAYMENT_TYPES, QUANTITY_UNITS, SALES_STATUSES, SALES_TYPES, PACKING_UNITS, RECEIPT_TYPES];
+
+/// Codes in use per KRA code class, from the synced code list
+#[derive(Debug, Clone, Default)]
+pub struct ValidCodes(HashMap<String, Vec<String>>);
+
+impl ValidCodes {
+    /// From `(cdCls, cd)` pairs
+    pub fn from_codes(codes: impl IntoIterator<Item = (String, String)>) -> Self {
+        let mut classes: HashMap<String, Vec<String>> = HashMap::new();
+        for (cd_cls, cd) in codes {
+            classes.entry(cd_cls).or_default().push(cd);
+        }
+        ValidCodes(classes)
+    }
+
+    fn of(&self, cd_cls: &str) -> &[String] {
+        self.0.get(cd_cls).map_or(&[], Vec::as_slice)
+    }
+}
+
+/// The codes invoices are validated against. Fails when a class is missing,
+/// i.e. the code lists have not been synced yet.
+pub async fn valid_codes<C: ConnectionTrait>(db: &C) -> Result<ValidCodes, String> {
+    let codes = Entity::find()
+        .filter(Column::CdCls.is_in(CODE_CLASSES))
+        .filter(Column::UseYn.eq("Y"))
+        .order_by_asc(Column::Cd)
+        .all(db)
+        .await
+        .map_err(|e| format!("Failed to fetch the KRA code list: {e}"))?;
+
+    let codes = ValidCodes::from_codes(codes.into_iter().map(|c| (c.cd_cls, c.cd)));
+    if let Some(missing) = CODE_CLASSES.iter().find(|cls| codes.of(cls).is_empty()) {
+        return Err(format!("Code class {missing} is missing from the KRA code list, sync /codes first"));
+    }
+
+    Ok(codes)
+}
+
+/// One problem with one field of an invoice in the payload
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FieldError {
+    /// Position of the invoice in the posted array
+    pub index: usize,
+    /// Field path, e.g. `taxblAmtB` or `itemList[2].qty`
+    pub field: String,
+    pub message: String,
+}
+
+/// Check every invoice of a `/sales` payload before anything is stored.
+/// Returns all problems found, not just the first one.
+pub fn validate_sales(sales: &[TrnsSalesSaveWrReq], codes: &ValidCodes) -> Vec<FieldError> {
+    let mut errors = Vec::new();
+
+    if sales.is_empty() {
+        errors.push(FieldError { index: 0, field: String::new(), message: "No invoices provided".to_string() });
+    }
+
+    for (index, sale) in sales.iter().enumerate() {
+        let mut check = Checker { index, codes, errors: &mut errors };
+        check.invoice(sale);
+
+        // The POS invoice number identifies a sale, see (api_key, trd_invc_no)
+        if let Some(trd_invc_no) = sale.trdInvcNo
+            && let Some(first) = sales[..index].iter().position(|s| s.trdInvcNo == Some(trd_invc_no))
+        {
+            check.fail("trdInvcNo", format!("trdInvcNo {trd_invc_no} is already used by invoice {first} of this payload"));
+        }
+    }
+
+    errors
+}
+
+struct Checker<'a> {
+    index: usize,
+    codes: &'a ValidCodes,
+    errors: &'a mut Vec<FieldError>,
+}
+
+impl Checker<'_> {
+    fn fail(&mut self, field: impl Into<String>, message: impl Into<String>) {
+        self.errors.push(FieldError { index: self.index, field: field.into(), message: message.into() });
+    }
+
+    fn invoice(&mut self, sale: &TrnsSalesSaveWrReq) {
+        self.codes(sale);
+        self.dates(sale);
+
+        if sale.itemList.is_empty() {
+            self.fail("itemList", "An invoice needs at least one item");
+        }
+
+        if sale.totItemCnt != sale.itemList.len() as i64 {
+            self.fail(
+                "totItemCnt",
+                format!("totItemCnt is {} but itemList has {} items", sale.totItemCnt, sale.itemList.len()),
+            );
+        }
+
+        for (i, item) in sale.itemList.iter().enumerate() {
+            self.item(i, item);
+        }
+
+        self.tax_totals(sale);
+        self.totals(sale);
+    }
+
+    fn codes(&mut self, sale: &TrnsSalesSaveWrReq) {
+        self.code("salesTyCd", &sale.salesTyCd, SALES_TYPES);
+        self.code("rcptTyCd", &sale.rcptTyCd, RECEIPT_TYPES);
+        self.code("pmtTyCd", &sale.pmtTyCd, PAYMENT_TYPES);
+        self.code("salesSttsCd", &sale.salesSttsCd, SALES_STATUSES);
+        self.one_of("prchrAcptcYn", &sale.prchrAcptcYn, &["Y", "N"]);
+
+        if sale.rcptTyCd == "R" {
+            if sale.orgInvcNo <= 0 {
+                self.fail("orgInvcNo", "A credit note must reference the original invoice");
+            }
+            if sale.rfdRsnCd.as_deref().is_none_or(str::is_empty) {
+                self.fail("rfdRsnCd", "A credit note needs a refund reason");
+            }
+        }
+    }
+
+    /// `value` must be a code of KRA code class `cd_cls`
+    fn code(&mut self, field: &str, value: &str, cd_cls: &str) {
+        let allowed = self.codes.of(cd_cls);
+        if !allowed.iter().any(|cd| cd == value) {
+            self.fail(field, format!("'{}' is not one of {}", value, allowed.join(", ")));
+        }
+    }
+
+    fn one_of(&mut self, field: &str, value: &str, allowed: &[&str]) {
+        if !allowed.contains(&value) {
+            self.fail(field, format!("'{}' is not one of {}", value, allowed.join(", ")));
+        }
+    }
+
+    fn dates(&mut self, sale: &TrnsSalesSaveWrReq) {
+        if NaiveDate::parse_from_str(&sale.salesDt, "%Y%m%d").is_err() {
+            self.fail("salesDt", format!("'{}' is not a yyyyMMdd date", sale.salesDt));
+        }
+
+        self.date_time("cfmDt", Some(&sale.cfmDt));
+        if !sale.stockRlsDt.is_empty() {
+            self.date_time("stockRlsDt", Some(&sale.stockRlsDt));
+        }
+        self.date_time("cnclReqDt", sale.cnclReqDt.as_deref());
+        self.date_time("cnclDt", sale.cnclDt.as_deref());
+        self.date_time("rfdDt", sale.rfdDt.as_deref());
+    }
+
+    fn date_time(&mut self, field: &str, value: Option<&str>) {
+        if let Some(value) = value
+            && NaiveDateTime::parse_from_str(value, "%Y%m%d%H%M%S").is_err()
+        {
+            self.fail(field, format!("'{}' is not a yyyyMMddHHmmss date", value));
+        }
+    }
+
+    fn item(&mut self, i: usize, item: &TrnsSalesSaveWrItem) {
+        let field = |name: &str| format!("itemList[{i}].{name}");
+
+        if item.itemSeq != i as i64 + 1 {
+            self.fail(field("itemSeq"), format!("Expected itemSeq {} but got {}", i + 1, item.itemSeq));
+        }
+        if item.qty <= Decimal::ZERO {
+            self.fail(field("qty"), "Quantity must be positive");
+        }
+        if item.prc < Decimal::ZERO {
+            self.fail(field("prc"), "Price cannot be negative");
+        }
+        self.code(&field("pkgUnitCd"), &item.pkgUnitCd, PACKING_UNITS);
+        self.code(&field("qtyUnitCd"), &item.qtyUnitCd, QUANTITY_UNITS);
+        self.code(&field("taxTyCd"), &item.taxTyCd, TAX_TYPES);
+
+        self.same(field("splyAmt"), item.splyAmt, item.prc * item.qty, "prc × qty");
+        self.same(field("totAmt"), item.totAmt, item.splyAmt - item.dcAmt, "splyAmt - dcAmt");
+    }
+
+    /// taxblAmtX / taxAmtX must be the sums of the lines of tax type X
+    fn tax_totals(&mut self, sale: &TrnsSalesSaveWrReq) {
+        let declared = [
+            ("A", sale.taxblAmtA, sale.taxAmtA),
+            ("B", sale.taxblAmtB, sale.taxAmtB),
+            ("C", sale.taxblAmtC, sale.taxAmtC),
+            ("D", sale.taxblAmtD, sale.taxAmtD),
+            ("E", sale.taxblAmtE, sale.taxAmtE),
+        ];
+
+        for (ty, taxbl_amt, tax_amt) in declared {
+            let lines = sale.itemList.iter().filter(|item| item.taxTyCd == ty);
+            let (line_taxbl, line_tax) = lines.fold((Decimal::ZERO, Decimal::ZERO), |(b, t), item| (b + item.taxblAmt, t + item.taxAmt));
+
+            self.same(format!("taxblAmt{ty}"), taxbl_amt, line_taxbl, &format!("the taxblAmt of type {ty} lines"));
+            self.same(format!("taxAmt{ty}"), tax_amt, line_tax, &format!("the taxAmt of type {ty} lines"));
+        }
+    }
+
+    fn totals(&mut self, sale: &TrnsSalesSaveWrReq) {
+        let taxbl = sale.taxblAmtA + sale.taxblAmtB + sale.taxblAmtC + sale.taxblAmtD + sale.taxblAmtE;
+        let tax = sale.taxAmtA + sale.taxAmtB + sale.taxAmtC + sale.taxAmtD + sale.taxAmtE;
+        let lines: Decimal = sale.itemList.iter().map(|item| item.totAmt).sum();
+
+        self.same("totTaxblAmt".to_string(), sale.totTaxblAmt, taxbl, "taxblAmtA..E");
+        self.same("totTaxAmt".to_string(), sale.totTaxAmt, tax, "taxAmtA..E");
+        self.same("totAmt".to_string(), sale.totAmt, lines, "the totAmt of the lines");
+    }
+
+    fn same(&mut self, field: String, declared: Decimal, expected: Decimal, what: &str) {
+        if (kra_round(declared) - kra_round(expected)).abs() > TOLERANCE {
+            self.fail(field, format!("{} does not match {} ({})", declared, what, kra_round(expected)));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sales::test_support::{dec, invoice, valid_codes};
+
+    fn fields(errors: &[FieldError]) -> Vec<&str> {
+        errors.iter().map(|e| e.field.as_str()).collect()
+    }
+
+    #[test]
+    fn accepts_a_consistent_invoice() {
+        assert!(validate_sales(&[invoice(1)], &valid_codes()).is_empty());
+    }
+
+    #[test]
+    fn allows_one_cent_of_rounding_and_no_more() {
+        type Field = fn(&mut TrnsSalesSaveWrReq) -> &mut Decimal;
+        let checked: [(&str, Field); 6] = [
+            ("itemList[0].splyAmt", |s| &mut s.itemList[0].splyAmt),
+            ("taxblAmtB", |s| &mut s.taxblAmtB),
+            ("taxAmtB", |s| &mut s.taxAmtB),
+            ("totTaxblAmt", |s| &mut s.totTaxblAmt),
+            ("totTaxAmt", |s| &mut s.totTaxAmt),
+            ("totAmt", |s| &mut s.totAmt),
+        ];
+
+        for (field, amount) in checked {
+            for (off_by, accepted) in [("0.01", true), ("-0.01", true), ("0.02", false), ("-0.02", false)] {
+                let mut sale = invoice(1);
+                *amount(&mut sale) += dec(off_by);
+
+                let errors = validate_sales(&[sale], &valid_codes());
+                if accepted {
+                    assert!(errors.is_empty(), "{field} off by {off_by}: {:?}", fields(&errors));
+                } else {
+                    assert!(fields(&errors).contains(&field), "{field} off by {off_by}: {:?}", fields(&errors));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn rejects_a_trd_invc_no_repeated_in_the_payload() {
+        let errors = validate_sales(&[invoice(7), invoice(8), invoice(7)], &valid_codes());
+
+        assert_eq!(errors.len(), 1, "{:?}", fields(&errors));
+        assert_eq!(errors[0].index, 2);
+        assert_eq!(errors[0].field, "trdInvcNo");
+    }
+
+    #[test]
+    fn rejects_codes_missing_from_the_code_list() {
+        let mut sale = invoice(1);
+        sale.pmtTyCd = "08".to_string();
+        sale.itemList[0].pkgUnitCd = "XX".to_string();
+        sale.itemList[0].qtyUnitCd = "BOX".to_string();
+
+        let errors = validate_sales(&[sale], &valid_codes());
+        assert_eq!(fields(&errors), ["pmtTyCd", "itemList[0].pkgUnitCd", "itemList[0].qtyUnitCd"]);
+    }
+}
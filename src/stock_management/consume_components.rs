@@ -0,0 +1,369 @@
+use std::{
+    collections::{BTreeMap, BTreeSet, HashMap},
+    sync::Arc,
+};
+
+use rust_decimal::{Decimal, prelude::ToPrimitive};
+use sea_orm::{
+    ColumnTrait, ConnectionTrait, DatabaseConnection, DatabaseTransaction, EntityTrait, QueryFilter,
+    TransactionTrait, sea_query::Expr,
+};
+use serde::{Deserialize, Serialize};
+use tracing::{info, warn};
+
+use crate::{
+    models::{item_composition, product_save_items, sales_uploads},
+    sales::{
+        credit_notes::quantities,
+        tax::{included_tax, kra_round, tax_rates},
+    },
+    stock_management::{
+        route_stock_movements::{StockError, record_movement},
+        transmit_stock_master::transmit_stock_master,
+        transmit_stock_movements::transmit_stock_movement,
+    },
+    types::{
+        salespayloadtype::AuthUser,
+        stock_management::{ItemDetail, StockItem},
+    },
+    vscu::device::Device,
+};
+
+/// Stock out type code for goods leaving with a sale
+const STOCK_OUT_SALE: &str = "11";
+
+/// Stock in type code for goods a customer brought back
+const STOCK_IN_RETURN: &str = "03";
+
+/// What a stored sale or credit note needs for its goods to move in stock
+#[derive(Debug, Clone)]
+pub struct SoldInvoice {
+    pub sale_id: i32,               // Stored row
+    pub invc_no: i64,
+    pub org_invc_no: i64,           // Sale a credit note refunds
+    pub sales_dt: String,           // yyyyMMdd
+    pub cust_tin: Option<String>,
+    pub cust_nm: Option<String>,
+    pub regr_nm: String,
+    pub regr_id: String,
+    /// (itemCd, qty) of every line
+    pub lines: Vec<(String, Decimal)>,
+}
+
+/// An automatic movement stored with its invoice, to send to the VSCU once
+/// the invoice's transaction has committed
+#[derive(Debug, Clone)]
+pub struct RecordedMovement {
+    pub movement_id: i32,
+    pub master_ids: Vec<i64>,
+}
+
+/// One composite item's part of a sale's automatic stock out, stored on the
+/// sale as `component_usage`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ComponentUse {
+    pub item_cd: String,        // Composite item sold
+    pub cpst_item_cd: String,   // Component taken out for it
+    #[serde(with = "rust_decimal::serde::float")]
+    pub qty: Decimal,           // Over all lines of the sale
+}
+
+/// Take the components of the composite items sold on `sale` out of stock
+/// with one automatic stock out movement, inside the sale's transaction so
+/// the sale and its stock out are stored together, and note on the sale what
+/// was taken. A shortfall is logged and leaves the components untouched; it
+/// never refuses the sale.
+pub async fn consume_components(
+    txn: &DatabaseTransaction,
+    user: &AuthUser,
+    sale: &SoldInvoice,
+) -> Result<Option<RecordedMovement>, sea_orm::DbErr> {
+    let what = format!("components of invoice #{}", sale.invc_no);
+    let mut usage = component_usage(txn, user, sale).await?;
+    if usage.is_empty() {
+        return Ok(None);
+    }
+
+    let remark = format!("Components of invoice #{}", sale.invc_no);
+    let Some(movement) = movement(txn, user, sale, totals(&usage), STOCK_OUT_SALE, remark).await? else {
+        return Ok(None);
+    };
+
+    // Components missing from item_master were left off the movement
+    let moved: BTreeSet<String> = movement.item_list.iter().map(|line| line.item_cd.clone()).collect();
+    let Some(recorded) = apply_movement(txn, user, movement, &what).await? else {
+        return Ok(None);
+    };
+    usage.retain(|part| moved.contains(&part.cpst_item_cd));
+
+    let usage = serde_json::to_value(&usage).map_err(|e| sea_orm::DbErr::Custom(e.to_string()))?;
+    sales_uploads::Entity::update_many()
+        .col_expr(sales_uploads::Column::ComponentUsage, Expr::value(usage))
+        .filter(sales_uploads::Column::Id.eq(sale.sale_id))
+        .exec(txn)
+        .await?;
+
+    Ok(Some(recorded))
+}
+
+/// Put back into stock, with one automatic stock in movement inside the
+/// credit note's transaction, the components the original sale's automatic
+/// stock out took for the composite items `note` refunds: the share of the
+/// sold quantity refunded, of what was taken then. Nothing comes back when
+/// that stock out was skipped; other items are left to the POS, which moved
+/// them itself.
+pub async fn return_stock(
+    txn: &DatabaseTransaction,
+    user: &AuthUser,
+    note: &SoldInvoice,
+) -> Result<Option<RecordedMovement>, sea_orm::DbErr> {
+    let what = format!("returns of credit note #{}", note.invc_no);
+    match returned_movement(txn, user, note).await? {
+        Some(movement) => apply_movement(txn, user, movement, &what).await,
+        None => Ok(None),
+    }
+}
+
+/// Send movements stored with committed invoices, and their stock master
+/// rows, to the VSCU. Failed sends are picked up by the retry worker.
+pub async fn transmit_movements(db: Arc<DatabaseConnection>, device: Device, movements: Vec<RecordedMovement>) {
+    for moved in movements {
+        transmit_stock_movement(db.as_ref(), &device, moved.movement_id).await;
+        transmit_stock_master(db.clone(), device.clone(), moved.master_ids).await;
+    }
+}
+
+/// Record `movement` with its stock master changes under a savepoint, so a
+/// refused movement is undone without aborting the invoice's transaction
+async fn apply_movement(
+    txn: &DatabaseTransaction,
+    user: &AuthUser,
+    movement: StockItem,
+    what: &str,
+) -> Result<Option<RecordedMovement>, sea_orm::DbErr> {
+    let savepoint = txn.begin().await?;
+
+    match record_movement(&savepoint, user, &movement).await {
+        Ok((saved, master_ids)) => {
+            savepoint.commit().await?;
+            info!("Stock of {} moved for {} items (sarNo {})", what, master_ids.len(), saved.sar_no);
+            Ok(Some(RecordedMovement { movement_id: saved.id, master_ids }))
+        }
+        Err(StockError::Invalid(msg)) => {
+            savepoint.rollback().await?;
+            warn!("Stock of {} not moved: {}", what, msg);
+            Ok(None)
+        }
+        Err(StockError::Db(e)) => Err(e),
+    }
+}
+
+/// Components the composite items sold on `sale` use today, per composite
+/// item; empty when it sold none
+async fn component_usage<C: ConnectionTrait>(
+    db: &C,
+    user: &AuthUser,
+    sale: &SoldInvoice,
+) -> Result<Vec<ComponentUse>, sea_orm::DbErr> {
+    let sold: Vec<&String> = sale.lines.iter().map(|(item_cd, _)| item_cd).collect();
+
+    let compositions = item_composition::Entity::find()
+        .filter(item_composition::Column::Tin.eq(&user.pin))
+        .filter(item_composition::Column::BhfId.eq(&user.branch_id))
+        .filter(item_composition::Column::ItemCd.is_in(sold))
+        .filter(item_composition::Column::UseYn.eq("Y"))
+        .all(db)
+        .await?;
+
+    // Component quantity used across all lines of the invoice
+    let mut used: BTreeMap<(String, String), Decimal> = BTreeMap::new();
+    for (item_cd, qty) in &sale.lines {
+        for part in compositions.iter().filter(|c| &c.item_cd == item_cd) {
+            *used.entry((item_cd.clone(), part.cpst_item_cd.clone())).or_default() += qty * part.cpst_qty;
+        }
+    }
+
+    Ok(used
+        .into_iter()
+        .map(|((item_cd, cpst_item_cd), qty)| ComponentUse { item_cd, cpst_item_cd, qty })
+        .collect())
+}
+
+/// Quantity of each component over all composite items
+fn totals(usage: &[ComponentUse]) -> BTreeMap<String, Decimal> {
+    let mut totals: BTreeMap<String, Decimal> = BTreeMap::new();
+    for part in usage {
+        *totals.entry(part.cpst_item_cd.clone()).or_default() += part.qty;
+    }
+    totals
+}
+
+/// Stock in movement for the components given back with a credit note, or
+/// None when the original sale's stock out took none for what it refunds
+async fn returned_movement<C: ConnectionTrait>(
+    db: &C,
+    user: &AuthUser,
+    note: &SoldInvoice,
+) -> Result<Option<StockItem>, sea_orm::DbErr> {
+    let Some(original) = sales_uploads::Entity::find()
+        .filter(sales_uploads::Column::ApiKey.eq(&user.api_key))
+        .filter(sales_uploads::Column::InvcNo.eq(note.org_invc_no))
+        .one(db)
+        .await?
+    else {
+        return Ok(None);
+    };
+
+    let usage: Vec<ComponentUse> = original
+        .component_usage
+        .clone()
+        .and_then(|usage| serde_json::from_value(usage).ok())
+        .unwrap_or_default();
+    let returned = returned_components(&usage, &quantities(&original), &note.lines);
+    if returned.is_empty() {
+        return Ok(None);
+    }
+
+    movement(db, user, note, returned, STOCK_IN_RETURN, format!("Returns of credit note #{}", note.invc_no)).await
+}
+
+/// Components coming back for the refunded `lines`: of what the sale took
+/// for each composite item, the share of its sold quantity refunded
+fn returned_components(
+    usage: &[ComponentUse],
+    sold: &HashMap<String, Decimal>,
+    lines: &[(String, Decimal)],
+) -> BTreeMap<String, Decimal> {
+    let mut returned: BTreeMap<String, Decimal> = BTreeMap::new();
+    for (item_cd, qty) in lines {
+        let Some(sold_qty) = sold.get(item_cd).filter(|q| !q.is_zero()) else {
+            continue;
+        };
+        let refunded = (*qty).min(*sold_qty);
+        for part in usage.iter().filter(|u| &u.item_cd == item_cd) {
+            *returned.entry(part.cpst_item_cd.clone()).or_default() += part.qty * refunded / sold_qty;
+        }
+    }
+    returned
+}
+
+/// Automatic movement of `quantities` (item code → qty) for an invoice,
+/// priced at the items' default price with the tax included in it, as on a
+/// sale. None when nothing is left to move.
+async fn movement<C: ConnectionTrait>(
+    db: &C,
+    user: &AuthUser,
+    sale: &SoldInvoice,
+    quantities: BTreeMap<String, Decimal>,
+    sar_ty_cd: &str,
+    remark: String,
+) -> Result<Option<StockItem>, sea_orm::DbErr> {
+    let rates = tax_rates(db).await.map_err(sea_orm::DbErr::Custom)?;
+
+    let items = product_save_items::Entity::find()
+        .filter(product_save_items::Column::Tin.eq(&user.pin))
+        .filter(product_save_items::Column::BhfId.eq(&user.branch_id))
+        .filter(product_save_items::Column::ItemCd.is_in(quantities.keys().cloned()))
+        .all(db)
+        .await?;
+
+    let mut item_list = Vec::new();
+    let mut tot_amt = Decimal::ZERO;
+    let mut tot_tax_amt = Decimal::ZERO;
+    for (item_cd, qty) in quantities {
+        let Some(item) = items.iter().find(|i| i.item_cd == item_cd) else {
+            warn!("Item {} of invoice #{} is not in item_master, skipped", item_cd, sale.invc_no);
+            continue;
+        };
+        let Some(rate) = rates.rate(&item.tax_ty_cd) else {
+            warn!("Item {} has unknown tax type {}, skipped", item_cd, item.tax_ty_cd);
+            continue;
+        };
+
+        let amount = kra_round(item.dft_prc * qty);
+        let tax_amt = included_tax(amount, rate);
+        tot_amt += amount;
+        tot_tax_amt += tax_amt;
+
+        let qty = qty.to_f64().unwrap_or_default();
+        let amount = amount.to_f64().unwrap_or_default();
+        item_list.push(ItemDetail {
+            item_seq: item_list.len() as u32 + 1,
+            item_cd: item.item_cd.clone(),
+            item_cls_cd: item.item_cls_cd.clone(),
+            item_nm: item.item_nm.clone(),
+            bcd: Some(item.bcd.clone()).filter(|b| !b.is_empty()),
+            pkg_unit_cd: item.pkg_unit_cd.clone(),
+            // item_master has no package quantity; one package per movement line
+            pkg: 1.0,
+            qty_unit_cd: item.qty_unit_cd.clone(),
+            qty,
+            item_expr_dt: None,
+            prc: item.dft_prc.to_f64().unwrap_or_default(),
+            sply_amt: amount,
+            tot_dc_amt: 0.0,
+            taxbl_amt: amount,
+            tax_ty_cd: item.tax_ty_cd.clone(),
+            tax_amt: tax_amt.to_f64().unwrap_or_default(),
+            tot_amt: amount,
+        });
+    }
+
+    if item_list.is_empty() {
+        return Ok(None);
+    }
+
+    let tot_amt = tot_amt.to_f64().unwrap_or_default();
+
+    Ok(Some(StockItem {
+        tin: None,
+        bhf_id: None,
+        org_sar_no: 0,
+        reg_ty_cd: "A".to_string(),
+        cust_tin: sale.cust_tin.clone(),
+        cust_nm: sale.cust_nm.clone(),
+        cust_bhf_id: None,
+        sar_ty_cd: sar_ty_cd.to_string(),
+        ocrn_dt: sale.sales_dt.clone(),
+        tot_item_cnt: item_list.len() as u32,
+        tot_taxbl_amt: tot_amt,
+        tot_tax_amt: tot_tax_amt.to_f64().unwrap_or_default(),
+        tot_amt,
+        remark: Some(remark),
+        regr_nm: sale.regr_nm.clone(),
+        regr_id: sale.regr_id.clone(),
+        modr_nm: sale.regr_nm.clone(),
+        modr_id: sale.regr_id.clone(),
+        item_list,
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sales::test_support::dec;
+
+    fn took(item_cd: &str, cpst_item_cd: &str, qty: &str) -> ComponentUse {
+        ComponentUse { item_cd: item_cd.to_string(), cpst_item_cd: cpst_item_cd.to_string(), qty: dec(qty) }
+    }
+
+    #[test]
+    fn returns_the_refunded_share_of_what_the_sale_took() {
+        let usage = [took("BREAD", "FLOUR", "6"), took("BREAD", "SALT", "1.5"), took("CAKE", "FLOUR", "2")];
+        let sold = HashMap::from([("BREAD".to_string(), dec("3")), ("CAKE".to_string(), dec("1"))]);
+
+        let returned = returned_components(&usage, &sold, &[("BREAD".to_string(), dec("1"))]);
+
+        assert_eq!(returned, BTreeMap::from([("FLOUR".to_string(), dec("2")), ("SALT".to_string(), dec("0.5"))]));
+    }
+
+    #[test]
+    fn returns_nothing_the_sale_did_not_take() {
+        let usage = [took("BREAD", "FLOUR", "6")];
+        let sold = HashMap::from([("BREAD".to_string(), dec("3")), ("MILK".to_string(), dec("1"))]);
+
+        assert!(returned_components(&usage, &sold, &[("MILK".to_string(), dec("1"))]).is_empty());
+        assert!(returned_components(&[], &sold, &[("BREAD".to_string(), dec("3"))]).is_empty());
+    }
+}
@@ -1,2 +1,6 @@
 pub mod route_stock_master;
-
+pub mod route_stock_movements;
+pub mod transmit_stock_master;
+pub mod transmit_stock_movements;
+pub mod consume_components;
+pub mod sar_numbers;
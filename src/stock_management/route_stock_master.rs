@@ -12,12 +12,15 @@ use rust_decimal::Decimal;
 use sea_orm::{ActiveModelTrait, ActiveValue::{self, Set}, DatabaseConnection, EntityTrait, TransactionTrait};
 
 use serde_json::{json};
-use tracing::info;
+use tracing::{info, error};
 use std::sync::Arc;
 
 use crate::{
     models::stock_master::ActiveModel,
+    sales::status,
+    stock_management::transmit_stock_master::transmit_stock_master,
     types::{salespayloadtype::AuthUser, stock_management::{StockMasterItem, StockMstSaveReq}}, utils::bearer::bearer_resolver,
+    vscu::device::Device,
 };
 
 // ── Router ─────────────────────────────────────────────────────────────────────
@@ -86,23 +89,39 @@ async fn create_stock_items(
             regr_id: ActiveValue::Set(item.regr_id.clone()),
             modr_nm: ActiveValue::Set(item.modr_nm.clone()),
             modr_id: ActiveValue::Set(item.modr_id.clone()),
+            status: Set(status::RECEIVED.to_string()),
             ..Default::default()
         };
 
-        if let Err(e) = active.insert(&mut txn).await {
-            let _ = txn.rollback().await;
-            return error_response(&format!("Insert failed for {}: {e}", item.item_cd), StatusCode::INTERNAL_SERVER_ERROR);
+        match active.insert(&mut txn).await {
+            Ok(inserted) => inserted_ids.push(inserted.id),
+            Err(e) => {
+                let _ = txn.rollback().await;
+                return error_response(&format!("Insert failed for {}: {e}", item.item_cd), StatusCode::INTERNAL_SERVER_ERROR);
+            }
         }
     }
 
     // Commit transaction
-    match txn.commit().await {
-        Ok(_) => (
-            StatusCode::OK,
-            Json(json!({"resultCd": "000", "resultMsg": "All items inserted successfully"})),
-        ),
-        Err(e) => error_response(&format!("Transaction commit failed: {e}"), StatusCode::INTERNAL_SERVER_ERROR),
+    if let Err(e) = txn.commit().await {
+        return error_response(&format!("Transaction commit failed: {e}"), StatusCode::INTERNAL_SERVER_ERROR);
     }
+
+    // Send to KRA in the background, failures are picked up by the retry worker
+    match Device::from_user(&user) {
+        Ok(device) => {
+            let db_clone = db.clone();
+            tokio::spawn(async move {
+                transmit_stock_master(db_clone, device, inserted_ids).await;
+            });
+        }
+        Err(e) => error!("Stock master saved but not transmitted: {}", e),
+    }
+
+    (
+        StatusCode::OK,
+        Json(json!({"resultCd": "000", "resultMsg": "All items inserted successfully"})),
+    )
 }
 
 
@@ -0,0 +1,364 @@
+use std::{collections::BTreeSet, sync::Arc};
+
+use axum::{Json, Router, extract::State, http::StatusCode, response::IntoResponse, routing::post};
+use axum_extra::TypedHeader;
+use chrono::Utc;
+use headers::{Authorization, authorization::Bearer};
+use rust_decimal::Decimal;
+use sea_orm::{
+    ActiveModelTrait, ActiveValue::Set, ColumnTrait, DatabaseConnection, DatabaseTransaction,
+    EntityTrait, QueryFilter, QueryOrder, QuerySelect, TransactionTrait,
+};
+use serde_json::json;
+use tracing::info;
+
+use crate::{
+    models::{stock_master, stock_movement, stock_movement_item},
+    sales::status,
+    stock_management::{
+        route_stock_master::error_response,
+        sar_numbers,
+        transmit_stock_master::transmit_stock_master,
+        transmit_stock_movements::transmit_stock_movement,
+    },
+    types::{salespayloadtype::AuthUser, stock_management::{SaveStockItemsReq, StockItem}},
+    utils::bearer::bearer_resolver,
+    vscu::device::Device,
+};
+
+pub fn movements_router(db: Arc<DatabaseConnection>) -> Router {
+    Router::new()
+        .route("/", post(create_movements).get(list_movements))
+        .with_state(db)
+}
+
+/// Stock In/Out type codes `01`..`06` add stock, `11`..`16` remove it
+pub fn is_stock_in(sar_ty_cd: &str) -> bool {
+    sar_ty_cd.starts_with('0')
+}
+
+/// Store stock in/out movements, apply them to `stock_master.rsd_qty` and
+/// send both to the VSCU (`stock/saveStockItems` then `stockMaster/saveStockMaster`)
+async fn create_movements(
+    TypedHeader(auth): TypedHeader<Authorization<Bearer>>,
+    State(db): State<Arc<DatabaseConnection>>,
+    Json(payload): Json<SaveStockItemsReq>,
+) -> impl IntoResponse {
+    let token = auth.token();
+
+    // 1️⃣ AUTH FIRST
+    let user: AuthUser = match bearer_resolver(token, db.as_ref()).await {
+        Ok(val) => match serde_json::from_value(val) {
+            Ok(u) => u,
+            Err(e) => {
+                return (
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    Json(json!({ "message": format!("Failed to parse user: {}", e) })),
+                )
+            }
+        },
+        Err(e) => {
+            return (
+                StatusCode::UNAUTHORIZED,
+                Json(json!({ "message": e })),
+            )
+        }
+    };
+
+    let device = match Device::from_user(&user) {
+        Ok(d) => d,
+        Err(e) => return error_response(&e, StatusCode::INTERNAL_SERVER_ERROR),
+    };
+
+    let movements: Vec<StockItem> = payload.0;
+
+    if movements.is_empty() {
+        return error_response("No movements provided", StatusCode::BAD_REQUEST);
+    }
+
+    for movement in &movements {
+        if let Err(msg) = validate_movement(movement, &device) {
+            return error_response(&msg, StatusCode::BAD_REQUEST);
+        }
+    }
+
+    // 2️⃣ STORE MOVEMENTS AND APPLY THEM TO THE STOCK MASTER
+    let txn = match db.begin().await {
+        Ok(t) => t,
+        Err(e) => return error_response(&format!("Failed to start transaction: {e}"), StatusCode::INTERNAL_SERVER_ERROR),
+    };
+
+    let mut movement_ids = Vec::new();
+    let mut sar_nos = Vec::new();
+    let mut master_ids = BTreeSet::new();
+
+    for movement in &movements {
+        let (saved, touched) = match record_movement(&txn, &user, movement).await {
+            Ok(recorded) => recorded,
+            Err(StockError::Invalid(msg)) => {
+                let _ = txn.rollback().await;
+                return error_response(&msg, StatusCode::BAD_REQUEST);
+            }
+            Err(StockError::Db(e)) => {
+                let _ = txn.rollback().await;
+                return error_response(&format!("Failed to store movement: {e}"), StatusCode::INTERNAL_SERVER_ERROR);
+            }
+        };
+
+        master_ids.extend(touched);
+        movement_ids.push(saved.id);
+        sar_nos.push(saved.sar_no);
+    }
+
+    if let Err(e) = txn.commit().await {
+        return error_response(&format!("Transaction commit failed: {e}"), StatusCode::INTERNAL_SERVER_ERROR);
+    }
+
+    info!("Stored {} stock movements (sarNo {:?})", movement_ids.len(), sar_nos);
+
+    // 3️⃣ SEND TO KRA IN THE BACKGROUND, movements first so the VSCU knows
+    // why the remaining quantities changed
+    let db_clone = db.clone();
+    tokio::spawn(async move {
+        for id in movement_ids {
+            transmit_stock_movement(db_clone.as_ref(), &device, id).await;
+        }
+        transmit_stock_master(db_clone, device, master_ids.into_iter().collect()).await;
+    });
+
+    (
+        StatusCode::OK,
+        Json(json!({
+            "resultCd": "000",
+            "resultMsg": "Stock movements saved and queued for KRA",
+            "sarNo": sar_nos,
+        })),
+    )
+}
+
+async fn list_movements(
+    TypedHeader(auth): TypedHeader<Authorization<Bearer>>,
+    State(db): State<Arc<DatabaseConnection>>,
+) -> impl IntoResponse {
+    let user: AuthUser = match bearer_resolver(auth.token(), db.as_ref()).await {
+        Ok(val) => match serde_json::from_value(val) {
+            Ok(u) => u,
+            Err(e) => return error_response(&format!("Failed to parse user: {e}"), StatusCode::INTERNAL_SERVER_ERROR),
+        },
+        Err(e) => return error_response(&e, StatusCode::UNAUTHORIZED),
+    };
+
+    let result = stock_movement::Entity::find()
+        .filter(stock_movement::Column::Tin.eq(&user.pin))
+        .filter(stock_movement::Column::BhfId.eq(&user.branch_id))
+        .order_by_desc(stock_movement::Column::SarNo)
+        .find_with_related(stock_movement_item::Entity)
+        .all(db.as_ref())
+        .await;
+
+    match result {
+        Ok(rows) => {
+            let data: Vec<_> = rows
+                .into_iter()
+                .map(|(movement, items)| json!({ "movement": movement, "itemList": items }))
+                .collect();
+
+            (
+                StatusCode::OK,
+                Json(json!({
+                    "resultCd": "000",
+                    "resultMsg": "Success",
+                    "data": data,
+                })),
+            )
+        }
+        Err(e) => error_response(&format!("Failed to fetch movements: {e}"), StatusCode::INTERNAL_SERVER_ERROR),
+    }
+}
+
+fn validate_movement(movement: &StockItem, device: &Device) -> Result<(), String> {
+    if movement.tin.as_deref().is_some_and(|tin| tin != device.tin)
+        || movement.bhf_id.as_deref().is_some_and(|bhf| bhf != device.bhf_id)
+    {
+        return Err("tin/bhfId do not match the authenticated device".to_string());
+    }
+
+    if movement.item_list.is_empty() {
+        return Err("A movement needs at least one item".to_string());
+    }
+
+    if movement.tot_item_cnt as usize != movement.item_list.len() {
+        return Err(format!(
+            "totItemCnt is {} but itemList has {} items",
+            movement.tot_item_cnt,
+            movement.item_list.len()
+        ));
+    }
+
+    if let Some(line) = movement.item_list.iter().find(|l| l.qty <= 0.0) {
+        return Err(format!("Quantity for {} must be positive", line.item_cd));
+    }
+
+    Ok(())
+}
+
+/// Store one movement and apply its lines to the stock master inside the
+/// caller's transaction. Returns the movement and the stock_master ids it
+/// touched, which still have to be transmitted after commit.
+pub async fn record_movement(
+    txn: &DatabaseTransaction,
+    user: &AuthUser,
+    movement: &StockItem,
+) -> Result<(stock_movement::Model, Vec<i64>), StockError> {
+    let saved = insert_movement(txn, user, movement).await?;
+
+    let mut master_ids = Vec::new();
+    for line in &movement.item_list {
+        master_ids.push(apply_to_stock_master(txn, user, movement, &line.item_cd, line.qty).await?);
+    }
+
+    Ok((saved, master_ids))
+}
+
+/// Insert header + lines with the next sar_no of the branch. The branch's
+/// sarNo counter stays locked until the caller's transaction ends.
+async fn insert_movement(
+    txn: &DatabaseTransaction,
+    user: &AuthUser,
+    movement: &StockItem,
+) -> Result<stock_movement::Model, sea_orm::DbErr> {
+    let sar_no = sar_numbers::allocate(txn, &user.pin, &user.branch_id).await?;
+
+    let header = stock_movement::ActiveModel {
+        tin: Set(user.pin.clone()),
+        bhf_id: Set(user.branch_id.clone()),
+        sar_no: Set(sar_no),
+        org_sar_no: Set(movement.org_sar_no as i64),
+        reg_ty_cd: Set(movement.reg_ty_cd.clone()),
+        cust_tin: Set(movement.cust_tin.clone()),
+        cust_nm: Set(movement.cust_nm.clone()),
+        cust_bhf_id: Set(movement.cust_bhf_id.clone()),
+        sar_ty_cd: Set(movement.sar_ty_cd.clone()),
+        ocrn_dt: Set(movement.ocrn_dt.clone()),
+        tot_item_cnt: Set(movement.tot_item_cnt as i32),
+        tot_taxbl_amt: Set(movement.tot_taxbl_amt),
+        tot_tax_amt: Set(movement.tot_tax_amt),
+        tot_amt: Set(movement.tot_amt),
+        remark: Set(movement.remark.clone()),
+        regr_nm: Set(movement.regr_nm.clone()),
+        regr_id: Set(movement.regr_id.clone()),
+        modr_nm: Set(movement.modr_nm.clone()),
+        modr_id: Set(movement.modr_id.clone()),
+        status: Set(status::RECEIVED.to_string()),
+        ..Default::default()
+    }
+    .insert(txn)
+    .await?;
+
+    for line in &movement.item_list {
+        stock_movement_item::ActiveModel {
+            movement_id: Set(header.id),
+            item_seq: Set(line.item_seq as i32),
+            item_cd: Set(line.item_cd.clone()),
+            item_cls_cd: Set(line.item_cls_cd.clone()),
+            item_nm: Set(line.item_nm.clone()),
+            bcd: Set(line.bcd.clone()),
+            pkg_unit_cd: Set(line.pkg_unit_cd.clone()),
+            pkg: Set(line.pkg),
+            qty_unit_cd: Set(line.qty_unit_cd.clone()),
+            qty: Set(line.qty),
+            item_expr_dt: Set(line.item_expr_dt.clone()),
+            prc: Set(line.prc),
+            sply_amt: Set(line.sply_amt),
+            tot_dc_amt: Set(line.tot_dc_amt),
+            taxbl_amt: Set(line.taxbl_amt),
+            tax_ty_cd: Set(line.tax_ty_cd.clone()),
+            tax_amt: Set(line.tax_amt),
+            tot_amt: Set(line.tot_amt),
+            ..Default::default()
+        }
+        .insert(txn)
+        .await?;
+    }
+
+    Ok(header)
+}
+
+pub enum StockError {
+    Invalid(String),
+    Db(sea_orm::DbErr),
+}
+
+impl From<sea_orm::DbErr> for StockError {
+    fn from(e: sea_orm::DbErr) -> Self {
+        StockError::Db(e)
+    }
+}
+
+/// Move the item's remaining quantity and queue the stock master row for
+/// transmission again. Returns the stock_master id.
+///
+/// The row stays locked (`SELECT ... FOR UPDATE`) until the caller commits,
+/// so concurrent movements, component stock-outs, credit note returns and
+/// purchase stock-ins add up instead of overwriting each other, and the
+/// non-negative check sees the latest quantity. A branch's first row of an
+/// item is created under the sarNo counter lock taken by `insert_movement`.
+async fn apply_to_stock_master(
+    txn: &DatabaseTransaction,
+    user: &AuthUser,
+    movement: &StockItem,
+    item_cd: &str,
+    qty: f64,
+) -> Result<i64, StockError> {
+    let qty = Decimal::try_from(qty)
+        .map_err(|e| StockError::Invalid(format!("Invalid quantity for {item_cd}: {e}")))?;
+    let delta = if is_stock_in(&movement.sar_ty_cd) { qty } else { -qty };
+
+    let current = stock_master::Entity::find()
+        .filter(stock_master::Column::Tin.eq(&user.pin))
+        .filter(stock_master::Column::BhfId.eq(&user.branch_id))
+        .filter(stock_master::Column::ItemCd.eq(item_cd))
+        .order_by_desc(stock_master::Column::Id)
+        .lock_exclusive()
+        .one(txn)
+        .await?;
+
+    let rsd_qty = current.as_ref().map(|m| m.rsd_qty).unwrap_or_default() + delta;
+    if rsd_qty.is_sign_negative() && !rsd_qty.is_zero() {
+        return Err(StockError::Invalid(format!(
+            "Insufficient stock for {item_cd}: remaining quantity would be {rsd_qty}"
+        )));
+    }
+
+    let saved = match current {
+        Some(record) => {
+            let mut model: stock_master::ActiveModel = record.into();
+            model.rsd_qty = Set(rsd_qty);
+            model.modr_nm = Set(movement.modr_nm.clone());
+            model.modr_id = Set(movement.modr_id.clone());
+            model.status = Set(status::RECEIVED.to_string());
+            model.retry_count = Set(0);
+            model.next_retry_at = Set(None);
+            model.updated_at = Set(Some(Utc::now().naive_utc()));
+            model.update(txn).await?
+        }
+        None => {
+            stock_master::ActiveModel {
+                tin: Set(Some(user.pin.clone())),
+                bhf_id: Set(Some(user.branch_id.clone())),
+                item_cd: Set(item_cd.to_string()),
+                rsd_qty: Set(rsd_qty),
+                regr_nm: Set(movement.regr_nm.clone()),
+                regr_id: Set(movement.regr_id.clone()),
+                modr_nm: Set(movement.modr_nm.clone()),
+                modr_id: Set(movement.modr_id.clone()),
+                status: Set(status::RECEIVED.to_string()),
+                ..Default::default()
+            }
+            .insert(txn)
+            .await?
+        }
+    };
+
+    Ok(saved.id)
+}
@@ -0,0 +1,17 @@
+use sea_orm::{ConnectionTrait, DbErr};
+
+use crate::utils::counters::Counter;
+
+/// Last stock movement number (sarNo) handed out per branch
+pub const SAR_NUMBERS: Counter = Counter {
+    table: "sar_counters",
+    keys: &["tin", "bhf_id"],
+    number: "last_sar_no",
+};
+
+/// Reserve the next sarNo of branch (`tin`, `bhf_id`) in the transaction that
+/// inserts the movement, so concurrent movements of the branch don't collide
+/// on `uq_stock_movement_branch_sar_no`
+pub async fn allocate<C: ConnectionTrait>(txn: &C, tin: &str, bhf_id: &str) -> Result<i64, DbErr> {
+    SAR_NUMBERS.allocate(txn, &[tin.into(), bhf_id.into()], 1).await
+}
@@ -0,0 +1,172 @@
+use std::sync::Arc;
+
+use chrono::NaiveDateTime;
+use rust_decimal::prelude::ToPrimitive;
+use sea_orm::{ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter, UpdateMany, sea_query::Expr};
+use serde_json::{Value, json};
+use tracing::{info, error, warn};
+
+use crate::{
+    models::stock_master::{Column, Entity, Model},
+    sales::status,
+    utils::retry::{MAX_RETRIES, claim, is_due, next_retry_at, retryable, still_claimed},
+    vscu::{device::Device, result::{ResultClass, VscuResponse}},
+};
+
+/// Send freshly stored stock master rows to `stockMaster/saveStockMaster`
+pub async fn transmit_stock_master(db: Arc<DatabaseConnection>, device: Device, ids: Vec<i64>) {
+    for id in ids {
+        let record = match Entity::find_by_id(id).one(db.as_ref()).await {
+            Ok(Some(r)) => r,
+            Ok(None) => {
+                error!("Stock master {} not found after insert", id);
+                continue;
+            }
+            Err(e) => {
+                error!("Failed to fetch stock master {}: {}", id, e);
+                continue;
+            }
+        };
+
+        send_and_record(db.as_ref(), &device, record).await;
+    }
+
+    info!("Completed stock master transmission");
+}
+
+/// Retry FAILED/stuck stock master rows whose backoff has elapsed
+pub async fn retry_failed_stock_master(db: &DatabaseConnection) -> Result<(), sea_orm::DbErr> {
+    let records = Entity::find()
+        .filter(retryable(&["updated_at"]))
+        .filter(Column::RetryCount.lt(MAX_RETRIES))
+        .all(db)
+        .await?;
+
+    for record in records {
+        if !is_due(record.next_retry_at.as_deref()) {
+            continue;
+        }
+
+        let (Some(tin), Some(bhf_id)) = (record.tin.clone(), record.bhf_id.clone()) else {
+            continue;
+        };
+
+        let device = match Device::find_by_identity(db, &tin, &bhf_id).await {
+            Ok(d) => d,
+            Err(e) => {
+                error!("Cannot retry stock master {}: {}", record.id, e);
+                continue;
+            }
+        };
+
+        info!("🔄 Retrying stock master {} (attempt {}/{})", record.id, record.retry_count + 1, MAX_RETRIES);
+        send_and_record(db, &device, record).await;
+    }
+
+    Ok(())
+}
+
+async fn send_and_record(db: &DatabaseConnection, device: &Device, record: Model) {
+    let id = record.id;
+    let retry_count = record.retry_count;
+
+    let record = match claim::<Entity, _>(db, id, &[status::RECEIVED, status::FAILED]).await {
+        Ok(Some(r)) => r,
+        Ok(None) => {
+            info!("Stock master {} is already being sent, skipped", id);
+            return;
+        }
+        Err(e) => {
+            error!("Failed to lock stock master {}: {}", id, e);
+            return;
+        }
+    };
+
+    let kra_payload = json!({
+        "tin": device.tin,
+        "bhfId": device.bhf_id,
+        "itemCd": record.item_cd,
+        "rsdQty": record.rsd_qty.to_f64(),
+        "regrNm": record.regr_nm,
+        "regrId": record.regr_id,
+        "modrNm": record.modr_nm,
+        "modrId": record.modr_id,
+    });
+
+    let claim = Claim { id, retry_count, claimed_at: record.claimed_at };
+    let outcome = match device.client.save_stock_master(&kra_payload).await {
+        Ok(res) => record_response(db, &claim, &res).await,
+        Err(e) => {
+            error!("Failed to transmit stock master {}: {}", id, e);
+            schedule_retry(db, &claim, None).await
+        }
+    };
+
+    if let Err(e) = outcome {
+        error!("Failed to update stock master {}: {}", id, e);
+    }
+}
+
+/// The send this outcome belongs to
+struct Claim {
+    id: i64,
+    retry_count: i64,
+    claimed_at: Option<NaiveDateTime>,
+}
+
+/// Store the VSCU answer, unless the row moved on while it was being sent:
+/// a movement that changed `rsd_qty` meanwhile put it back to RECEIVED, and
+/// that quantity still has to go out
+async fn settle(db: &DatabaseConnection, claim: &Claim, update: UpdateMany<Entity>) -> Result<bool, sea_orm::DbErr> {
+    let res = update
+        .filter(Column::Id.eq(claim.id))
+        .filter(still_claimed(claim.claimed_at))
+        .exec(db)
+        .await?;
+
+    if res.rows_affected == 0 {
+        info!("Stock master {} changed while it was being sent, left for the next send", claim.id);
+    }
+    Ok(res.rows_affected > 0)
+}
+
+async fn record_response(db: &DatabaseConnection, claim: &Claim, res: &VscuResponse<Value>) -> Result<(), sea_orm::DbErr> {
+    let response = serde_json::to_value(res).ok();
+
+    let new_status = match res.class() {
+        ResultClass::Success => status::TRANSMITTED,
+        ResultClass::Rejected => {
+            warn!("Stock master {} rejected by KRA: {} {}", claim.id, res.result_cd, res.result_msg);
+            status::REJECTED
+        }
+        ResultClass::Retryable => return schedule_retry(db, claim, response).await,
+    };
+
+    let update = Entity::update_many()
+        .col_expr(Column::Status, Expr::value(new_status))
+        .col_expr(Column::Response, Expr::value(response))
+        .col_expr(Column::NextRetryAt, Expr::value(Option::<String>::None));
+
+    if settle(db, claim, update).await? {
+        info!("Stock master {} is {}", claim.id, new_status);
+    }
+    Ok(())
+}
+
+async fn schedule_retry(db: &DatabaseConnection, claim: &Claim, response: Option<Value>) -> Result<(), sea_orm::DbErr> {
+    let new_retry_count = claim.retry_count + 1;
+    let next = next_retry_at(new_retry_count);
+
+    let mut update = Entity::update_many()
+        .col_expr(Column::Status, Expr::value(status::FAILED))
+        .col_expr(Column::RetryCount, Expr::value(new_retry_count))
+        .col_expr(Column::NextRetryAt, Expr::value(Some(next.clone())));
+    if let Some(response) = response {
+        update = update.col_expr(Column::Response, Expr::value(response));
+    }
+
+    if settle(db, claim, update).await? {
+        info!("Stock master {} FAILED ({}/{}), next retry at {}", claim.id, new_retry_count, MAX_RETRIES, next);
+    }
+    Ok(())
+}
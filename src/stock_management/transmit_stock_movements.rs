@@ -0,0 +1,205 @@
+use sea_orm::{
+    ActiveModelTrait, ActiveValue::Set, ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter,
+    QueryOrder,
+};
+use serde_json::{Value, json};
+use tracing::{info, error, warn};
+
+use crate::{
+    models::{
+        stock_movement::{ActiveModel, Column, Entity, Model},
+        stock_movement_item,
+    },
+    sales::status,
+    utils::retry::{MAX_RETRIES, claim, is_due, next_retry_at, retryable},
+    vscu::{device::Device, result::{ResultClass, VscuResponse}},
+};
+
+/// Send a stored stock movement to `stock/saveStockItems`
+pub async fn transmit_stock_movement(db: &DatabaseConnection, device: &Device, id: i32) {
+    match Entity::find_by_id(id).one(db).await {
+        Ok(Some(record)) => send_and_record(db, device, record).await,
+        Ok(None) => error!("Stock movement {} not found after insert", id),
+        Err(e) => error!("Failed to fetch stock movement {}: {}", id, e),
+    }
+}
+
+/// Retry FAILED/stuck stock movements whose backoff has elapsed, and ones
+/// whose first send never happened
+pub async fn retry_failed_stock_movements(db: &DatabaseConnection) -> Result<(), sea_orm::DbErr> {
+    let records = Entity::find()
+        .filter(retryable(&["created_at"]))
+        .filter(Column::RetryCount.lt(MAX_RETRIES))
+        .order_by_asc(Column::SarNo)
+        .all(db)
+        .await?;
+
+    for record in records {
+        if !is_due(record.next_retry_at.as_deref()) {
+            continue;
+        }
+
+        let device = match Device::find_by_identity(db, &record.tin, &record.bhf_id).await {
+            Ok(d) => d,
+            Err(e) => {
+                error!("Cannot retry stock movement {}: {}", record.id, e);
+                continue;
+            }
+        };
+
+        info!("🔄 Retrying stock movement {} (attempt {}/{})", record.id, record.retry_count + 1, MAX_RETRIES);
+        send_and_record(db, &device, record).await;
+    }
+
+    Ok(())
+}
+
+async fn send_and_record(db: &DatabaseConnection, device: &Device, record: Model) {
+    let id = record.id;
+    let retry_count = record.retry_count;
+
+    let items = match stock_movement_item::Entity::find()
+        .filter(stock_movement_item::Column::MovementId.eq(id))
+        .order_by_asc(stock_movement_item::Column::ItemSeq)
+        .all(db)
+        .await
+    {
+        Ok(items) => items,
+        Err(e) => {
+            error!("Failed to load lines of stock movement {}: {}", id, e);
+            return;
+        }
+    };
+
+    let record = match claim::<Entity, _>(db, id, &[status::RECEIVED, status::FAILED]).await {
+        Ok(Some(r)) => r,
+        Ok(None) => {
+            info!("Stock movement {} is already being sent, skipped", id);
+            return;
+        }
+        Err(e) => {
+            error!("Failed to lock stock movement {}: {}", id, e);
+            return;
+        }
+    };
+
+    let kra_payload = build_payload(device, &record, &items);
+
+    let outcome = match device.client.save_stock_items(&kra_payload).await {
+        Ok(res) => record_response(db, id, retry_count, &res).await,
+        Err(e) => {
+            error!("Failed to transmit stock movement {}: {}", id, e);
+            schedule_retry(db, id, retry_count, None).await
+        }
+    };
+
+    if let Err(e) = outcome {
+        error!("Failed to update stock movement {}: {}", id, e);
+    }
+}
+
+fn build_payload(device: &Device, record: &Model, items: &[stock_movement_item::Model]) -> Value {
+    let item_list: Vec<Value> = items
+        .iter()
+        .map(|item| {
+            json!({
+                "itemSeq": item.item_seq,
+                "itemCd": item.item_cd,
+                "itemClsCd": item.item_cls_cd,
+                "itemNm": item.item_nm,
+                "bcd": item.bcd,
+                "pkgUnitCd": item.pkg_unit_cd,
+                "pkg": item.pkg,
+                "qtyUnitCd": item.qty_unit_cd,
+                "qty": item.qty,
+                "itemExprDt": item.item_expr_dt,
+                "prc": item.prc,
+                "splyAmt": item.sply_amt,
+                "totDcAmt": item.tot_dc_amt,
+                "taxblAmt": item.taxbl_amt,
+                "taxTyCd": item.tax_ty_cd,
+                "taxAmt": item.tax_amt,
+                "totAmt": item.tot_amt,
+            })
+        })
+        .collect();
+
+    json!({
+        "tin": device.tin,
+        "bhfId": device.bhf_id,
+        "sarNo": record.sar_no,
+        "orgSarNo": record.org_sar_no,
+        "regTyCd": record.reg_ty_cd,
+        "custTin": record.cust_tin,
+        "custNm": record.cust_nm,
+        "custBhfId": record.cust_bhf_id,
+        "sarTyCd": record.sar_ty_cd,
+        "ocrnDt": record.ocrn_dt,
+        "totItemCnt": record.tot_item_cnt,
+        "totTaxblAmt": record.tot_taxbl_amt,
+        "totTaxAmt": record.tot_tax_amt,
+        "totAmt": record.tot_amt,
+        "remark": record.remark,
+        "regrNm": record.regr_nm,
+        "regrId": record.regr_id,
+        "modrNm": record.modr_nm,
+        "modrId": record.modr_id,
+        "itemList": item_list,
+    })
+}
+
+async fn record_response(
+    db: &DatabaseConnection,
+    id: i32,
+    retry_count: i64,
+    res: &VscuResponse<Value>,
+) -> Result<(), sea_orm::DbErr> {
+    let response = serde_json::to_value(res).ok();
+
+    let new_status = match res.class() {
+        ResultClass::Success => status::TRANSMITTED,
+        ResultClass::Rejected => {
+            warn!("Stock movement {} rejected by KRA: {} {}", id, res.result_cd, res.result_msg);
+            status::REJECTED
+        }
+        ResultClass::Retryable => return schedule_retry(db, id, retry_count, response).await,
+    };
+
+    let mut model: ActiveModel = find(db, id).await?.into();
+    model.status = Set(new_status.to_string());
+    model.response = Set(response);
+    model.next_retry_at = Set(None);
+    model.update(db).await?;
+
+    info!("Stock movement {} is {}", id, new_status);
+    Ok(())
+}
+
+async fn schedule_retry(
+    db: &DatabaseConnection,
+    id: i32,
+    retry_count: i64,
+    response: Option<Value>,
+) -> Result<(), sea_orm::DbErr> {
+    let new_retry_count = retry_count + 1;
+    let next = next_retry_at(new_retry_count);
+
+    let mut model: ActiveModel = find(db, id).await?.into();
+    model.status = Set(status::FAILED.to_string());
+    model.retry_count = Set(new_retry_count);
+    model.next_retry_at = Set(Some(next.clone()));
+    if response.is_some() {
+        model.response = Set(response);
+    }
+    model.update(db).await?;
+
+    info!("Stock movement {} FAILED ({}/{}), next retry at {}", id, new_retry_count, MAX_RETRIES, next);
+    Ok(())
+}
+
+async fn find(db: &DatabaseConnection, id: i32) -> Result<Model, sea_orm::DbErr> {
+    Entity::find_by_id(id)
+        .one(db)
+        .await?
+        .ok_or(sea_orm::DbErr::RecordNotFound(format!("ID {}", id)))
+}
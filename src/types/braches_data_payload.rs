@@ -1,5 +1,7 @@
 use serde::{Serialize, Deserialize};
 
+use crate::vscu::result::VscuResponse;
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct BhfCustSaveReq {
     pub tin: String,           // Customer TIN, max 11 chars
@@ -53,9 +55,31 @@ pub struct BhfInsuranceSaveReq {
     pub modrNm: String,        // Modifier Name, max 60 chars
     pub modrId: String,        // Modifier ID, max 20 chars
 }
-#[derive(Debug, Serialize, Deserialize)]
-pub struct BhfInsuranceSaveRes {
-    pub resultCd: String,      // Result Code
-    pub resultMsg: String,     // Result Message
-    pub resultDt: String,      // Result Date/Time
+/// Answer of `branches/saveBrancheCustomers`, `saveBrancheUsers` and
+/// `saveBrancheInsurances` (result code only, no data)
+pub type BhfSaveRes = VscuResponse<serde_json::Value>;
+
+/// `data` of VSCU `branches/selectBranches`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BhfListData {
+    pub bhf_list: Vec<BhfInfo>,
+}
+
+/// A branch as KRA has it registered under the PIN
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BhfInfo {
+    pub tin: String,
+    pub bhf_id: String,
+    pub bhf_nm: String,
+    pub bhf_stts_cd: String,
+    pub prvnc_nm: Option<String>,
+    pub dstrt_nm: Option<String>,
+    pub sctr_nm: Option<String>,
+    pub loc_desc: Option<String>,
+    pub mgr_nm: Option<String>,
+    pub mgr_tel_no: Option<String>,
+    pub mgr_email: Option<String>,
+    pub hq_yn: String,
 }
@@ -0,0 +1,41 @@
+use serde::{Deserialize, Serialize};
+
+/// `data` of VSCU `code/selectCodes`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CodeListData {
+    pub cls_list: Vec<CodeClassItem>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CodeClassItem {
+    pub cd_cls: String,
+    pub cd_cls_nm: String,
+    pub cd_cls_desc: Option<String>,
+    pub use_yn: String,
+    pub user_dfn_nm1: Option<String>,
+    pub user_dfn_nm2: Option<String>,
+    pub user_dfn_nm3: Option<String>,
+    #[serde(default)]
+    pub dtl_list: Vec<CodeDetailItem>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CodeDetailItem {
+    pub cd: String,
+    pub cd_nm: String,
+    pub cd_desc: Option<String>,
+    pub use_yn: String,
+    pub srt_ord: Option<i32>,
+    pub user_dfn_cd1: Option<String>,
+    pub user_dfn_cd2: Option<String>,
+    pub user_dfn_cd3: Option<String>,
+}
+
+/// Query string of `GET /codes`
+#[derive(Debug, Clone, Deserialize)]
+pub struct CodeQuery {
+    pub cd_cls: Option<String>,
+}
@@ -0,0 +1,42 @@
+use serde::{Deserialize, Serialize};
+
+/// `data` of VSCU `customers/selectCustomer`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CustomerListData {
+    pub cust_list: Vec<CustomerInfo>,
+}
+
+/// A taxpayer as KRA has it registered
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CustomerInfo {
+    pub tin: String,
+    pub taxpr_nm: String,
+    pub taxpr_stts_cd: Option<String>,
+    pub prvnc_nm: Option<String>,
+    pub dstrt_nm: Option<String>,
+    pub sctr_nm: Option<String>,
+    pub loc_desc: Option<String>,
+}
+
+/// What to do with customer PINs KRA does not know
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PinPolicy {
+    /// Do not look the PINs up (default)
+    #[default]
+    Off,
+    /// Accept the request but report unknown PINs in the response
+    Flag,
+    /// Refuse the request with 422
+    Reject,
+}
+
+/// Query string accepted by `POST /sales` and `POST /branch/customers`
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PinCheckQuery {
+    #[serde(default)]
+    pub pin_check: PinPolicy,
+}
@@ -0,0 +1,59 @@
+use serde::{Deserialize, Serialize};
+
+/// `data` of VSCU `imports/selectImportItems`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportItemListData {
+    pub item_list: Vec<ImportItemInfo>,
+}
+
+/// A declared import line, as customs reports it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportItemInfo {
+    pub task_cd: String,
+    pub dcl_de: String,
+    pub item_seq: i32,
+    pub dcl_no: String,
+    pub hs_cd: String,
+    pub item_nm: String,
+    pub impt_item_stts_cd: String,
+    pub orgn_nat_cd: Option<String>,
+    pub expt_nat_cd: Option<String>,
+    pub pkg: f64,
+    pub pkg_unit_cd: Option<String>,
+    pub qty: f64,
+    pub qty_unit_cd: Option<String>,
+    pub tot_wt: f64,
+    pub net_wt: f64,
+    pub spplr_nm: Option<String>,
+    pub agnt_nm: Option<String>,
+    pub invc_fcur_amt: f64,
+    pub invc_fcur_cd: Option<String>,
+    pub invc_fcur_excrt: f64,
+}
+
+/// Body of `POST /imports/{id}/approve`
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportApproveReq {
+    pub item_cd: String,
+    pub modr_nm: String,
+    pub modr_id: String,
+    pub remark: Option<String>,
+}
+
+/// Body of `POST /imports/{id}/reject`
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportRejectReq {
+    pub modr_nm: String,
+    pub modr_id: String,
+    pub remark: Option<String>,
+}
+
+/// Query string of `GET /imports`
+#[derive(Debug, Clone, Deserialize)]
+pub struct ImportQuery {
+    pub status: Option<String>,
+}
@@ -2,7 +2,7 @@ use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct VerificationInfo{
-tin: String,
-bhfId: String,
-lastReqDt: String,
+pub tin: String,
+pub bhfId: String,
+pub lastReqDt: String,
 } 
\ No newline at end of file
@@ -1,4 +1,7 @@
 use serde::{Deserialize, Serialize};
+
+use crate::vscu::result::VscuResponse;
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct InitializeData {
      pub companyId: String,
@@ -7,4 +10,33 @@ pub struct InitializeData {
     pub pin: String,
     pub branchId: String,
     pub deviceSerial: String
-}
\ No newline at end of file
+}
+
+/// Body of VSCU `initializer/selectInitInfo`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InitInfoReq {
+    pub tin: String,
+    pub bhf_id: String,
+    pub dvc_srl_no: String,
+}
+
+pub type InitInfoRes = VscuResponse<InitInfoData>;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InitInfoData {
+    pub info: InitInfo,
+}
+
+/// Device/taxpayer details the VSCU holds for an initialized device
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InitInfo {
+    pub tin: Option<String>,
+    pub taxpr_nm: Option<String>,
+    pub bhf_id: Option<String>,
+    pub bhf_nm: Option<String>,
+    pub dvc_id: Option<String>,
+    pub sdc_id: Option<String>,
+    pub mrc_no: Option<String>,
+}
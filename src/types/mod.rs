@@ -4,4 +4,9 @@ pub mod braches_data_payload;
 pub mod product_management_payload_types;
 pub mod info;
 pub mod signup;
-pub mod initializeTypes;
\ No newline at end of file
+pub mod initializeTypes;
+pub mod codes;
+pub mod purchases;
+pub mod imports;
+pub mod notices;
+pub mod customers;
@@ -0,0 +1,27 @@
+use serde::{Deserialize, Serialize};
+
+/// `data` of VSCU `notices/selectNotices`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NoticeListData {
+    pub notice_list: Vec<NoticeInfo>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NoticeInfo {
+    pub ntc_no: i64,
+    pub title: String,
+    pub cont: String,
+    pub dtl_url: Option<String>,
+    pub regr_nm: Option<String>,
+    pub reg_dt: String,             // yyyyMMddHHmmss
+}
+
+/// Query string of `GET /notices`
+#[derive(Debug, Clone, Deserialize)]
+pub struct NoticeQuery {
+    /// Only notices the dashboard user has not read yet
+    #[serde(default)]
+    pub unread: bool,
+}
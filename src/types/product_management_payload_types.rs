@@ -59,4 +59,105 @@ pub struct KraResponse {
     pub result_msg: String,
     pub result_dt: String,
     pub data: Option<serde_json::Value>,
-}
\ No newline at end of file
+}
+
+/// `data` of VSCU `itemClass/selectItemsClass`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ItemClassListData {
+    pub item_cls_list: Vec<ItemClassItem>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ItemClassItem {
+    pub item_cls_cd: String,
+    pub item_cls_nm: String,
+    pub item_cls_lvl: i32,
+    pub tax_ty_cd: Option<String>,
+    pub mjr_tg_yn: Option<String>,
+    pub use_yn: String,
+}
+
+/// Query string of `GET /product/classes`
+#[derive(Debug, Clone, Deserialize)]
+pub struct ItemClassQuery {
+    pub q: Option<String>,
+    pub lvl: Option<i32>,
+    pub limit: Option<u64>,
+}
+
+/// Body of `POST /product/items_select`; without `lastReqDt` the device's
+/// last sync point is used
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ItemSelectReq {
+    pub last_req_dt: Option<String>,
+}
+
+/// `data` of VSCU `items/selectItems`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ItemListData {
+    pub item_list: Vec<KraItem>,
+}
+
+/// Item as registered with KRA
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct KraItem {
+    pub item_cd: String,
+    pub item_cls_cd: String,
+    pub item_ty_cd: String,
+    pub item_nm: String,
+    pub item_std_nm: Option<String>,
+    pub orgn_nat_cd: String,
+    pub pkg_unit_cd: String,
+    pub qty_unit_cd: String,
+    pub tax_ty_cd: String,
+    pub btch_no: Option<String>,
+    pub bcd: Option<String>,
+    pub dft_prc: Decimal,
+    pub grp_prc_l1: Option<Decimal>,
+    pub grp_prc_l2: Option<Decimal>,
+    pub grp_prc_l3: Option<Decimal>,
+    pub grp_prc_l4: Option<Decimal>,
+    pub grp_prc_l5: Option<Decimal>,
+    pub add_info: Option<String>,
+    pub sfty_qty: Option<Decimal>,
+    pub isrc_aplcb_yn: String,
+    pub use_yn: String,
+}
+
+/// Body of `POST /product/composition`: components of one composite item.
+/// Listed components are added or have their quantity changed.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ItemCompositionReq {
+    pub item_cd: String,
+    pub regr_nm: String,
+    pub regr_id: String,
+    pub components: Vec<ItemComponent>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ItemComponent {
+    pub cpst_item_cd: String,
+    pub cpst_qty: Decimal,            // NUMBER(13,2)
+}
+
+/// Query string of `DELETE /product/composition`
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ItemComponentRef {
+    pub item_cd: String,
+    pub cpst_item_cd: String,
+}
+
+/// Query string of `GET /product/composition`
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ItemCompositionQuery {
+    pub item_cd: Option<String>,
+}
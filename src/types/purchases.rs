@@ -0,0 +1,106 @@
+use serde::{Deserialize, Serialize};
+
+/// `data` of VSCU `trnsPurchase/selectTrnsPurchaseSales`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PurchaseSalesListData {
+    pub sale_list: Vec<SupplierSale>,
+}
+
+/// A supplier's sale to this branch, as KRA reports it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SupplierSale {
+    pub spplr_tin: String,
+    pub spplr_nm: String,
+    pub spplr_bhf_id: String,
+    pub spplr_invc_no: i64,
+    pub rcpt_ty_cd: String,
+    pub pmt_ty_cd: Option<String>,
+    pub cfm_dt: String,            // yyyy-MM-dd HH:mm:ss
+    pub sales_dt: String,          // yyyyMMdd
+    pub stock_rls_dt: Option<String>,
+    pub tot_item_cnt: i32,
+    pub taxbl_amt_a: f64,
+    pub taxbl_amt_b: f64,
+    pub taxbl_amt_c: f64,
+    pub taxbl_amt_d: f64,
+    pub taxbl_amt_e: f64,
+    pub tax_rt_a: f64,
+    pub tax_rt_b: f64,
+    pub tax_rt_c: f64,
+    pub tax_rt_d: f64,
+    pub tax_rt_e: f64,
+    pub tax_amt_a: f64,
+    pub tax_amt_b: f64,
+    pub tax_amt_c: f64,
+    pub tax_amt_d: f64,
+    pub tax_amt_e: f64,
+    pub tot_taxbl_amt: f64,
+    pub tot_tax_amt: f64,
+    pub tot_amt: f64,
+    pub remark: Option<String>,
+    #[serde(default)]
+    pub item_list: Vec<SupplierSaleItem>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SupplierSaleItem {
+    pub item_seq: i32,
+    pub item_cd: Option<String>,
+    pub item_cls_cd: String,
+    pub item_nm: String,
+    pub bcd: Option<String>,
+    pub pkg_unit_cd: String,
+    pub pkg: f64,
+    pub qty_unit_cd: String,
+    pub qty: f64,
+    pub prc: f64,
+    pub sply_amt: f64,
+    pub dc_rt: f64,
+    pub dc_amt: f64,
+    pub tax_ty_cd: String,
+    pub taxbl_amt: f64,
+    pub tax_amt: f64,
+    pub tot_amt: f64,
+}
+
+/// Body of `POST /purchases/{id}/accept`
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PurchaseAcceptReq {
+    pub regr_nm: String,
+    pub regr_id: String,
+    pub pchs_ty_cd: Option<String>,   // defaults to N (normal)
+    pub pmt_ty_cd: Option<String>,    // defaults to the supplier's payment type
+    pub remark: Option<String>,
+    /// Our item code for supplier lines, by itemSeq
+    #[serde(default)]
+    pub item_map: Vec<PurchaseItemMap>,
+    /// Also record the goods as a stock-in movement (sarTyCd 02)
+    #[serde(default)]
+    pub create_stock_in: bool,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PurchaseItemMap {
+    pub item_seq: i32,
+    pub item_cd: String,
+}
+
+/// Body of `POST /purchases/{id}/reject`
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PurchaseRejectReq {
+    pub regr_nm: String,
+    pub regr_id: String,
+    pub remark: Option<String>,
+}
+
+/// Query string of `GET /purchases`
+#[derive(Debug, Clone, Deserialize)]
+pub struct PurchaseQuery {
+    pub status: Option<String>,
+}
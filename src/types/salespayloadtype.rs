@@ -1,3 +1,4 @@
+use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -32,27 +33,45 @@ pub next_retry_at:Option<String>,
 
     pub totItemCnt: i64,
 
-    pub taxblAmtA: f64,
-    pub taxblAmtB: f64,
-    pub taxblAmtC: f64,
-    pub taxblAmtD: f64,
-    pub taxblAmtE: f64,
-
-    pub taxRtA: f64,
-    pub taxRtB: f64,
-    pub taxRtC: f64,
-    pub taxRtD: f64,
-    pub taxRtE: f64,
-
-    pub taxAmtA: f64,
-    pub taxAmtB: f64,
-    pub taxAmtC: f64,
-    pub taxAmtD: f64,
-    pub taxAmtE: f64,
-
-    pub totTaxblAmt: f64,
-    pub totTaxAmt: f64,
-    pub totAmt: f64,
+    #[serde(with = "rust_decimal::serde::float")]
+    pub taxblAmtA: Decimal,
+    #[serde(with = "rust_decimal::serde::float")]
+    pub taxblAmtB: Decimal,
+    #[serde(with = "rust_decimal::serde::float")]
+    pub taxblAmtC: Decimal,
+    #[serde(with = "rust_decimal::serde::float")]
+    pub taxblAmtD: Decimal,
+    #[serde(with = "rust_decimal::serde::float")]
+    pub taxblAmtE: Decimal,
+
+    #[serde(with = "rust_decimal::serde::float")]
+    pub taxRtA: Decimal,
+    #[serde(with = "rust_decimal::serde::float")]
+    pub taxRtB: Decimal,
+    #[serde(with = "rust_decimal::serde::float")]
+    pub taxRtC: Decimal,
+    #[serde(with = "rust_decimal::serde::float")]
+    pub taxRtD: Decimal,
+    #[serde(with = "rust_decimal::serde::float")]
+    pub taxRtE: Decimal,
+
+    #[serde(with = "rust_decimal::serde::float")]
+    pub taxAmtA: Decimal,
+    #[serde(with = "rust_decimal::serde::float")]
+    pub taxAmtB: Decimal,
+    #[serde(with = "rust_decimal::serde::float")]
+    pub taxAmtC: Decimal,
+    #[serde(with = "rust_decimal::serde::float")]
+    pub taxAmtD: Decimal,
+    #[serde(with = "rust_decimal::serde::float")]
+    pub taxAmtE: Decimal,
+
+    #[serde(with = "rust_decimal::serde::float")]
+    pub totTaxblAmt: Decimal,
+    #[serde(with = "rust_decimal::serde::float")]
+    pub totTaxAmt: Decimal,
+    #[serde(with = "rust_decimal::serde::float")]
+    pub totAmt: Decimal,
 
     pub prchrAcptcYn: String,
     pub remark: Option<String>,
@@ -66,7 +85,7 @@ pub next_retry_at:Option<String>,
     pub itemList: Vec<TrnsSalesSaveWrItem>,
     pub response: Option<Vec<TrnsSalesSaveWrRes>>
 }
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ReceiptInfo {
     pub custTin: String,
     pub custMblNo: Option<String>,
@@ -77,7 +96,7 @@ pub struct ReceiptInfo {
     pub btmMsg: String,
     pub prchrAcptcYn: String,
 }
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TrnsSalesSaveWrItem {
     pub itemSeq: i64,
     pub itemCd: String,
@@ -86,26 +105,37 @@ pub struct TrnsSalesSaveWrItem {
     pub bcd: Option<String>,
 
     pub pkgUnitCd: String,
-    pub pkg: f64,
+    #[serde(with = "rust_decimal::serde::float")]
+    pub pkg: Decimal,
 
     pub qtyUnitCd: String,
-    pub qty: f64,
+    #[serde(with = "rust_decimal::serde::float")]
+    pub qty: Decimal,
 
-    pub prc: f64,
-    pub splyAmt: f64,
+    #[serde(with = "rust_decimal::serde::float")]
+    pub prc: Decimal,
+    #[serde(with = "rust_decimal::serde::float")]
+    pub splyAmt: Decimal,
 
-    pub dcRt: f64,
-    pub dcAmt: f64,
+    #[serde(with = "rust_decimal::serde::float")]
+    pub dcRt: Decimal,
+    #[serde(with = "rust_decimal::serde::float")]
+    pub dcAmt: Decimal,
 
     pub isrccCd: Option<String>,
     pub isrccNm: Option<String>,
-    pub isrcRt: Option<f64>,
-    pub isrcAmt: Option<f64>,
+    #[serde(default, with = "rust_decimal::serde::float_option")]
+    pub isrcRt: Option<Decimal>,
+    #[serde(default, with = "rust_decimal::serde::float_option")]
+    pub isrcAmt: Option<Decimal>,
 
     pub taxTyCd: String,
-    pub taxblAmt: f64,
-    pub taxAmt: f64,
-    pub totAmt: f64,
+    #[serde(with = "rust_decimal::serde::float")]
+    pub taxblAmt: Decimal,
+    #[serde(with = "rust_decimal::serde::float")]
+    pub taxAmt: Decimal,
+    #[serde(with = "rust_decimal::serde::float")]
+    pub totAmt: Decimal,
 }
 #[derive(Debug, Serialize, Deserialize)]
 pub struct TrnsSalesSaveWrRes {
@@ -120,6 +150,7 @@ pub struct TrnsSalesSaveResData {
     pub intrlData: String,
     pub rcptSign: String,
     pub totRcptNo: i64,
+    #[serde(alias = "vsdcRcptPbctDate")]
     pub VSCURcptPbctDate: String,
     pub sdcId: String,
     pub mrcNo: String,
@@ -130,7 +161,7 @@ pub struct TrnsSalesSaveResData {
 
 
 pub struct InvoicePayload(pub Vec<TrnsSalesSaveWrReq>);
-#[derive(Debug, Deserialize, Serialize)]
+#[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct AuthUser {
     pub api_key: String,
     pub branch_id: String,
@@ -149,4 +180,55 @@ pub struct KraResponse {
     // For now using generic Value
     #[serde(flatten)]
     pub data: serde_json::Value,
-}
\ No newline at end of file
+}
+/// Query string of `POST /sales`
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SalesQuery {
+    /// Compute tax amounts and totals from the lines instead of trusting the POS
+    #[serde(default)]
+    pub calculate_tax: bool,
+}
+
+/// Query string of `GET /sales`; every filter is optional
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SalesListQuery {
+    pub status: Option<String>,
+    pub sales_dt_from: Option<String>, // yyyyMMdd, inclusive
+    pub sales_dt_to: Option<String>,   // yyyyMMdd, inclusive
+    pub cust_tin: Option<String>,
+    pub rcpt_ty_cd: Option<String>,
+    pub invc_no_from: Option<i64>,
+    pub invc_no_to: Option<i64>,
+    /// `nextCursor` of the previous page
+    pub cursor: Option<i64>,
+    pub limit: Option<u64>,
+}
+
+/// Body of `POST /sales/{invc_no}/credit-note`. The credit note copies the
+/// customer, prices and taxes of the original sale; only what is given back
+/// and why comes from the POS.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CreditNoteReq {
+    /// POS number of the credit note itself, deduplicated like a sale's
+    pub trd_invc_no: Option<i32>,
+    pub rfd_rsn_cd: String,
+    /// yyyyMMddHHmmss, now when left out
+    pub rfd_dt: Option<String>,
+    pub remark: Option<String>,
+    pub regr_id: String,
+    pub regr_nm: String,
+    /// Lines to refund; everything still refundable when empty
+    #[serde(default)]
+    pub items: Vec<CreditNoteItem>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CreditNoteItem {
+    pub item_cd: String,
+    #[serde(with = "rust_decimal::serde::float")]
+    pub qty: Decimal,
+}
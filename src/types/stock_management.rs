@@ -31,11 +31,13 @@ pub struct StockMstSaveReq(pub Vec<StockMasterItem>);
 pub struct SaveStockItemsReq(pub Vec<StockItem>);
 
 #[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
 pub struct StockItem {
     // Header-level fields
     pub tin: Option<String>,        // Branch TIN (max 11)
     pub bhf_id: Option<String>,             // Branch ID
-    pub sar_no: u32,               // Stock In/Out Number
+    // sarNo is not accepted, it is allocated per branch when the movement is stored
+    #[serde(default)]
     pub org_sar_no: u32,           // Original Stock In/Out Number
     pub reg_ty_cd: String,         // Registration Type Code
     pub cust_tin: Option<String>,  // Customer TIN
@@ -58,6 +60,7 @@ pub struct StockItem {
 }
 
 #[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
 pub struct ItemDetail {
     pub item_seq: u32,             // Item Sequence
     pub item_cd: String,           // Item Code
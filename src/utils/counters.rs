@@ -0,0 +1,149 @@
+use sea_orm::{
+    ConnectionTrait, DbErr, Value,
+    sea_query::{Alias, Expr, OnConflict, Query, SelectStatement, UpdateStatement},
+};
+
+/// A table holding the last number handed out per key, e.g. invoice numbers
+/// per device. `keys` are its unique key columns and `number` the last number;
+/// both it and `updated_at` have database defaults.
+pub struct Counter {
+    pub table: &'static str,
+    pub keys: &'static [&'static str],
+    pub number: &'static str,
+}
+
+impl Counter {
+    /// Lock the counter of `key` (values of `keys`, in order) until the
+    /// surrounding transaction ends, creating it on first use, and return the
+    /// last number handed out.
+    ///
+    /// While it is held no other request can take numbers under the key, so
+    /// lookups made under it stay valid until commit.
+    pub async fn lock<C: ConnectionTrait>(&self, txn: &C, key: &[Value]) -> Result<i64, DbErr> {
+        self.ensure(txn, key).await?;
+
+        let mut select = Query::select();
+        select.column(Alias::new(self.number)).from(Alias::new(self.table)).lock_exclusive();
+        self.filter_select(&mut select, key);
+
+        self.last_number(txn.query_one(txn.get_database_backend().build(&select)).await?, key)
+    }
+
+    /// Reserve `count` consecutive numbers under `key` and return the first.
+    ///
+    /// Must run inside the transaction that stores what they number: the
+    /// counter row stays locked until it commits, so concurrent requests for
+    /// the same key wait their turn, and a rollback hands the numbers back
+    /// instead of leaving a gap.
+    pub async fn allocate<C: ConnectionTrait>(&self, txn: &C, key: &[Value], count: i64) -> Result<i64, DbErr> {
+        self.ensure(txn, key).await?;
+
+        let number = Alias::new(self.number);
+        let mut update = Query::update();
+        update
+            .table(Alias::new(self.table))
+            .value(number.clone(), Expr::col(number.clone()).add(count))
+            .value(Alias::new("updated_at"), Expr::current_timestamp())
+            .returning_col(number);
+        self.filter_update(&mut update, key);
+
+        let last = self.last_number(txn.query_one(txn.get_database_backend().build(&update)).await?, key)?;
+        Ok(last - count + 1)
+    }
+
+    /// Create the counter of `key` at 0 unless it exists
+    async fn ensure<C: ConnectionTrait>(&self, txn: &C, key: &[Value]) -> Result<(), DbErr> {
+        let columns = || self.keys.iter().map(|col| Alias::new(*col));
+
+        let insert = Query::insert()
+            .into_table(Alias::new(self.table))
+            .columns(columns())
+            .values(key.iter().cloned().map(Expr::value))
+            .map_err(|e| DbErr::Custom(e.to_string()))?
+            .on_conflict(OnConflict::columns(columns()).do_nothing().to_owned())
+            .to_owned();
+        txn.execute(txn.get_database_backend().build(&insert)).await?;
+        Ok(())
+    }
+
+    fn filter_select(&self, select: &mut SelectStatement, key: &[Value]) {
+        for (col, value) in self.keys.iter().zip(key) {
+            select.and_where(Expr::col(Alias::new(*col)).eq(value.clone()));
+        }
+    }
+
+    fn filter_update(&self, update: &mut UpdateStatement, key: &[Value]) {
+        for (col, value) in self.keys.iter().zip(key) {
+            update.and_where(Expr::col(Alias::new(*col)).eq(value.clone()));
+        }
+    }
+
+    fn last_number(&self, row: Option<sea_orm::QueryResult>, key: &[Value]) -> Result<i64, DbErr> {
+        row.ok_or_else(|| DbErr::RecordNotFound(format!("{} of {key:?}", self.table)))?
+            .try_get("", self.number)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeSet;
+
+    use chrono::Utc;
+    use sea_orm::{Database, DatabaseConnection, TransactionTrait, sea_query::Query};
+
+    use super::*;
+    use crate::{sales::invoice_numbers::INVOICES, stock_management::sar_numbers::SAR_NUMBERS};
+
+    /// 50 parallel allocations of 1-3 numbers under one key, some rolled back
+    async fn parallel_allocations(db: &DatabaseConnection, counter: &'static Counter, key: Vec<Value>) -> Vec<i64> {
+        let tasks: Vec<_> = (0..50)
+            .map(|i| {
+                let db = db.clone();
+                let key = key.clone();
+                tokio::spawn(async move {
+                    let count = i % 3 + 1;
+                    let txn = db.begin().await.unwrap();
+                    let first = counter.allocate(&txn, &key, count).await.unwrap();
+                    if i % 7 == 0 {
+                        txn.rollback().await.unwrap();
+                        return Vec::new();
+                    }
+                    txn.commit().await.unwrap();
+                    (first..first + count).collect::<Vec<_>>()
+                })
+            })
+            .collect();
+
+        let mut numbers = Vec::new();
+        for task in tasks {
+            numbers.extend(task.await.unwrap());
+        }
+
+        let mut delete = Query::delete();
+        delete.from_table(Alias::new(counter.table));
+        for (col, value) in counter.keys.iter().zip(&key) {
+            delete.and_where(Expr::col(Alias::new(*col)).eq(value.clone()));
+        }
+        db.execute(db.get_database_backend().build(&delete)).await.unwrap();
+
+        numbers
+    }
+
+    fn assert_no_gaps_or_duplicates(numbers: Vec<i64>) {
+        let unique: BTreeSet<i64> = numbers.iter().copied().collect();
+        assert_eq!(unique.len(), numbers.len(), "duplicate numbers");
+        assert_eq!(unique.into_iter().collect::<Vec<_>>(), (1..=numbers.len() as i64).collect::<Vec<_>>());
+    }
+
+    /// Run with `DATABASE_URL=... cargo test -- --ignored` against a migrated database
+    #[tokio::test(flavor = "multi_thread", worker_threads = 8)]
+    #[ignore = "needs DATABASE_URL of a migrated Postgres"]
+    async fn parallel_allocations_have_no_gaps_or_duplicates() {
+        dotenvy::dotenv().ok();
+        let db = Database::connect(std::env::var("DATABASE_URL").expect("DATABASE_URL")).await.unwrap();
+        let run = format!("counters-test-{}", Utc::now().timestamp_nanos_opt().unwrap());
+
+        assert_no_gaps_or_duplicates(parallel_allocations(&db, &INVOICES, vec![run.clone().into()]).await);
+        assert_no_gaps_or_duplicates(parallel_allocations(&db, &SAR_NUMBERS, vec![run.into(), "00".into()]).await);
+    }
+}
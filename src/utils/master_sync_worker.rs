@@ -0,0 +1,57 @@
+use std::sync::Arc;
+
+use sea_orm::DatabaseConnection;
+use tokio::time::{interval, Duration};
+use tracing::{info, error};
+
+use crate::{
+    branch_operations::sync_branches::sync_branches,
+    codes::sync_codes::sync_codes,
+    imports::sync_imports::sync_imports,
+    notices::sync_notices::sync_notices,
+    product_management::sync_item_classes::sync_item_classes,
+    purchases::sync_purchases::sync_purchases,
+    vscu::device::Device,
+};
+
+/// Periodically pulls KRA master data (code lists, item classes, branches, ...) and
+/// incoming supplier sales, customs imports and KRA notices for every device
+pub fn start_master_sync_worker(db: Arc<DatabaseConnection>) {
+    tokio::spawn(async move {
+        let mut ticker = interval(Duration::from_secs(60 * 60)); // Every hour
+
+        loop {
+            ticker.tick().await;
+            info!("🔄 Master data sync tick");
+
+            let devices = match Device::all(db.as_ref()).await {
+                Ok(d) => d,
+                Err(e) => {
+                    error!("❌ Failed to load devices for sync: {}", e);
+                    continue;
+                }
+            };
+
+            for device in devices {
+                if let Err(e) = sync_codes(db.as_ref(), &device).await {
+                    error!("❌ Code sync failed for device {}: {}", device.credentials_id, e);
+                }
+                if let Err(e) = sync_item_classes(db.as_ref(), &device).await {
+                    error!("❌ Item class sync failed for device {}: {}", device.credentials_id, e);
+                }
+                if let Err(e) = sync_branches(db.as_ref(), &device).await {
+                    error!("❌ Branch sync failed for device {}: {}", device.credentials_id, e);
+                }
+                if let Err(e) = sync_purchases(db.as_ref(), &device).await {
+                    error!("❌ Purchase sync failed for device {}: {}", device.credentials_id, e);
+                }
+                if let Err(e) = sync_imports(db.as_ref(), &device).await {
+                    error!("❌ Import sync failed for device {}: {}", device.credentials_id, e);
+                }
+                if let Err(e) = sync_notices(db.as_ref(), &device).await {
+                    error!("❌ Notice sync failed for device {}: {}", device.credentials_id, e);
+                }
+            }
+        }
+    });
+}
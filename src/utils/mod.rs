@@ -1,3 +1,7 @@
 pub mod crypto;
 pub mod bearer;
-pub mod polling_retry_worker;
\ No newline at end of file
+pub mod polling_retry_worker;
+pub mod sync_state;
+pub mod master_sync_worker;
+pub mod retry;
+pub mod counters;
@@ -3,12 +3,20 @@ use std::sync::Arc;
 use sea_orm::{DatabaseConnection, ColumnTrait, EntityTrait, QueryFilter, ActiveModelTrait};
 use tokio::time::{interval, Duration};
 use tracing::{info, error};
-use chrono::Utc;
-use serde_json::json;
 
 use crate::{
+    branch_operations::transmit_branches::retry_failed_branch_data,
+    imports::transmit_imports::retry_failed_imports,
     models::sales_uploads::{Entity, ActiveModel, Column},
-    utils::crypto::decrypt_deterministic,
+    product_management::transmit_item_composition::retry_failed_item_compositions,
+    purchases::transmit_purchases::retry_failed_purchases,
+    sales::{kra_payload::KraSalesPayload, status::{self, apply_kra_response}},
+    stock_management::{
+        transmit_stock_master::retry_failed_stock_master,
+        transmit_stock_movements::retry_failed_stock_movements,
+    },
+    utils::{crypto::{decrypt, decrypt_deterministic}, retry::{MAX_RETRIES, claim, is_due, next_retry_at, retryable}},
+    vscu::{client::VscuError, device::Device, result::ResultClass},
 };
 
 pub fn start_retry_worker(db: Arc<DatabaseConnection>) {
@@ -22,6 +30,30 @@ pub fn start_retry_worker(db: Arc<DatabaseConnection>) {
             if let Err(e) = retry_failed_transactions(db.as_ref()).await {
                 error!("❌ Retry worker error: {}", e);
             }
+
+            if let Err(e) = retry_failed_stock_movements(db.as_ref()).await {
+                error!("❌ Stock movement retry error: {}", e);
+            }
+
+            if let Err(e) = retry_failed_stock_master(db.as_ref()).await {
+                error!("❌ Stock master retry error: {}", e);
+            }
+
+            if let Err(e) = retry_failed_branch_data(db.as_ref()).await {
+                error!("❌ Branch data retry error: {}", e);
+            }
+
+            if let Err(e) = retry_failed_purchases(db.as_ref()).await {
+                error!("❌ Purchase retry error: {}", e);
+            }
+
+            if let Err(e) = retry_failed_imports(db.as_ref()).await {
+                error!("❌ Import retry error: {}", e);
+            }
+
+            if let Err(e) = retry_failed_item_compositions(db.as_ref()).await {
+                error!("❌ Item composition retry error: {}", e);
+            }
         }
     });
 }
@@ -29,15 +61,13 @@ pub fn start_retry_worker(db: Arc<DatabaseConnection>) {
 async fn retry_failed_transactions(
     db: &DatabaseConnection,
 ) -> Result<(), sea_orm::DbErr> {
-    use sea_orm::ActiveValue::Set;
-
     // Find all FAILED records that haven't exceeded retry limit
-    // and are ready for retry (next_retry_at is in the past or empty)
-    let now = Utc::now().to_rfc3339();
-    
+    // and are ready for retry (next_retry_at is in the past or empty).
+    // PROCESSING sales only once their sender's lease has run out, RECEIVED
+    // ones only once their own request had time to send them
     let failed_records = Entity::find()
-        .filter(Column::Status.is_in(["FAILED", "PROCESSING"]))
-        .filter(Column::RetryCount.lt(5)) // Max 5 retries
+        .filter(retryable(&["created_at"]))
+        .filter(Column::RetryCount.lt(MAX_RETRIES))
         .all(db)
         .await?;
 
@@ -45,44 +75,39 @@ async fn retry_failed_transactions(
 
     for record in failed_records {
         let id = record.id;
-        
-        // Check if it's time to retry (exponential backoff)
-      if let Some(next_retry_at) = &record.next_retry_at {
-    if next_retry_at > &now {
-        info!(
-            "⏰ Record {} not ready for retry yet (next: {})",
-            id, next_retry_at
-        );
-        continue;
-    }
-}
 
-
-        let retry_count = record.retry_count.unwrap_or(0);
-
-info!(
-    "🔄 Retrying record {} (attempt {}/5)",
-    id,
-    retry_count + 1
-);
-
-
-        // 1️⃣ Lock the record with PROCESSING status
-        if let Err(e) = update_status(db, id, "PROCESSING").await {
-            error!("Failed to lock record {} for processing: {}", id, e);
+        // Check if it's time to retry (exponential backoff)
+        if !is_due(record.next_retry_at.as_deref()) {
+            info!("⏰ Record {} not ready for retry yet (next: {:?})", id, record.next_retry_at);
             continue;
         }
 
+        let retry_count = record.retry_count.unwrap_or(0);
+        info!("🔄 Retrying record {} (attempt {}/{})", id, retry_count + 1, MAX_RETRIES);
+
+        // 1️⃣ Claim the record; whoever else sends it meanwhile keeps it
+        let record = match claim::<Entity, _>(db, id, &[status::FAILED, status::RECEIVED]).await {
+            Ok(Some(r)) => r,
+            Ok(None) => {
+                info!("Record {} was claimed by another sender, skipped", id);
+                continue;
+            }
+            Err(e) => {
+                error!("Failed to claim record {} for processing: {}", id, e);
+                continue;
+            }
+        };
+
         // 2️⃣ Attempt to resend to KRA
         match resend_to_kra(db, &record).await {
-            Ok(true) => {
-                // Success - mark as TRANSMITTED
+            Ok(ResultClass::Success) => {
                 info!("✅ Successfully transmitted record {}", id);
-                if let Err(e) = update_status(db, id, "TRANSMITTED").await {
-                    error!("Failed to update status to TRANSMITTED for {}: {}", id, e);
-                }
             }
-            Ok(false) => {
+            Ok(ResultClass::Rejected) => {
+                // KRA refused the invoice - retrying the same payload won't help
+                info!("⛔ Record {} rejected by KRA, not retrying", id);
+            }
+            Ok(ResultClass::Retryable) => {
                 // Failed - increment retry count with exponential backoff
                 info!("❌ Failed to transmit record {}, will retry later", id);
                 if let Err(e) = increment_retry(db, id, record.retry_count.unwrap_or(0)).await
@@ -103,144 +128,41 @@ info!(
     Ok(())
 }
 
-// Change this function signature
 async fn resend_to_kra(
     db: &DatabaseConnection,
     record: &crate::models::sales_uploads::Model,
-) -> Result<bool, String> {  // ✅ Changed from Box<dyn std::error::Error>
+) -> Result<ResultClass, String> {
     // Decrypt TIN and BHF_ID
     let decrypted_tin = decrypt_deterministic(&record.tin)
         .map_err(|e| format!("Decrypt TIN error: {}", e))?;
     
-    let decrypted_bhf_id = decrypt_deterministic(&record.bhf_id)
+    // branch_id is stored with the non-deterministic cipher
+    let decrypted_bhf_id = decrypt(&record.bhf_id)
         .map_err(|e| format!("Decrypt BHF_ID error: {}", e))?;
 
     // Build KRA payload
-    let kra_payload = json!({
-        "tin": decrypted_tin,
-        "bhfId": decrypted_bhf_id,
-        "trdInvcNo": record.trd_invc_no,
-        "invcNo": record.invc_no,
-        "orgInvcNo": record.org_invc_no,
-        "custTin": record.cust_tin,
-        "custNm": record.cust_nm,
-        "salesTyCd": record.sales_ty_cd,
-        "rcptTyCd": record.rcpt_ty_cd,
-        "pmtTyCd": record.pmt_ty_cd,
-        "salesSttsCd": record.sales_stts_cd,
-        "cfmDt": record.cfm_dt,
-        "salesDt": record.sales_dt,
-        "stockRlsDt": record.stock_rls_dt,
-        "cnclReqDt": record.cncl_req_dt,
-        "cnclDt": record.cncl_dt,
-        "rfdDt": record.rfd_dt,
-        "rfdRsnCd": record.rfd_rsn_cd,
-        "totItemCnt": record.tot_item_cnt,
-        "taxblAmtA": record.taxbl_amt_a,
-        "taxblAmtB": record.taxbl_amt_b,
-        "taxblAmtC": record.taxbl_amt_c,
-        "taxblAmtD": record.taxbl_amt_d,
-        "taxblAmtE": record.taxbl_amt_e,
-        "taxRtA": record.tax_rt_a,
-        "taxRtB": record.tax_rt_b,
-        "taxRtC": record.tax_rt_c,
-        "taxRtD": record.tax_rt_d,
-        "taxRtE": record.tax_rt_e,
-        "taxAmtA": record.tax_amt_a,
-        "taxAmtB": record.tax_amt_b,
-        "taxAmtC": record.tax_amt_c,
-        "taxAmtD": record.tax_amt_d,
-        "taxAmtE": record.tax_amt_e,
-        "totTaxblAmt": record.tot_taxbl_amt,
-        "totTaxAmt": record.tot_tax_amt,
-        "totAmt": record.tot_amt,
-        "prchrAcptcYn": record.prchr_acptc_yn,
-        "remark": record.remark,
-        "regrId": record.regr_id,
-        "regrNm": record.regr_nm,
-        "modrId": record.modr_id,
-        "modrNm": record.modr_nm,
-        "receipt": record.receipt,
-        "itemList": record.item_list,
-    });
+    let kra_payload = KraSalesPayload::new(record, decrypted_tin, decrypted_bhf_id);
 
     info!("📤 Sending retry payload to KRA for invoice #{}", record.invc_no);
 
-    // Send to KRA endpoint
-    let client = reqwest::Client::builder()
-        .timeout(Duration::from_secs(30))
-        .build()
-        .map_err(|e| format!("Client build error: {}", e))?;
-
-    let response = client
-        .post("http://192.168.1.71:8088/trnsSales/saveSales")
-        .json(&kra_payload)
-        .send()
-        .await
-        .map_err(|e| format!("Request send error: {}", e))?;
-
-    if !response.status().is_success() {
-        let status = response.status();
-        error!("KRA returned error status: {}", status);
-        return Ok(false);
-    }
+    // Send to the VSCU the invoice's device is registered against
+    let device = Device::find_by_api_key(db, &record.api_key).await?;
 
-    let kra_response = response.json::<serde_json::Value>()
-        .await
-        .map_err(|e| format!("JSON parse error: {}", e))?;
+    let kra_response = match device.client.save_sales(&kra_payload).await {
+        Ok(r) => r,
+        Err(VscuError::Status(status)) => {
+            error!("KRA returned error status: {}", status);
+            return Ok(ResultClass::Retryable);
+        }
+        Err(e) => return Err(e.to_string()),
+    };
     
     info!("📥 Received response from KRA for invoice #{}", record.invc_no);
 
-    // Save response to database
-    update_record_with_response(db, record.id, kra_response, "TRANSMITTED")
+    // Save response and let the result code drive the status
+    apply_kra_response(db, record.id, &kra_response)
         .await
-        .map_err(|e| format!("DB update error: {}", e))?;
-
-    Ok(true)
-}
-
-async fn update_status(
-    db: &DatabaseConnection,
-    id: i32,
-    new_status: &str,
-) -> Result<(), sea_orm::DbErr> {
-    use sea_orm::ActiveValue::Set;
-    
-    let record = Entity::find_by_id(id)
-        .one(db)
-        .await?
-        .ok_or(sea_orm::DbErr::RecordNotFound(format!("ID {}", id)))?;
-
-    let mut active_model: ActiveModel = record.into();
-    active_model.status = Set(new_status.to_string());
-    active_model.update(db).await?;
-
-    info!("✏️ Updated record {} status to {}", id, new_status);
-    Ok(())
-}
-
-async fn update_record_with_response(
-    db: &DatabaseConnection,
-    id: i32,
-    kra_response: serde_json::Value,
-    new_status: &str,
-) -> Result<(), sea_orm::DbErr> {
-    use sea_orm::ActiveValue::Set;
-    
-    let record = Entity::find_by_id(id)
-        .one(db)
-        .await?
-        .ok_or(sea_orm::DbErr::RecordNotFound(format!("ID {}", id)))?;
-
-    let mut active_model: ActiveModel = record.into();
-    active_model.status = Set(new_status.to_string());
-    active_model.response = Set(Some(kra_response));
-    active_model.next_retry_at = Set(None);
- // Clear retry timestamp
-    active_model.update(db).await?;
-
-    info!("✅ Updated record {} with KRA response and status {}", id, new_status);
-    Ok(())
+        .map_err(|e| format!("DB update error: {}", e))
 }
 
 async fn increment_retry(
@@ -249,7 +171,6 @@ async fn increment_retry(
     current_retry_count: i64,
 ) -> Result<(), sea_orm::DbErr> {
     use sea_orm::ActiveValue::Set;
-    use chrono::Duration as ChronoDuration;
 
     let record = Entity::find_by_id(id)
         .one(db)
@@ -257,31 +178,15 @@ async fn increment_retry(
         .ok_or(sea_orm::DbErr::RecordNotFound("not found".into()))?;
 
     let new_retry_count = current_retry_count + 1;
-    
-    // Exponential backoff: 2^retry_count minutes
-    // Retry 1: 2 min, Retry 2: 4 min, Retry 3: 8 min, Retry 4: 16 min, Retry 5: 32 min
-    let backoff_minutes = 2_i64.pow(new_retry_count as u32);
-    let next_retry = Utc::now() + ChronoDuration::minutes(backoff_minutes);
-
-
-
-    // Entity::update_many()
-    // .col_expr(Column::RetryCount, Expr::value(new_retry_count))
-    // .col_expr(Column::Status, Expr::value("FAILED"))
-    // .col_expr(Column::NextRetryAt, Expr::value(next_retry))
-    // .filter(Column::Id.eq(record.id))
-    // .exec(db)
-    // .await?;
-
+    let next = next_retry_at(new_retry_count);
 
     let mut model: ActiveModel = record.into();
     model.retry_count = Set(Some(new_retry_count));
-    model.status = Set("FAILED".to_string());
-    model.next_retry_at = Set(Some(next_retry.to_rfc3339()));
+    model.status = Set(status::FAILED.to_string());
+    model.next_retry_at = Set(Some(next.clone()));
     model.update(db).await?;
 
-    info!("📊 Record {} retry count: {}/5, next retry at: {}", 
-          id, new_retry_count, next_retry.format("%Y-%m-%d %H:%M:%S"));
+    info!("📊 Record {} retry count: {}/{}, next retry at: {}", id, new_retry_count, MAX_RETRIES, next);
 
     Ok(())
 }
\ No newline at end of file
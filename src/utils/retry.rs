@@ -0,0 +1,104 @@
+use chrono::{Duration as ChronoDuration, NaiveDateTime, Utc};
+use sea_orm::{
+    Condition, ConnectionTrait, DbErr, EntityTrait, QueryFilter, Value,
+    sea_query::{Alias, Expr, Func},
+};
+
+use crate::sales::status;
+
+/// Attempts after which a FAILED record is left for a human
+pub const MAX_RETRIES: i64 = 5;
+
+/// How long a PROCESSING row belongs to the sender that claimed it. Longer
+/// than the VSCU client's 30 s timeout, so a slow call is never sent twice.
+pub const LEASE_SECS: i64 = 90;
+
+/// How long a freshly stored row may stay RECEIVED before the retry worker
+/// sends it itself. The request that stored it normally sends it right away;
+/// one still RECEIVED after this lost its background send (e.g. a restart).
+pub const RECEIVED_GRACE_SECS: i64 = 120;
+
+/// Exponential backoff: 2^retry_count minutes from now (RFC 3339, like sales.next_retry_at)
+pub fn next_retry_at(retry_count: i64) -> String {
+    let backoff_minutes = 2_i64.pow(retry_count.clamp(0, 10) as u32);
+    (Utc::now() + ChronoDuration::minutes(backoff_minutes)).to_rfc3339()
+}
+
+/// Whether a record scheduled for `next_retry_at` may be retried now
+pub fn is_due(next_retry_at: Option<&str>) -> bool {
+    match next_retry_at {
+        Some(at) => at <= Utc::now().to_rfc3339().as_str(),
+        None => true,
+    }
+}
+
+/// Claims older than this have expired
+fn lease_cutoff() -> NaiveDateTime {
+    (Utc::now() - ChronoDuration::seconds(LEASE_SECS)).naive_utc()
+}
+
+/// Rows whose status is one of `from`, or PROCESSING rows whose sender has
+/// been silent for longer than the lease (e.g. it died with the process)
+pub fn claimable(from: &[&str]) -> Condition {
+    let status = || Expr::col(Alias::new("status"));
+    let claimed_at = || Expr::col(Alias::new("claimed_at"));
+
+    Condition::any()
+        .add(status().is_in(from.iter().copied()))
+        .add(
+            Condition::all()
+                .add(status().eq(status::PROCESSING))
+                .add(
+                    Condition::any()
+                        .add(claimed_at().is_null())
+                        .add(claimed_at().lt(lease_cutoff())),
+                ),
+        )
+}
+
+/// Rows the retry worker should pick up: the `claimable` FAILED ones, plus
+/// RECEIVED rows last queued (the first non-null of `received_at`) before the
+/// grace period. Senders claim them from `[RECEIVED, FAILED]`.
+pub fn retryable(received_at: &[&str]) -> Condition {
+    let received_at = Func::coalesce(received_at.iter().map(|col| Expr::col(Alias::new(*col)).into()));
+    let stale = (Utc::now() - ChronoDuration::seconds(RECEIVED_GRACE_SECS)).naive_utc();
+
+    Condition::any().add(claimable(&[status::FAILED])).add(
+        Condition::all()
+            .add(Expr::col(Alias::new("status")).eq(status::RECEIVED))
+            .add(Expr::expr(received_at).lt(stale)),
+    )
+}
+
+/// The row is still PROCESSING under the claim taken at `claimed_at`, i.e.
+/// nothing re-queued it (a stock movement resetting it to RECEIVED) and no
+/// other sender took it over while this one was waiting for the VSCU
+pub fn still_claimed(claimed_at: Option<NaiveDateTime>) -> Condition {
+    let claimed = Expr::col(Alias::new("claimed_at"));
+
+    Condition::all()
+        .add(Expr::col(Alias::new("status")).eq(status::PROCESSING))
+        .add(match claimed_at {
+            Some(at) => claimed.eq(at),
+            None => claimed.is_null(),
+        })
+}
+
+/// Atomically mark row `id` PROCESSING for this sender, if it is still
+/// `claimable(from)`. Returns the claimed row, or None when another sender
+/// holds it or it was already dealt with.
+pub async fn claim<E, C>(db: &C, id: impl Into<Value>, from: &[&str]) -> Result<Option<E::Model>, DbErr>
+where
+    E: EntityTrait,
+    C: ConnectionTrait,
+{
+    let claimed = E::update_many()
+        .col_expr(Alias::new("status"), Expr::value(status::PROCESSING))
+        .col_expr(Alias::new("claimed_at"), Expr::value(Utc::now().naive_utc()))
+        .filter(Expr::col(Alias::new("id")).eq(id.into()))
+        .filter(claimable(from))
+        .exec_with_returning(db)
+        .await?;
+
+    Ok(claimed.into_iter().next())
+}
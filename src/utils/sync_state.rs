@@ -0,0 +1,59 @@
+use chrono::{Local, Utc};
+use sea_orm::{
+    ActiveValue::Set, ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter,
+    sea_query::OnConflict,
+};
+
+use crate::models::sync_state::{ActiveModel, Column, Entity};
+
+/// lastReqDt used for a device's very first sync, i.e. "everything"
+pub const INITIAL_REQ_DT: &str = "20180520000000";
+
+/// lastReqDt to send for `resource` on this device
+pub async fn last_req_dt(
+    db: &DatabaseConnection,
+    credentials_id: i32,
+    resource: &str,
+) -> Result<String, sea_orm::DbErr> {
+    let row = Entity::find()
+        .filter(Column::CredentialsId.eq(credentials_id))
+        .filter(Column::Resource.eq(resource))
+        .one(db)
+        .await?;
+
+    Ok(row
+        .map(|r| r.last_req_dt)
+        .unwrap_or_else(|| INITIAL_REQ_DT.to_string()))
+}
+
+/// Current time in the VSCU's yyyyMMddHHmmss format
+pub fn now_req_dt() -> String {
+    Local::now().format("%Y%m%d%H%M%S").to_string()
+}
+
+/// Remember `req_dt` as the point the next sync of `resource` starts from
+pub async fn mark_synced(
+    db: &DatabaseConnection,
+    credentials_id: i32,
+    resource: &str,
+    req_dt: &str,
+) -> Result<(), sea_orm::DbErr> {
+    let model = ActiveModel {
+        credentials_id: Set(credentials_id),
+        resource: Set(resource.to_string()),
+        last_req_dt: Set(req_dt.to_string()),
+        updated_at: Set(Utc::now().naive_utc()),
+        ..Default::default()
+    };
+
+    Entity::insert(model)
+        .on_conflict(
+            OnConflict::columns([Column::CredentialsId, Column::Resource])
+                .update_columns([Column::LastReqDt, Column::UpdatedAt])
+                .to_owned(),
+        )
+        .exec(db)
+        .await?;
+
+    Ok(())
+}
@@ -0,0 +1,227 @@
+use std::{fmt, sync::OnceLock, time::Duration};
+
+use reqwest::StatusCode;
+use serde::{Serialize, de::DeserializeOwned};
+use serde_json::Value;
+use tracing::info;
+
+use crate::{
+    types::{
+        braches_data_payload::{BhfListData, BhfSaveRes},
+        codes::CodeListData,
+        customers::CustomerListData,
+        imports::ImportItemListData,
+        info::VerificationInfo,
+        initializeTypes::{InitInfoReq, InitInfoRes},
+        notices::NoticeListData,
+        product_management_payload_types::{ItemClassListData, ItemListData},
+        purchases::PurchaseSalesListData,
+        salespayloadtype::{AuthUser, TrnsSalesSaveWrRes},
+    },
+    vscu::result::VscuResponse,
+};
+
+/// Shared HTTP client so every VSCU call reuses the same connection pool
+static HTTP: OnceLock<reqwest::Client> = OnceLock::new();
+
+fn http() -> &'static reqwest::Client {
+    HTTP.get_or_init(|| {
+        reqwest::Client::builder()
+            .timeout(Duration::from_secs(30))
+            .connect_timeout(Duration::from_secs(10))
+            .build()
+            .expect("failed to build VSCU HTTP client")
+    })
+}
+
+// ========== Error Type ==========
+
+#[derive(Debug)]
+pub enum VscuError {
+    /// Request never got an HTTP answer (connection refused, timeout, ...)
+    Transport(reqwest::Error),
+    /// VSCU answered with a non-2xx status
+    Status(StatusCode),
+    /// Body could not be decoded into the expected type
+    Decode(String),
+}
+
+impl fmt::Display for VscuError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            VscuError::Transport(e) => write!(f, "VSCU request failed: {}", e),
+            VscuError::Status(s) => write!(f, "VSCU returned error status: {}", s),
+            VscuError::Decode(e) => write!(f, "Failed to parse VSCU response: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for VscuError {}
+
+// ========== Client ==========
+
+/// Client for a single device's VSCU, addressed by the `environment_url`
+/// stored on its `credentials` row at `/initialize`.
+#[derive(Debug, Clone)]
+pub struct VscuClient {
+    base_url: String,
+}
+
+impl VscuClient {
+    pub fn new(base_url: &str) -> Self {
+        Self {
+            base_url: base_url.trim_end_matches('/').to_string(),
+        }
+    }
+
+    pub fn base_url(&self) -> &str {
+        &self.base_url
+    }
+
+    /// Client for the device behind an already resolved bearer token
+    pub fn for_user(user: &AuthUser) -> Self {
+        Self::new(&user.environment_url)
+    }
+
+    /// POST `body` to `{base_url}/{path}` and decode the JSON answer
+    async fn post<B, R>(&self, path: &str, body: &B) -> Result<R, VscuError>
+    where
+        B: Serialize + ?Sized,
+        R: DeserializeOwned,
+    {
+        let url = format!("{}/{}", self.base_url, path);
+        info!("POST {}", url);
+
+        let response = http()
+            .post(&url)
+            .json(body)
+            .send()
+            .await
+            .map_err(VscuError::Transport)?;
+
+        if !response.status().is_success() {
+            return Err(VscuError::Status(response.status()));
+        }
+
+        response
+            .json::<R>()
+            .await
+            .map_err(|e| VscuError::Decode(e.to_string()))
+    }
+
+    // ========== Endpoints ==========
+
+    /// `initializer/selectInitInfo`
+    pub async fn select_init_info(&self, body: &InitInfoReq) -> Result<InitInfoRes, VscuError> {
+        self.post("initializer/selectInitInfo", body).await
+    }
+
+    /// `code/selectCodes`
+    pub async fn select_codes(
+        &self,
+        body: &VerificationInfo,
+    ) -> Result<VscuResponse<CodeListData>, VscuError> {
+        self.post("code/selectCodes", body).await
+    }
+
+    /// `itemClass/selectItemsClass`
+    pub async fn select_item_classes(
+        &self,
+        body: &VerificationInfo,
+    ) -> Result<VscuResponse<ItemClassListData>, VscuError> {
+        self.post("itemClass/selectItemsClass", body).await
+    }
+
+    /// `items/selectItems`
+    pub async fn select_items(
+        &self,
+        body: &VerificationInfo,
+    ) -> Result<VscuResponse<ItemListData>, VscuError> {
+        self.post("items/selectItems", body).await
+    }
+
+    /// `stockMaster/saveStockMaster`
+    pub async fn save_stock_master<B: Serialize + ?Sized>(
+        &self,
+        body: &B,
+    ) -> Result<VscuResponse<Value>, VscuError> {
+        self.post("stockMaster/saveStockMaster", body).await
+    }
+
+    /// `stock/saveStockItems`
+    pub async fn save_stock_items<B: Serialize + ?Sized>(
+        &self,
+        body: &B,
+    ) -> Result<VscuResponse<Value>, VscuError> {
+        self.post("stock/saveStockItems", body).await
+    }
+
+    /// One of the `branches/saveBranche*` endpoints, which all answer with a
+    /// bare result code
+    pub async fn save_branch_record<B: Serialize + ?Sized>(
+        &self,
+        endpoint: &str,
+        body: &B,
+    ) -> Result<BhfSaveRes, VscuError> {
+        self.post(endpoint, body).await
+    }
+
+    /// `branches/selectBranches`
+    pub async fn select_branches(&self, req: &VerificationInfo) -> Result<VscuResponse<BhfListData>, VscuError> {
+        self.post("branches/selectBranches", req).await
+    }
+
+    /// `trnsPurchase/selectTrnsPurchaseSales`
+    pub async fn select_purchase_sales(
+        &self,
+        req: &VerificationInfo,
+    ) -> Result<VscuResponse<PurchaseSalesListData>, VscuError> {
+        self.post("trnsPurchase/selectTrnsPurchaseSales", req).await
+    }
+
+    /// `trnsPurchase/savePurchases`
+    pub async fn save_purchases<B: Serialize + ?Sized>(&self, body: &B) -> Result<VscuResponse<Value>, VscuError> {
+        self.post("trnsPurchase/savePurchases", body).await
+    }
+
+    /// `imports/selectImportItems`
+    pub async fn select_import_items(
+        &self,
+        req: &VerificationInfo,
+    ) -> Result<VscuResponse<ImportItemListData>, VscuError> {
+        self.post("imports/selectImportItems", req).await
+    }
+
+    /// `imports/updateImportItems`
+    pub async fn update_import_items<B: Serialize + ?Sized>(&self, body: &B) -> Result<VscuResponse<Value>, VscuError> {
+        self.post("imports/updateImportItems", body).await
+    }
+
+    /// `customers/selectCustomer`
+    pub async fn select_customer<B: Serialize + ?Sized>(
+        &self,
+        body: &B,
+    ) -> Result<VscuResponse<CustomerListData>, VscuError> {
+        self.post("customers/selectCustomer", body).await
+    }
+
+    /// `notices/selectNotices`
+    pub async fn select_notices(&self, req: &VerificationInfo) -> Result<VscuResponse<NoticeListData>, VscuError> {
+        self.post("notices/selectNotices", req).await
+    }
+
+    /// `items/saveItemComposition`
+    pub async fn save_item_composition<B: Serialize + ?Sized>(&self, body: &B) -> Result<VscuResponse<Value>, VscuError> {
+        self.post("items/saveItemComposition", body).await
+    }
+
+    /// `trnsSales/saveSales`
+    pub async fn save_sales<B: Serialize + ?Sized>(&self, body: &B) -> Result<TrnsSalesSaveWrRes, VscuError> {
+        self.post("trnsSales/saveSales", body).await
+    }
+
+    /// `items/saveItems`
+    pub async fn save_items<B: Serialize + ?Sized>(&self, body: &B) -> Result<Value, VscuError> {
+        self.post("items/saveItems", body).await
+    }
+}
@@ -0,0 +1,116 @@
+use sea_orm::{ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter};
+
+use crate::{
+    models::initialization::{Column, Entity as Credentials, Model as CredentialsModel},
+    types::{info::VerificationInfo, salespayloadtype::AuthUser},
+    utils::crypto::{decrypt, decrypt_deterministic},
+    vscu::client::VscuClient,
+};
+
+/// A registered device with its identity decrypted, ready to talk to its VSCU
+#[derive(Debug, Clone)]
+pub struct Device {
+    pub credentials_id: i32,
+    pub tin: String,
+    pub bhf_id: String,
+    pub client: VscuClient,
+}
+
+impl Device {
+    pub fn from_credentials(credentials: &CredentialsModel) -> Result<Self, String> {
+        // pin is stored deterministic (unique index), branch_id is not
+        let tin = decrypt_deterministic(&credentials.pin)
+            .map_err(|e| format!("Decrypt PIN error: {}", e))?;
+        let bhf_id = decrypt(&credentials.branch_id)
+            .map_err(|e| format!("Decrypt branch_id error: {}", e))?;
+
+        Ok(Self {
+            credentials_id: credentials.id,
+            tin,
+            bhf_id,
+            client: VscuClient::new(&credentials.environment_url),
+        })
+    }
+
+    /// Device behind an already resolved bearer token
+    pub fn from_user(user: &AuthUser) -> Result<Self, String> {
+        let tin = decrypt_deterministic(&user.pin)
+            .map_err(|e| format!("Decrypt PIN error: {}", e))?;
+        let bhf_id = decrypt(&user.branch_id)
+            .map_err(|e| format!("Decrypt branch_id error: {}", e))?;
+
+        Ok(Self {
+            credentials_id: user.id,
+            tin,
+            bhf_id,
+            client: VscuClient::for_user(user),
+        })
+    }
+
+    /// Device whose stored (encrypted) pin and branch_id match a record's
+    /// `tin`/`bhf_id`, for records that do not carry an api_key
+    pub async fn find_by_identity(
+        db: &DatabaseConnection,
+        pin: &str,
+        branch_id: &str,
+    ) -> Result<Self, String> {
+        let row = Credentials::find()
+            .filter(Column::Pin.eq(pin))
+            .filter(Column::BranchId.eq(branch_id))
+            .one(db)
+            .await
+            .map_err(|e| format!("Database error: {}", e))?
+            .ok_or_else(|| "No device registered for this record".to_string())?;
+
+        Self::from_credentials(&row)
+    }
+
+    /// Device by its `credentials` id
+    pub async fn find(db: &DatabaseConnection, credentials_id: i32) -> Result<Self, String> {
+        let row = Credentials::find_by_id(credentials_id)
+            .one(db)
+            .await
+            .map_err(|e| format!("Database error: {}", e))?
+            .ok_or_else(|| format!("Device {} not found", credentials_id))?;
+
+        Self::from_credentials(&row)
+    }
+
+    /// Device that owns `api_key`, for records stored through a bearer token
+    pub async fn find_by_api_key(db: &DatabaseConnection, api_key: &str) -> Result<Self, String> {
+        let row = Credentials::find()
+            .filter(Column::ApiKey.eq(api_key))
+            .one(db)
+            .await
+            .map_err(|e| format!("Database error: {}", e))?
+            .ok_or_else(|| "No device registered for this api key".to_string())?;
+
+        Self::from_credentials(&row)
+    }
+
+    /// Every device in `credentials`; devices whose identity cannot be
+    /// decrypted are logged and skipped
+    pub async fn all(db: &DatabaseConnection) -> Result<Vec<Self>, sea_orm::DbErr> {
+        let rows = Credentials::find().all(db).await?;
+
+        Ok(rows
+            .iter()
+            .filter_map(|row| match Self::from_credentials(row) {
+                Ok(device) => Some(device),
+                Err(e) => {
+                    tracing::error!("Skipping device {}: {}", row.id, e);
+                    None
+                }
+            })
+            .collect())
+    }
+
+    /// Body for the VSCU select endpoints (`tin`, `bhfId`, `lastReqDt`)
+    pub fn select_request(&self, last_req_dt: &str) -> VerificationInfo {
+        VerificationInfo {
+            tin: self.tin.clone(),
+            bhfId: self.bhf_id.clone(),
+            lastReqDt: last_req_dt.to_string(),
+        }
+    }
+}
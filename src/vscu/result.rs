@@ -0,0 +1,74 @@
+use serde::{Deserialize, Serialize};
+
+/// Envelope every VSCU endpoint answers with
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VscuResponse<T> {
+    pub result_cd: String,
+    pub result_msg: String,
+    pub result_dt: Option<String>,
+    pub data: Option<T>,
+}
+
+impl<T> VscuResponse<T> {
+    pub fn class(&self) -> ResultClass {
+        classify(&self.result_cd)
+    }
+
+    /// `001` - a select endpoint had nothing new since `lastReqDt`
+    pub fn is_empty_search(&self) -> bool {
+        self.result_cd == "001"
+    }
+}
+
+/// How a VSCU `resultCd` should be handled by the caller
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ResultClass {
+    /// `000` - the VSCU accepted the request
+    Success,
+    /// KRA refused the data itself; resending the same payload will not help
+    Rejected,
+    /// Communication/VSCU side problem; the same payload may go through later
+    Retryable,
+}
+
+/// Classify a VSCU result code.
+///
+/// 89x codes are raised by the VSCU while talking to KRA, 999 is KRA's
+/// "unknown error" and 922 means the invoice has to wait for its sales data,
+/// so all of those are worth another attempt. Everything else in the 9xx
+/// range is a validation or registration error on the payload.
+pub fn classify(result_cd: &str) -> ResultClass {
+    match result_cd {
+        "000" => ResultClass::Success,
+        "922" | "999" => ResultClass::Retryable,
+        cd if cd.starts_with("89") => ResultClass::Retryable,
+        cd if cd.starts_with('9') => ResultClass::Rejected,
+        // Anything we do not recognise is treated as transient so it is
+        // not silently dropped
+        _ => ResultClass::Retryable,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn classifies_result_codes() {
+        let cases = [
+            ("000", ResultClass::Success),
+            // Empty search; only the select endpoints treat it as an answer
+            ("001", ResultClass::Retryable),
+            ("894", ResultClass::Retryable),
+            ("922", ResultClass::Retryable),
+            ("999", ResultClass::Retryable),
+            ("910", ResultClass::Rejected),
+            ("123", ResultClass::Retryable),
+        ];
+
+        for (result_cd, expected) in cases {
+            assert_eq!(classify(result_cd), expected, "resultCd {}", result_cd);
+        }
+    }
+}